@@ -1,6 +1,6 @@
 //! Byte array-based Base64 encoding and decoding functions
 
-use base64::{Engine as _, engine::general_purpose};
+use super::encoding::{decode_base64, encode_base64};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
@@ -33,7 +33,7 @@ pub unsafe extern "C" fn bytes_to_base64(bytes: *const u8, length: usize) -> *mu
     }
 
     let byte_slice = unsafe { std::slice::from_raw_parts(bytes, length) };
-    let encoded = general_purpose::STANDARD.encode(byte_slice);
+    let encoded = encode_base64(byte_slice);
 
     match CString::new(encoded) {
         Ok(c_str) => {
@@ -90,10 +90,10 @@ pub unsafe extern "C" fn base64_to_bytes(input: *const c_char, out_length: *mut
         return crate::memory::allocate_byte_array(Vec::<u8>::new());
     }
 
-    let decoded_bytes = match general_purpose::STANDARD.decode(input_str) {
+    let decoded_bytes = match decode_base64(input_str) {
         Ok(bytes) => bytes,
         Err(e) => {
-            crate::error::set_error(format!("Failed to decode Base64: {}", e));
+            crate::error::set_error(e);
             if !out_length.is_null() {
                 unsafe {
                     *out_length = 0;
@@ -1,5 +1,19 @@
 //! Encoding conversion helper functions
 
+use base64::{Engine as _, engine::general_purpose};
+
+/// Encode bytes as standard padded Base64
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    general_purpose::STANDARD.encode(bytes)
+}
+
+/// Decode standard padded Base64
+pub(crate) fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    general_purpose::STANDARD
+        .decode(input)
+        .map_err(|e| format!("Failed to decode Base64: {}", e))
+}
+
 /// Convert a Rust string to bytes using the specified encoding
 pub(crate) fn convert_string_to_bytes(input: &str, encoding: &str) -> Result<Vec<u8>, String> {
     // Use eq_ignore_ascii_case to avoid allocating with to_uppercase()
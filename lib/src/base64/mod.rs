@@ -11,4 +11,5 @@ pub use string_ops::{base64_to_string, base64_to_string_lenient, string_to_base6
 // Re-export encoding helpers for use by other modules
 pub(crate) use encoding::{
     convert_bytes_to_string, convert_bytes_to_string_with_fallback, convert_string_to_bytes,
+    decode_base64, encode_base64,
 };
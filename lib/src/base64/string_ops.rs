@@ -2,8 +2,8 @@
 
 use super::encoding::{
     convert_bytes_to_string, convert_bytes_to_string_with_fallback, convert_string_to_bytes,
+    decode_base64, encode_base64,
 };
-use base64::{Engine as _, engine::general_purpose};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
@@ -62,7 +62,7 @@ pub unsafe extern "C" fn string_to_base64(
         }
     };
 
-    let encoded = general_purpose::STANDARD.encode(&bytes);
+    let encoded = encode_base64(&bytes);
 
     match CString::new(encoded) {
         Ok(c_str) => {
@@ -115,10 +115,10 @@ pub unsafe extern "C" fn base64_to_string(
         }
     };
 
-    let decoded_bytes = match general_purpose::STANDARD.decode(input_str) {
+    let decoded_bytes = match decode_base64(input_str) {
         Ok(bytes) => bytes,
         Err(e) => {
-            crate::error::set_error(format!("Failed to decode Base64: {}", e));
+            crate::error::set_error(e);
             return std::ptr::null_mut();
         }
     };
@@ -181,10 +181,10 @@ pub unsafe extern "C" fn base64_to_string_lenient(
         }
     };
 
-    let decoded_bytes = match general_purpose::STANDARD.decode(input_str) {
+    let decoded_bytes = match decode_base64(input_str) {
         Ok(bytes) => bytes,
         Err(e) => {
-            crate::error::set_error(format!("Failed to decode Base64: {}", e));
+            crate::error::set_error(e);
             return std::ptr::null_mut();
         }
     };
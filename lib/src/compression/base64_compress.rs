@@ -0,0 +1,129 @@
+//! Compress and Base64 encode functions
+
+use base64::Engine as _;
+use std::os::raw::c_char;
+
+use super::compress::{compress_input, read_compression_args};
+use crate::helpers::finish_string;
+
+/// Convert a string to bytes, compress it, and Base64 encode the result in one operation
+///
+/// The inverse of `base64_to_decompressed_string_with_format`. `format`,
+/// `level` and `strategy` accept the same values as
+/// `compress_string_with_options` and may be null to use the defaults (Gzip).
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `format`, `level` and `strategy` are valid null-terminated C strings or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn string_to_compressed_base64(
+    input: *const c_char,
+    encoding: *const c_char,
+    format: *const c_char,
+    level: *const c_char,
+    strategy: *const c_char,
+) -> *mut c_char {
+    let result = read_compression_args(format, level, strategy)
+        .and_then(|(format, options)| compress_input(input, encoding, format, options))
+        .map(|compressed| base64::engine::general_purpose::STANDARD.encode(compressed));
+
+    finish_string(result, "Base64 result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::{
+        base64_to_decompressed_string, base64_to_decompressed_string_with_format,
+    };
+    use std::ffi::{CStr, CString};
+
+    #[test]
+    fn test_string_to_compressed_base64_round_trip() {
+        let input = CString::new("Hello, compressed world!").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let level = CString::new("BEST").unwrap();
+
+        let encoded = unsafe {
+            string_to_compressed_base64(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                std::ptr::null(),
+                level.as_ptr(),
+                std::ptr::null(),
+            )
+        };
+        assert!(!encoded.is_null());
+
+        let decoded = unsafe { base64_to_decompressed_string(encoded, encoding.as_ptr()) };
+        assert!(!decoded.is_null());
+        let text = unsafe { CStr::from_ptr(decoded).to_str().unwrap().to_string() };
+        assert_eq!(text, "Hello, compressed world!");
+
+        unsafe {
+            crate::memory::free_string(encoded);
+            crate::memory::free_string(decoded);
+        }
+    }
+
+    #[test]
+    fn test_string_to_compressed_base64_invalid_strategy() {
+        let input = CString::new("x").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let strategy = CString::new("zstd").unwrap();
+
+        let result = unsafe {
+            string_to_compressed_base64(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                strategy.as_ptr(),
+            )
+        };
+
+        assert!(result.is_null());
+    }
+
+    #[test]
+    fn test_string_to_compressed_base64_zlib_round_trip() {
+        let input = CString::new("zlib through Base64").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let format = CString::new("ZLIB").unwrap();
+
+        let encoded = unsafe {
+            string_to_compressed_base64(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                format.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        assert!(!encoded.is_null());
+        assert!(unsafe { base64_to_decompressed_string(encoded, encoding.as_ptr()) }.is_null());
+
+        let decoded = unsafe {
+            base64_to_decompressed_string_with_format(
+                encoded,
+                encoding.as_ptr(),
+                format.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        };
+        assert!(!decoded.is_null());
+        let text = unsafe { CStr::from_ptr(decoded).to_str().unwrap().to_string() };
+        assert_eq!(text, "zlib through Base64");
+
+        unsafe {
+            crate::memory::free_string(encoded);
+            crate::memory::free_string(decoded);
+        }
+    }
+}
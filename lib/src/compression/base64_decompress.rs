@@ -1,16 +1,22 @@
 //! Base64 decode and decompress functions
 
 use base64::Engine as _;
-use flate2::read::GzDecoder;
+use flate2::read::MultiGzDecoder;
 use std::ffi::{CStr, CString};
-use std::io::Read;
 use std::os::raw::c_char;
 
+use super::decompress::decompress_to_string;
+use super::detect::DecompressionFormat;
+use super::limits::DecompressionLimits;
+use crate::helpers::{finish_string, read_optional_str};
+
 /// Decode a Base64 string, decompress it, and convert to a string in one operation
 ///
 /// This function combines Base64 decoding, Gzip decompression, and string conversion
 /// into a single FFI call, reducing the overhead of multiple round-trips between
-/// PowerShell and Rust.
+/// PowerShell and Rust. Output is capped at the fixed default limit of 256 MiB;
+/// to set other limits, call `base64_to_decompressed_string_with_format` with a
+/// null `format`, which also selects Gzip.
 ///
 /// # Safety
 /// This function is unsafe because it dereferences raw pointers.
@@ -57,13 +63,15 @@ pub unsafe extern "C" fn base64_to_decompressed_string(
         }
     };
 
-    let mut decoder = GzDecoder::new(compressed_bytes.as_slice());
-    let mut decompressed = Vec::new();
-
-    if let Err(e) = decoder.read_to_end(&mut decompressed) {
-        crate::error::set_error(format!("Decompression failed: {}", e));
-        return std::ptr::null_mut();
-    }
+    let decoder = MultiGzDecoder::new(compressed_bytes.as_slice());
+    let decompressed =
+        match DecompressionLimits::default().read_to_end(decoder, compressed_bytes.len()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                crate::error::set_error(e);
+                return std::ptr::null_mut();
+            }
+        };
 
     let result_string = match crate::base64::convert_bytes_to_string(&decompressed, encoding_str) {
         Ok(s) => s,
@@ -91,7 +99,9 @@ pub unsafe extern "C" fn base64_to_decompressed_string(
 ///
 /// This is a lenient version of `base64_to_decompressed_string` that automatically
 /// falls back to Latin-1 (ISO-8859-1) encoding when the decompressed bytes are invalid
-/// for the specified encoding.
+/// for the specified encoding. Output is capped at the same fixed 256 MiB; call
+/// `base64_to_decompressed_string_with_format` with `lenient` set to choose
+/// other limits.
 ///
 /// # Safety
 /// Same safety requirements as `base64_to_decompressed_string`.
@@ -134,13 +144,15 @@ pub unsafe extern "C" fn base64_to_decompressed_string_lenient(
         }
     };
 
-    let mut decoder = GzDecoder::new(compressed_bytes.as_slice());
-    let mut decompressed = Vec::new();
-
-    if let Err(e) = decoder.read_to_end(&mut decompressed) {
-        crate::error::set_error(format!("Decompression failed: {}", e));
-        return std::ptr::null_mut();
-    }
+    let decoder = MultiGzDecoder::new(compressed_bytes.as_slice());
+    let decompressed =
+        match DecompressionLimits::default().read_to_end(decoder, compressed_bytes.len()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                crate::error::set_error(e);
+                return std::ptr::null_mut();
+            }
+        };
 
     let result_string =
         match crate::base64::convert_bytes_to_string_with_fallback(&decompressed, encoding_str) {
@@ -164,3 +176,41 @@ pub unsafe extern "C" fn base64_to_decompressed_string_lenient(
         }
     }
 }
+
+/// Decode a Base64 string, decompress it from the given format, and convert to a string
+///
+/// Accepts the same `format` values as `decompress_string_with_format`,
+/// including `AUTO` (null selects Gzip), and `lenient` enables the Latin-1 fallback of
+/// `base64_to_decompressed_string_lenient`. `max_output_size` and `max_ratio`
+/// bound the decompressed output as in `decompress_string_with_format`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `format` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn base64_to_decompressed_string_with_format(
+    input: *const c_char,
+    encoding: *const c_char,
+    format: *const c_char,
+    lenient: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    let result = read_optional_str(input, "Input").and_then(|input| {
+        let input = input.ok_or_else(|| "Input pointer is null".to_string())?;
+        let encoding = read_optional_str(encoding, "Encoding")?
+            .ok_or_else(|| "Encoding pointer is null".to_string())?;
+        let format = DecompressionFormat::parse(read_optional_str(format, "Format")?)?;
+        let compressed = base64::engine::general_purpose::STANDARD
+            .decode(input)
+            .map_err(|e| format!("Failed to decode Base64: {}", e))?;
+        decompress_to_string(&compressed, encoding, format, lenient, limits)
+    });
+
+    finish_string(result, "decompressed result")
+}
@@ -0,0 +1,113 @@
+//! Brotli compression and decompression
+
+use std::io::Write;
+
+use super::limits::DecompressionLimits;
+use super::options::LevelSpec;
+
+/// Brotli quality 0-11; 11 is the reference encoder's default used for static assets.
+pub(crate) const BROTLI_LEVELS: LevelSpec = LevelSpec {
+    min: 0,
+    max: 11,
+    fastest: 0,
+    default: 11,
+    best: 11,
+};
+
+/// Default sliding window (log2 of its size in bytes).
+pub(crate) const DEFAULT_WINDOW_BITS: u32 = 22;
+
+const MIN_WINDOW_BITS: u32 = 10;
+const MAX_WINDOW_BITS: u32 = 24;
+
+const BUFFER_SIZE: usize = 4096;
+
+/// Validates a Brotli window size; 0 selects the default.
+pub(crate) fn window_bits(requested: u32) -> Result<u32, String> {
+    match requested {
+        0 => Ok(DEFAULT_WINDOW_BITS),
+        MIN_WINDOW_BITS..=MAX_WINDOW_BITS => Ok(requested),
+        _ => Err(format!(
+            "Brotli window must be between {} and {} bits, got {}",
+            MIN_WINDOW_BITS, MAX_WINDOW_BITS, requested
+        )),
+    }
+}
+
+/// Compresses `data` into a Brotli stream.
+pub(crate) fn brotli_compress(
+    data: &[u8],
+    quality: i32,
+    window_bits: u32,
+) -> Result<Vec<u8>, String> {
+    let quality = u32::try_from(quality).map_err(|_| "Invalid Brotli quality".to_string())?;
+    let mut writer = brotli::CompressorWriter::new(Vec::new(), BUFFER_SIZE, quality, window_bits);
+    writer
+        .write_all(data)
+        .map_err(|e| format!("Brotli compression failed: {}", e))?;
+    writer
+        .flush()
+        .map_err(|e| format!("Brotli compression failed: {}", e))?;
+    Ok(writer.into_inner())
+}
+
+/// Decompresses a Brotli stream.
+pub(crate) fn brotli_decompress(
+    data: &[u8],
+    limits: DecompressionLimits,
+) -> Result<Vec<u8>, String> {
+    limits.read_to_end(brotli::Decompressor::new(data, BUFFER_SIZE), data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brotli_round_trip_qualities() {
+        let input = b"body { margin: 0; padding: 0; } ".repeat(100);
+        for quality in [0, 5, 11] {
+            let compressed = brotli_compress(&input, quality, DEFAULT_WINDOW_BITS).unwrap();
+            assert!(compressed.len() < input.len() / 10);
+            assert_eq!(
+                brotli_decompress(&compressed, DecompressionLimits::default()).unwrap(),
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_brotli_decompress_known_stream() {
+        // `brotli -c` output for "hello"
+        let stream = [0x0b, 0x02, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x03];
+        assert_eq!(
+            brotli_decompress(&stream, DecompressionLimits::default()).unwrap(),
+            b"hello"
+        );
+    }
+
+    #[test]
+    fn test_brotli_empty_input() {
+        let compressed = brotli_compress(&[], 11, DEFAULT_WINDOW_BITS).unwrap();
+        assert!(
+            brotli_decompress(&compressed, DecompressionLimits::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_brotli_rejects_garbage() {
+        assert!(
+            brotli_decompress(b"definitely not brotli", DecompressionLimits::default()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_window_bits_validation() {
+        assert_eq!(window_bits(0).unwrap(), DEFAULT_WINDOW_BITS);
+        assert_eq!(window_bits(16).unwrap(), 16);
+        assert!(window_bits(9).is_err());
+        assert!(window_bits(25).is_err());
+    }
+}
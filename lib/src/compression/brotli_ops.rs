@@ -0,0 +1,174 @@
+//! Brotli string compression operations
+
+use std::os::raw::c_char;
+
+use super::brotli_codec::{BROTLI_LEVELS, brotli_compress, brotli_decompress, window_bits};
+use super::codec::Format;
+use super::compress::encode_input;
+use super::decompress::decoded_to_string;
+use super::limits::DecompressionLimits;
+use super::options::parse_level;
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bytes, finish_string, read_optional_str};
+
+/// Compress a string using Brotli
+///
+/// `quality` is `0`-`11` or one of the presets `FASTEST` (0), `DEFAULT` (11)
+/// and `BEST` (11), and may be null for 11. `window_bits` is the log2 window
+/// size from 10 to 24, or 0 for the default of 22; smaller windows reduce the
+/// memory needed to decompress.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `quality` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn brotli_compress_string(
+    input: *const c_char,
+    encoding: *const c_char,
+    quality: *const c_char,
+    window_bits_log2: u32,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = read_optional_str(quality, "Quality").and_then(|quality| {
+        let quality = match quality {
+            Some(quality) => parse_level(quality, Format::Brotli)?,
+            None => BROTLI_LEVELS.default,
+        };
+        let window = window_bits(window_bits_log2)?;
+        let bytes = encode_input(input, encoding)?;
+        brotli_compress(&bytes, quality, window)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Decompress a Brotli byte array to a string
+///
+/// With `lenient` set, bytes that are invalid for the encoding fall back to
+/// Latin-1 as in `decompress_string_lenient`. For Base64 input such as a
+/// captured `Content-Encoding: br` body, use
+/// `base64_to_decompressed_string_with_format` with the `BROTLI` format.
+/// `max_output_size` and `max_ratio` bound the output as in
+/// `decompress_string_with_format`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `encoding` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn brotli_decompress_string(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    lenient: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    let result = read_optional_str(encoding, "Encoding").and_then(|encoding| {
+        let encoding = encoding.ok_or_else(|| "Encoding pointer is null".to_string())?;
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        let decompressed = brotli_decompress(data, limits)?;
+        decoded_to_string(&decompressed, encoding, lenient)
+    });
+
+    finish_string(result, "decompressed result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::base64_to_decompressed_string_with_format;
+    use base64::Engine as _;
+    use std::ffi::{CStr, CString};
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    #[test]
+    fn test_brotli_string_round_trip() {
+        let input = CString::new("<html><body>Brotli</body></html>").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let quality = CString::new("5").unwrap();
+        let mut len = 0usize;
+
+        let ptr = unsafe {
+            brotli_compress_string(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                quality.as_ptr(),
+                16,
+                &mut len,
+            )
+        };
+        assert!(!ptr.is_null());
+        let compressed = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+
+        let decoded = take_string(unsafe {
+            brotli_decompress_string(
+                compressed.as_ptr(),
+                compressed.len(),
+                encoding.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        });
+        assert_eq!(decoded.as_deref(), Some("<html><body>Brotli</body></html>"));
+    }
+
+    #[test]
+    fn test_brotli_compress_string_invalid_window() {
+        let input = CString::new("x").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let mut len = 4usize;
+
+        let ptr = unsafe {
+            brotli_compress_string(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                std::ptr::null(),
+                30,
+                &mut len,
+            )
+        };
+
+        assert!(ptr.is_null());
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn test_base64_brotli_body() {
+        let body = base64::engine::general_purpose::STANDARD
+            .encode([0x0b, 0x02, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x03]);
+        let input = CString::new(body).unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let format = CString::new("br").unwrap();
+
+        let decoded = take_string(unsafe {
+            base64_to_decompressed_string_with_format(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                format.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        });
+        assert_eq!(decoded.as_deref(), Some("hello"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Byte array compression operations for every supported format
+
+use std::os::raw::c_char;
+
+use super::codec::compress_data;
+use super::compress::read_compression_args;
+use super::detect::DecompressionFormat;
+use super::limits::DecompressionLimits;
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bytes, read_optional_str};
+
+/// Compress a byte array in any supported format
+///
+/// Unlike `compress_string_with_options` the input is taken as-is, so binary
+/// data needs no text encoding. `format`, `level` and `strategy` accept the
+/// same values as `compress_string_with_options`, and each may be null to use
+/// the default (GZIP at level 6).
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `format`, `level` and `strategy` are valid null-terminated C strings or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compress_bytes(
+    bytes: *const u8,
+    length: usize,
+    format: *const c_char,
+    level: *const c_char,
+    strategy: *const c_char,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = read_compression_args(format, level, strategy).and_then(|(format, options)| {
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        compress_data(data, format, options)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Decompress a byte array in any supported format to a byte array
+///
+/// The decompressed bytes are returned unchanged rather than decoded as text.
+/// `format` accepts the same values as `decompress_string_with_format`,
+/// including `AUTO`, and may be null for GZIP. `max_output_size` (0 for the default of 256 MiB) and
+/// `max_ratio` (0 for none) bound the output as in `decompress_string_with_format`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `format` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompress_bytes(
+    bytes: *const u8,
+    length: usize,
+    format: *const c_char,
+    max_output_size: u64,
+    max_ratio: u32,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    let result = read_optional_str(format, "Format").and_then(|format| {
+        let format = DecompressionFormat::parse(format)?;
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        format.decompress(data, limits)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Option<Vec<u8>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        Some(bytes)
+    }
+
+    #[test]
+    fn test_binary_round_trip_every_format() {
+        // Every byte value, including NUL and invalid UTF-8 sequences
+        let input: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let formats = [
+            "GZIP",
+            "ZLIB",
+            "DEFLATE",
+            "ZSTD",
+            "BROTLI",
+            "LZ4",
+            "LZ4_BLOCK",
+            "SNAPPY",
+            "SNAPPY_RAW",
+            "BZIP2",
+            "XZ",
+            "LZMA",
+        ];
+
+        for name in formats {
+            let format = CString::new(name).unwrap();
+            let mut len = 0usize;
+            let compressed = take_bytes(
+                unsafe {
+                    compress_bytes(
+                        input.as_ptr(),
+                        input.len(),
+                        format.as_ptr(),
+                        std::ptr::null(),
+                        std::ptr::null(),
+                        &mut len,
+                    )
+                },
+                len,
+            )
+            .unwrap_or_else(|| panic!("{} compression failed", name));
+
+            let decompressed = take_bytes(
+                unsafe {
+                    decompress_bytes(
+                        compressed.as_ptr(),
+                        compressed.len(),
+                        format.as_ptr(),
+                        0,
+                        0,
+                        &mut len,
+                    )
+                },
+                len,
+            );
+            assert_eq!(decompressed.as_deref(), Some(&input[..]), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_defaults_match_compress_string() {
+        let input = CString::new("Hello, World!").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let mut string_len = 0usize;
+        let from_string = take_bytes(
+            unsafe {
+                crate::compression::compress_string(
+                    input.as_ptr(),
+                    encoding.as_ptr(),
+                    &mut string_len,
+                )
+            },
+            string_len,
+        )
+        .unwrap();
+
+        let mut len = 0usize;
+        let data = input.as_bytes();
+        let from_bytes = take_bytes(
+            unsafe {
+                compress_bytes(
+                    data.as_ptr(),
+                    data.len(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap();
+        assert_eq!(from_bytes, from_string);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let mut len = 1usize;
+        let compressed = take_bytes(
+            unsafe {
+                compress_bytes(
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap();
+
+        let decompressed = take_bytes(
+            unsafe {
+                decompress_bytes(
+                    compressed.as_ptr(),
+                    compressed.len(),
+                    std::ptr::null(),
+                    0,
+                    0,
+                    &mut len,
+                )
+            },
+            len,
+        );
+        assert_eq!(decompressed, Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_decompress_bytes_errors() {
+        let garbage = [1u8, 2, 3, 4];
+        let mut len = 5usize;
+        let ptr = unsafe {
+            decompress_bytes(
+                garbage.as_ptr(),
+                garbage.len(),
+                std::ptr::null(),
+                0,
+                0,
+                &mut len,
+            )
+        };
+        assert!(ptr.is_null());
+        assert_eq!(len, 0);
+
+        let format = CString::new("ZIP").unwrap();
+        let ptr = unsafe { decompress_bytes(garbage.as_ptr(), 4, format.as_ptr(), 0, 0, &mut len) };
+        assert!(ptr.is_null());
+
+        let ptr =
+            unsafe { decompress_bytes(std::ptr::null(), 4, std::ptr::null(), 0, 0, &mut len) };
+        assert!(ptr.is_null());
+    }
+
+    #[test]
+    fn test_decompress_bytes_output_limit() {
+        let input = vec![0u8; 100_000];
+        let format = CString::new("ZSTD").unwrap();
+        let mut len = 0usize;
+        let compressed = take_bytes(
+            unsafe {
+                compress_bytes(
+                    input.as_ptr(),
+                    input.len(),
+                    format.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap();
+
+        let ptr = unsafe {
+            decompress_bytes(
+                compressed.as_ptr(),
+                compressed.len(),
+                format.as_ptr(),
+                50_000,
+                0,
+                &mut len,
+            )
+        };
+        assert!(ptr.is_null());
+        let error = unsafe { crate::error::get_last_error() };
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { crate::memory::free_string(error) };
+        assert_eq!(
+            message,
+            "Decompression limit exceeded: output is larger than 50000 bytes"
+        );
+
+        let ptr = unsafe {
+            decompress_bytes(
+                compressed.as_ptr(),
+                compressed.len(),
+                format.as_ptr(),
+                100_000,
+                0,
+                &mut len,
+            )
+        };
+        assert_eq!(take_bytes(ptr, len), Some(input));
+    }
+}
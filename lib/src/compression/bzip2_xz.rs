@@ -0,0 +1,64 @@
+//! bzip2, xz and legacy LZMA (`.lzma`) codec settings
+
+use liblzma::stream::{CONCATENATED, LzmaOptions, Stream};
+
+use super::limits::DecompressionLimits;
+use super::options::LevelSpec;
+
+/// bzip2 block size 1-9 (x100 KiB); 9 matches the `bzip2` command-line default.
+pub(crate) const BZIP2_LEVELS: LevelSpec = LevelSpec {
+    min: 1,
+    max: 9,
+    fastest: 1,
+    default: 9,
+    best: 9,
+};
+
+/// xz / LZMA presets 0-9; 6 matches the `xz` command-line default.
+pub(crate) const XZ_LEVELS: LevelSpec = LevelSpec {
+    min: 0,
+    max: 9,
+    fastest: 0,
+    default: 6,
+    best: 9,
+};
+
+/// Lowest memory limit given to the xz and LZMA decoders. Decoding preset 9
+/// output needs about 65 MiB for its 64 MiB dictionary, whatever the data size.
+const MIN_DECODER_MEMORY_LIMIT: u64 = 96 * 1024 * 1024;
+
+/// Memory limit for the xz and LZMA decoders: the permitted output size, since
+/// a dictionary larger than the output is never needed, but at least enough
+/// for every preset. A stream whose header asks for more fails with a memory
+/// limit error instead of allocating it.
+pub(crate) fn decoder_memory_limit(limits: DecompressionLimits) -> u64 {
+    limits.max_output_size.max(MIN_DECODER_MEMORY_LIMIT)
+}
+
+pub(crate) fn bzip2_compression(level: i32) -> bzip2::Compression {
+    bzip2::Compression::new(level.clamp(BZIP2_LEVELS.min, BZIP2_LEVELS.max) as u32)
+}
+
+pub(crate) fn xz_preset(level: i32) -> u32 {
+    level.clamp(XZ_LEVELS.min, XZ_LEVELS.max) as u32
+}
+
+/// Encoder for the legacy `.lzma` ("LZMA alone") container.
+pub(crate) fn lzma_alone_encoder(level: i32) -> Result<Stream, String> {
+    LzmaOptions::new_preset(xz_preset(level))
+        .and_then(|options| Stream::new_lzma_encoder(&options))
+        .map_err(|e| format!("Failed to initialize LZMA encoder: {}", e))
+}
+
+/// Decoder for `.xz` data, including concatenated streams as written by
+/// parallel compressors.
+pub(crate) fn xz_decoder(limits: DecompressionLimits) -> Result<Stream, String> {
+    Stream::new_stream_decoder(decoder_memory_limit(limits), CONCATENATED)
+        .map_err(|e| format!("Failed to initialize xz decoder: {}", e))
+}
+
+/// Decoder for the legacy `.lzma` ("LZMA alone") container.
+pub(crate) fn lzma_alone_decoder(limits: DecompressionLimits) -> Result<Stream, String> {
+    Stream::new_lzma_decoder(decoder_memory_limit(limits))
+        .map_err(|e| format!("Failed to initialize LZMA decoder: {}", e))
+}
@@ -0,0 +1,318 @@
+//! Container format selection and whole-buffer compression
+
+use std::io::Write;
+
+use super::brotli_codec::{BROTLI_LEVELS, DEFAULT_WINDOW_BITS, brotli_compress, brotli_decompress};
+use super::bzip2_xz::{BZIP2_LEVELS, XZ_LEVELS};
+use super::deflate::{deflate_raw, gzip_compress, zlib_compress};
+use super::limits::DecompressionLimits;
+use super::lz4_snappy::{lz4_compress, lz4_decompress, snappy_compress, snappy_decompress};
+use super::options::{CompressionOptions, DEFLATE_LEVELS, LevelSpec};
+use super::stream;
+use super::zstandard::{ZSTD_LEVELS, zstd_compress, zstd_decompress};
+
+/// Supported compressed data formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// RFC 1952 Gzip (`GZipStream`, `.gz`)
+    Gzip,
+    /// RFC 1950 zlib (`ZLibStream`, PDF and PNG streams)
+    Zlib,
+    /// RFC 1951 raw DEFLATE (`DeflateStream`)
+    Deflate,
+    /// RFC 8878 Zstandard
+    Zstd,
+    /// RFC 7932 Brotli (`Content-Encoding: br`)
+    Brotli,
+    /// LZ4 frame format
+    Lz4,
+    /// LZ4 block with a 4-byte little-endian size prefix
+    Lz4Block,
+    /// Snappy framing format
+    Snappy,
+    /// Raw Snappy block
+    SnappyRaw,
+    /// bzip2 (`.bz2`)
+    Bzip2,
+    /// xz container (`.xz`)
+    Xz,
+    /// Legacy LZMA "alone" container (`.lzma`)
+    Lzma,
+}
+
+impl Format {
+    /// Parses a format name; `None` selects Gzip.
+    pub(crate) fn parse(name: Option<&str>) -> Result<Self, String> {
+        let Some(name) = name else {
+            return Ok(Format::Gzip);
+        };
+
+        match name.trim().to_uppercase().as_str() {
+            "GZIP" | "GZ" => Ok(Format::Gzip),
+            "ZLIB" => Ok(Format::Zlib),
+            "DEFLATE" | "RAW" => Ok(Format::Deflate),
+            "ZSTD" | "ZSTANDARD" => Ok(Format::Zstd),
+            "BROTLI" | "BR" => Ok(Format::Brotli),
+            "LZ4" => Ok(Format::Lz4),
+            "LZ4_BLOCK" => Ok(Format::Lz4Block),
+            "SNAPPY" => Ok(Format::Snappy),
+            "SNAPPY_RAW" => Ok(Format::SnappyRaw),
+            "BZIP2" | "BZ2" => Ok(Format::Bzip2),
+            "XZ" => Ok(Format::Xz),
+            "LZMA" => Ok(Format::Lzma),
+            _ => Err(format!(
+                "Unsupported compression format: {}. Supported: GZIP, ZLIB, DEFLATE, ZSTD, BROTLI, LZ4, LZ4_BLOCK, SNAPPY, SNAPPY_RAW, BZIP2, XZ, LZMA",
+                name
+            )),
+        }
+    }
+
+    /// Canonical upper-case name used in messages.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Format::Gzip => "GZIP",
+            Format::Zlib => "ZLIB",
+            Format::Deflate => "DEFLATE",
+            Format::Zstd => "ZSTD",
+            Format::Brotli => "BROTLI",
+            Format::Lz4 => "LZ4",
+            Format::Lz4Block => "LZ4_BLOCK",
+            Format::Snappy => "SNAPPY",
+            Format::SnappyRaw => "SNAPPY_RAW",
+            Format::Bzip2 => "BZIP2",
+            Format::Xz => "XZ",
+            Format::Lzma => "LZMA",
+        }
+    }
+
+    /// Valid compression levels and presets, or `None` when the format has no levels.
+    pub(crate) fn level_spec(self) -> Option<LevelSpec> {
+        match self {
+            Format::Gzip | Format::Zlib | Format::Deflate => Some(DEFLATE_LEVELS),
+            Format::Zstd => Some(ZSTD_LEVELS),
+            Format::Brotli => Some(BROTLI_LEVELS),
+            Format::Bzip2 => Some(BZIP2_LEVELS),
+            Format::Xz | Format::Lzma => Some(XZ_LEVELS),
+            Format::Lz4 | Format::Lz4Block | Format::Snappy | Format::SnappyRaw => None,
+        }
+    }
+
+    /// Whether the DEFLATE strategy option applies to this format.
+    pub(crate) fn supports_strategy(self) -> bool {
+        matches!(self, Format::Gzip | Format::Zlib | Format::Deflate)
+    }
+}
+
+/// Compresses `data` into the requested format.
+pub(crate) fn compress_data(
+    data: &[u8],
+    format: Format,
+    options: CompressionOptions,
+) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Gzip => gzip_compress(data, options),
+        Format::Zlib => zlib_compress(data, options),
+        Format::Deflate => deflate_raw(data, options),
+        Format::Zstd => zstd_compress(data, options.level, None),
+        Format::Brotli => brotli_compress(data, options.level, DEFAULT_WINDOW_BITS),
+        Format::Lz4 => lz4_compress(data, true),
+        Format::Lz4Block => lz4_compress(data, false),
+        Format::Snappy => snappy_compress(data, true),
+        Format::SnappyRaw => snappy_compress(data, false),
+        Format::Bzip2 | Format::Xz | Format::Lzma => {
+            let mut encoder = stream::encoder(Vec::new(), format, options)?;
+            encoder
+                .write_all(data)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| format!("Compression failed: {}", e))
+        }
+    }
+}
+
+/// Decompresses `data` from the requested format, failing once the output
+/// exceeds `limits`.
+pub(crate) fn decompress_data(
+    data: &[u8],
+    format: Format,
+    limits: DecompressionLimits,
+) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Zstd => zstd_decompress(data, None, limits),
+        Format::Brotli => brotli_decompress(data, limits),
+        Format::Lz4 => lz4_decompress(data, true, limits),
+        Format::Lz4Block => lz4_decompress(data, false, limits),
+        Format::Snappy => snappy_decompress(data, true, limits),
+        Format::SnappyRaw => snappy_decompress(data, false, limits),
+        Format::Gzip
+        | Format::Zlib
+        | Format::Deflate
+        | Format::Bzip2
+        | Format::Xz
+        | Format::Lzma => limits.read_to_end(stream::decoder(data, format, limits)?, data.len()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const ALL_FORMATS: [Format; 12] = [
+        Format::Gzip,
+        Format::Zlib,
+        Format::Deflate,
+        Format::Zstd,
+        Format::Brotli,
+        Format::Lz4,
+        Format::Lz4Block,
+        Format::Snappy,
+        Format::SnappyRaw,
+        Format::Bzip2,
+        Format::Xz,
+        Format::Lzma,
+    ];
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let input = b"The quick brown fox jumps over the lazy dog. ".repeat(20);
+        for format in ALL_FORMATS {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let compressed = compress_data(&input, format, options).unwrap();
+            assert_eq!(
+                decompress_data(&compressed, format, DecompressionLimits::default()).unwrap(),
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_size_limit_stops_bombs_in_every_format() {
+        // 2 MiB of zeros compresses to a few KiB in every format
+        let bomb_source = vec![0u8; 2 * 1024 * 1024];
+        let limits = DecompressionLimits::new(256 * 1024, 0);
+        for format in ALL_FORMATS {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let bomb = compress_data(&bomb_source, format, options).unwrap();
+            let error = decompress_data(&bomb, format, limits).unwrap_err();
+            assert!(
+                error.starts_with("Decompression limit exceeded"),
+                "{:?}: {}",
+                format,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_ratio_limit() {
+        let input = vec![b'a'; 100_000];
+        let compressed =
+            compress_data(&input, Format::Gzip, CompressionOptions::default()).unwrap();
+
+        let error = decompress_data(&compressed, Format::Gzip, DecompressionLimits::new(0, 100))
+            .unwrap_err();
+        assert!(error.contains("100 times"), "{}", error);
+
+        let unlimited_ratio = DecompressionLimits::new(0, 0);
+        assert_eq!(
+            decompress_data(&compressed, Format::Gzip, unlimited_ratio).unwrap(),
+            input
+        );
+    }
+
+    #[test]
+    fn test_corrupt_input_is_not_reported_as_limit() {
+        let error =
+            decompress_data(b"not gzip", Format::Gzip, DecompressionLimits::default()).unwrap_err();
+        assert!(error.starts_with("Decompression failed"), "{}", error);
+    }
+
+    #[test]
+    fn test_decompress_known_dotnet_deflate_stream() {
+        // Raw DEFLATE of "hello" as written by zlib-backed `DeflateStream`
+        let deflated = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00];
+        assert_eq!(
+            decompress_data(&deflated, Format::Deflate, DecompressionLimits::default()).unwrap(),
+            b"hello"
+        );
+    }
+
+    #[test]
+    fn test_zlib_header_and_checksum() {
+        let compressed =
+            compress_data(b"hello", Format::Zlib, CompressionOptions::default()).unwrap();
+        assert_eq!(compressed[0], 0x78);
+        assert_eq!(u16::from_be_bytes([compressed[0], compressed[1]]) % 31, 0);
+        // Adler-32 of "hello"
+        assert_eq!(
+            &compressed[compressed.len() - 4..],
+            &[0x06, 0x2c, 0x02, 0x15]
+        );
+    }
+
+    #[test]
+    fn test_wrong_format_fails() {
+        let gzip = compress_data(b"data", Format::Gzip, CompressionOptions::default()).unwrap();
+        assert!(decompress_data(&gzip, Format::Zlib, DecompressionLimits::default()).is_err());
+        assert!(decompress_data(&gzip, Format::Bzip2, DecompressionLimits::default()).is_err());
+        assert!(decompress_data(&gzip, Format::Xz, DecompressionLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_bzip2_xz_lzma_magic() {
+        let options = |format| CompressionOptions::parse(format, None, None).unwrap();
+        let bz2 = compress_data(b"log", Format::Bzip2, options(Format::Bzip2)).unwrap();
+        assert_eq!(&bz2[..4], b"BZh9");
+        let xz = compress_data(b"log", Format::Xz, options(Format::Xz)).unwrap();
+        assert_eq!(&xz[..6], b"\xfd7zXZ\0");
+        // LZMA-alone header: properties byte 0x5d (lc=3, lp=0, pb=2)
+        let lzma = compress_data(b"log", Format::Lzma, options(Format::Lzma)).unwrap();
+        assert_eq!(lzma[0], 0x5d);
+    }
+
+    #[test]
+    fn test_concatenated_bzip2_and_xz_members() {
+        for format in [Format::Bzip2, Format::Xz] {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let mut joined = compress_data(b"first ", format, options).unwrap();
+            joined.extend(compress_data(b"second", format, options).unwrap());
+            assert_eq!(
+                decompress_data(&joined, format, DecompressionLimits::default()).unwrap(),
+                b"first second"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lzma_dictionary_beyond_memory_limit_rejected() {
+        let options = CompressionOptions::parse(Format::Lzma, None, None).unwrap();
+        let mut lzma = compress_data(b"log", Format::Lzma, options).unwrap();
+        // Claim a 3 GiB dictionary in the LZMA-alone header
+        lzma[1..5].copy_from_slice(&0xC000_0000u32.to_le_bytes());
+
+        let err = decompress_data(&lzma, Format::Lzma, DecompressionLimits::default()).unwrap_err();
+        assert!(err.contains("memory limit"), "{}", err);
+    }
+
+    #[test]
+    fn test_best_preset_decodes_under_small_output_limit() {
+        let limits = DecompressionLimits::new(16, 0);
+        for format in [Format::Xz, Format::Lzma] {
+            let options = CompressionOptions::parse(format, Some("BEST"), None).unwrap();
+            let compressed = compress_data(b"log", format, options).unwrap();
+            assert_eq!(
+                decompress_data(&compressed, format, limits).unwrap(),
+                b"log"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(Format::parse(None).unwrap(), Format::Gzip);
+        assert_eq!(Format::parse(Some("zlib")).unwrap(), Format::Zlib);
+        assert_eq!(Format::parse(Some("Deflate")).unwrap(), Format::Deflate);
+        assert_eq!(Format::parse(Some("bz2")).unwrap(), Format::Bzip2);
+        assert_eq!(Format::parse(Some("XZ")).unwrap(), Format::Xz);
+        assert!(Format::parse(Some("zip")).is_err());
+    }
+}
@@ -1,11 +1,57 @@
 //! String compression functions
 
-use flate2::Compression;
-use flate2::write::GzEncoder;
 use std::ffi::CStr;
-use std::io::Write;
 use std::os::raw::c_char;
 
+use super::codec::{Format, compress_data};
+use super::options::CompressionOptions;
+use crate::helpers::{finish_bytes, read_optional_str};
+
+/// Converts the input string to bytes with the named encoding.
+pub(crate) fn encode_input(
+    input: *const c_char,
+    encoding: *const c_char,
+) -> Result<Vec<u8>, String> {
+    if input.is_null() {
+        return Err("Input pointer is null".to_string());
+    }
+
+    if encoding.is_null() {
+        return Err("Encoding pointer is null".to_string());
+    }
+
+    let input_str = unsafe { CStr::from_ptr(input).to_str() }
+        .map_err(|_| "Invalid UTF-8 in input string".to_string())?;
+    let encoding_str = unsafe { CStr::from_ptr(encoding).to_str() }
+        .map_err(|_| "Invalid UTF-8 in encoding string".to_string())?;
+
+    crate::base64::convert_string_to_bytes(input_str, encoding_str)
+}
+
+/// Converts the input string with the named encoding and compresses it.
+pub(crate) fn compress_input(
+    input: *const c_char,
+    encoding: *const c_char,
+    format: Format,
+    options: CompressionOptions,
+) -> Result<Vec<u8>, String> {
+    let bytes = encode_input(input, encoding)?;
+    compress_data(&bytes, format, options)
+}
+
+/// Parses the optional format, level and strategy arguments shared by the
+/// compression exports.
+pub(crate) fn read_compression_args(
+    format: *const c_char,
+    level: *const c_char,
+    strategy: *const c_char,
+) -> Result<(Format, CompressionOptions), String> {
+    let format = Format::parse(read_optional_str(format, "Format")?)?;
+    let level = read_optional_str(level, "Level")?;
+    let strategy = read_optional_str(strategy, "Strategy")?;
+    Ok((format, CompressionOptions::parse(format, level, strategy)?))
+}
+
 /// Compress a string using Gzip compression
 ///
 /// Converts the input string to bytes using the specified encoding, then compresses
@@ -25,98 +71,53 @@ pub unsafe extern "C" fn compress_string(
     encoding: *const c_char,
     out_length: *mut usize,
 ) -> *mut u8 {
-    if input.is_null() {
-        crate::error::set_error("Input pointer is null".to_string());
-        if !out_length.is_null() {
-            unsafe {
-                *out_length = 0;
-            }
-        }
-        return std::ptr::null_mut();
-    }
-
-    if encoding.is_null() {
-        crate::error::set_error("Encoding pointer is null".to_string());
-        if !out_length.is_null() {
-            unsafe {
-                *out_length = 0;
-            }
-        }
-        return std::ptr::null_mut();
-    }
-
-    let input_str = match unsafe { CStr::from_ptr(input).to_str() } {
-        Ok(s) => s,
-        Err(_) => {
-            crate::error::set_error("Invalid UTF-8 in input string".to_string());
-            if !out_length.is_null() {
-                unsafe {
-                    *out_length = 0;
-                }
-            }
-            return std::ptr::null_mut();
-        }
-    };
-
-    let encoding_str = match unsafe { CStr::from_ptr(encoding).to_str() } {
-        Ok(s) => s,
-        Err(_) => {
-            crate::error::set_error("Invalid UTF-8 in encoding string".to_string());
-            if !out_length.is_null() {
-                unsafe {
-                    *out_length = 0;
-                }
-            }
-            return std::ptr::null_mut();
-        }
-    };
-
-    let bytes = match crate::base64::convert_string_to_bytes(input_str, encoding_str) {
-        Ok(b) => b,
-        Err(e) => {
-            crate::error::set_error(e);
-            if !out_length.is_null() {
-                unsafe {
-                    *out_length = 0;
-                }
-            }
-            return std::ptr::null_mut();
-        }
-    };
-
-    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-    if let Err(e) = encoder.write_all(&bytes) {
-        crate::error::set_error(format!("Compression write failed: {}", e));
-        if !out_length.is_null() {
-            unsafe {
-                *out_length = 0;
-            }
-        }
-        return std::ptr::null_mut();
-    }
-
-    let compressed = match encoder.finish() {
-        Ok(data) => data,
-        Err(e) => {
-            crate::error::set_error(format!("Compression finish failed: {}", e));
-            if !out_length.is_null() {
-                unsafe {
-                    *out_length = 0;
-                }
-            }
-            return std::ptr::null_mut();
-        }
-    };
+    finish_bytes(
+        compress_input(input, encoding, Format::Gzip, CompressionOptions::default()),
+        out_length,
+    )
+}
 
-    let length = compressed.len();
-    if !out_length.is_null() {
-        unsafe {
-            *out_length = length;
-        }
-    }
+/// Compress a string with an explicit container format, level and strategy
+///
+/// `format` is `GZIP`, `ZLIB`, `DEFLATE` (raw, as read by .NET's
+/// `DeflateStream`), `ZSTD`, `BROTLI`, `LZ4`, `LZ4_BLOCK`, `SNAPPY`,
+/// `SNAPPY_RAW`, `BZIP2`, `XZ` or `LZMA`. For the DEFLATE-based formats `level`
+/// is `0`-`9` or one of the presets `NONE` (0), `FASTEST` (1), `DEFAULT` (6) and
+/// `BEST` (9); for `ZSTD` it is `1`-`22` with `DEFAULT` (3) and `BEST` (19), and
+/// for `BROTLI` it is the quality `0`-`11` (default 11). `BZIP2` takes the block
+/// size `1`-`9` (default 9) and `XZ`/`LZMA` the preset `0`-`9` (default 6).
+/// LZ4 and Snappy have no levels. `strategy` selects the DEFLATE match strategy
+/// and is rejected for the other formats. Each may be null to use the default;
+/// with all three null the output is identical to `compress_string`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `format`, `level` and `strategy` are valid null-terminated C strings or null
+/// - `out_length` is a valid pointer to a usize
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Strategies
+/// - DEFAULT
+/// - FILTERED (matches of at least 5 bytes)
+/// - HUFFMAN_ONLY (no match search)
+/// - RLE (run-length matches only)
+/// - FIXED (static Huffman blocks)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compress_string_with_options(
+    input: *const c_char,
+    encoding: *const c_char,
+    format: *const c_char,
+    level: *const c_char,
+    strategy: *const c_char,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = read_compression_args(format, level, strategy)
+        .and_then(|(format, options)| compress_input(input, encoding, format, options));
 
-    crate::error::clear_error();
-    crate::memory::allocate_byte_array(compressed)
+    finish_bytes(result, out_length)
 }
 
 #[cfg(test)]
@@ -328,4 +329,100 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_compress_string_with_options_levels() {
+        let input = CString::new("compress me ".repeat(200)).unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let compress = |level: &str| {
+            let level = CString::new(level).unwrap();
+            let mut out_length: usize = 0;
+            let ptr = unsafe {
+                compress_string_with_options(
+                    input.as_ptr(),
+                    encoding.as_ptr(),
+                    std::ptr::null(),
+                    level.as_ptr(),
+                    std::ptr::null(),
+                    &mut out_length as *mut usize,
+                )
+            };
+            CompressedBytes::new(ptr, out_length)
+        };
+
+        let stored = compress("NONE");
+        let best = compress("9");
+
+        assert!(!stored.is_null() && !best.is_null());
+        assert!(best.len() < stored.len());
+    }
+
+    #[test]
+    fn test_compress_string_with_options_defaults_match_compress_string() {
+        let input = CString::new("same output").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let mut out_length: usize = 0;
+        let ptr = unsafe {
+            compress_string_with_options(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut out_length as *mut usize,
+            )
+        };
+        let with_options = CompressedBytes::new(ptr, out_length);
+        let plain = compress_with_encoding("same output", "UTF8");
+
+        let a = unsafe { std::slice::from_raw_parts(with_options.as_ptr(), with_options.len()) };
+        let b = unsafe { std::slice::from_raw_parts(plain.as_ptr(), plain.len()) };
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_compress_string_with_options_invalid_level() {
+        let input = CString::new("x").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let level = CString::new("11").unwrap();
+        let mut out_length: usize = 7;
+
+        let result = unsafe {
+            compress_string_with_options(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                std::ptr::null(),
+                level.as_ptr(),
+                std::ptr::null(),
+                &mut out_length as *mut usize,
+            )
+        };
+
+        assert!(result.is_null());
+        assert_eq!(out_length, 0);
+    }
+
+    #[test]
+    fn test_compress_string_with_options_zlib_format() {
+        let input = CString::new("zlib please").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let format = CString::new("ZLIB").unwrap();
+        let mut out_length: usize = 0;
+
+        let ptr = unsafe {
+            compress_string_with_options(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                format.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut out_length as *mut usize,
+            )
+        };
+        let compressed = CompressedBytes::new(ptr, out_length);
+
+        assert!(!compressed.is_null());
+        let data = unsafe { std::slice::from_raw_parts(compressed.as_ptr(), compressed.len()) };
+        assert_eq!(data[0], 0x78);
+    }
 }
@@ -1,15 +1,50 @@
-//! Gzip decompression functions
+//! Decompression functions for all supported formats
 
-use flate2::read::GzDecoder;
+use flate2::read::MultiGzDecoder;
 use std::ffi::{CStr, CString};
-use std::io::Read;
 use std::os::raw::c_char;
 
+use super::detect::DecompressionFormat;
+use super::limits::DecompressionLimits;
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_string, read_optional_str};
+
+/// Decompresses `data` within `limits` and converts the result to a string
+/// with the named encoding.
+///
+/// With `lenient` set, bytes that are invalid in the encoding fall back to Latin-1.
+pub(crate) fn decompress_to_string(
+    data: &[u8],
+    encoding: &str,
+    format: DecompressionFormat,
+    lenient: bool,
+    limits: DecompressionLimits,
+) -> Result<String, String> {
+    let decompressed = format.decompress(data, limits)?;
+    decoded_to_string(&decompressed, encoding, lenient)
+}
+
+/// Converts decompressed bytes to a string, optionally with Latin-1 fallback.
+pub(crate) fn decoded_to_string(
+    decompressed: &[u8],
+    encoding: &str,
+    lenient: bool,
+) -> Result<String, String> {
+    if lenient {
+        crate::base64::convert_bytes_to_string_with_fallback(decompressed, encoding)
+    } else {
+        crate::base64::convert_bytes_to_string(decompressed, encoding)
+    }
+}
+
 /// Decompress a Gzip-compressed byte array to a string
 ///
 /// Decompresses the input byte array using Gzip, then converts the decompressed
 /// bytes to a string using the specified encoding. Handles special characters,
-/// Unicode, and various encodings correctly.
+/// Unicode, and various encodings correctly. Concatenated Gzip members (as from
+/// `cat a.gz b.gz`) are all decompressed. Output is capped at the fixed default
+/// limit of 256 MiB; to set other limits, call `decompress_string_with_format`
+/// with a null `format`, which also selects Gzip.
 ///
 /// # Safety
 /// This function is unsafe because it dereferences raw pointers.
@@ -44,13 +79,15 @@ pub unsafe extern "C" fn decompress_string(
 
     let compressed_slice = unsafe { std::slice::from_raw_parts(bytes, length) };
 
-    let mut decoder = GzDecoder::new(compressed_slice);
-    let mut decompressed = Vec::new();
-
-    if let Err(e) = decoder.read_to_end(&mut decompressed) {
-        crate::error::set_error(format!("Decompression failed: {}", e));
-        return std::ptr::null_mut();
-    }
+    let decompressed = match DecompressionLimits::default()
+        .read_to_end(MultiGzDecoder::new(compressed_slice), length)
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
 
     let result_string = match crate::base64::convert_bytes_to_string(&decompressed, encoding_str) {
         Ok(s) => s,
@@ -84,6 +121,10 @@ pub unsafe extern "C" fn decompress_string(
 /// Use this function when you want best-effort conversion without errors.
 /// Use `decompress_string` when you want strict validation of the encoding.
 ///
+/// Output is capped at the same fixed 256 MiB as `decompress_string`; call
+/// `decompress_string_with_format` with a null `format` and `lenient` set to
+/// choose other limits.
+///
 /// # Safety
 /// Same safety requirements as `decompress_string`.
 #[unsafe(no_mangle)]
@@ -112,13 +153,15 @@ pub unsafe extern "C" fn decompress_string_lenient(
 
     let compressed_slice = unsafe { std::slice::from_raw_parts(bytes, length) };
 
-    let mut decoder = GzDecoder::new(compressed_slice);
-    let mut decompressed = Vec::new();
-
-    if let Err(e) = decoder.read_to_end(&mut decompressed) {
-        crate::error::set_error(format!("Decompression failed: {}", e));
-        return std::ptr::null_mut();
-    }
+    let decompressed = match DecompressionLimits::default()
+        .read_to_end(MultiGzDecoder::new(compressed_slice), length)
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
 
     let result_string =
         match crate::base64::convert_bytes_to_string_with_fallback(&decompressed, encoding_str) {
@@ -143,6 +186,53 @@ pub unsafe extern "C" fn decompress_string_lenient(
     }
 }
 
+/// Decompress a byte array in any supported format to a string
+///
+/// `format` is `GZIP`, `ZLIB`, `DEFLATE`, `ZSTD`, `BROTLI`, `LZ4`, `LZ4_BLOCK`,
+/// `SNAPPY`, `SNAPPY_RAW`, `BZIP2`, `XZ` or `LZMA` and may be null for Gzip, so
+/// data written by .NET's `DeflateStream` or `ZLibStream` can be read directly.
+/// `AUTO` detects GZIP, ZLIB, ZSTD, BZIP2, XZ, LZMA, LZ4 and SNAPPY data from
+/// its header and converts data without a recognised header as-is; see
+/// `detect_compression`.
+/// Concatenated Gzip, bzip2 and xz members are decoded in full. For large
+/// `.bz2` or `.xz` files use `decompress_file` instead.
+/// With `lenient` set this behaves like `decompress_string_lenient`, falling
+/// back to Latin-1 when the bytes are invalid for the encoding.
+///
+/// Decompression stops with a "Decompression limit exceeded" error once the
+/// output passes `max_output_size` bytes (0 for the default of 256 MiB) or
+/// `max_ratio` times the compressed length (0 for no ratio limit). The xz and
+/// LZMA decoders may use up to `max_output_size` bytes of memory (at least
+/// 96 MiB), so data whose header asks for a larger dictionary is rejected.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `encoding` is a valid null-terminated C string or null
+/// - `format` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompress_string_with_format(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    format: *const c_char,
+    lenient: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    let result = read_optional_str(encoding, "Encoding").and_then(|encoding| {
+        let encoding = encoding.ok_or_else(|| "Encoding pointer is null".to_string())?;
+        let format = DecompressionFormat::parse(read_optional_str(format, "Format")?)?;
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        decompress_to_string(data, encoding, format, lenient, limits)
+    });
+
+    finish_string(result, "decompressed result")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,4 +442,50 @@ mod tests {
         let result_bytes = result.as_bytes();
         assert_eq!(result_bytes, original_bytes, "Bytes should match exactly");
     }
+
+    #[test]
+    fn test_decompress_string_with_format_raw_deflate() {
+        // Raw DEFLATE of "hello" as written by `DeflateStream`
+        let deflated = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00];
+        let encoding = CString::new("UTF8").unwrap();
+        let format = CString::new("DEFLATE").unwrap();
+
+        let result = unsafe {
+            decompress_string_with_format(
+                deflated.as_ptr(),
+                deflated.len(),
+                encoding.as_ptr(),
+                format.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        };
+
+        assert!(!result.is_null());
+        let text = unsafe { CStr::from_ptr(result).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(result) };
+        assert_eq!(text, "hello");
+    }
+
+    #[test]
+    fn test_decompress_string_with_format_mismatch_returns_null() {
+        let deflated = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00];
+        let encoding = CString::new("UTF8").unwrap();
+        let format = CString::new("ZLIB").unwrap();
+
+        let result = unsafe {
+            decompress_string_with_format(
+                deflated.as_ptr(),
+                deflated.len(),
+                encoding.as_ptr(),
+                format.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        };
+
+        assert!(result.is_null());
+    }
 }
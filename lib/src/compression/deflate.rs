@@ -0,0 +1,257 @@
+//! DEFLATE encoding with configurable level and strategy, and Gzip/zlib framing
+
+use flate2::Crc;
+use miniz_oxide::deflate::core::{
+    CompressorOxide, TDEFLFlush, TDEFLStatus, compress, create_comp_flags_from_zip_params,
+};
+use std::io::{self, Write};
+
+use super::gzip_header::GzipHeader;
+use super::options::CompressionOptions;
+
+/// Size of the scratch buffer compressed output is staged in before writing.
+const OUTPUT_CHUNK: usize = 32 * 1024;
+
+/// Container wrapped around the DEFLATE stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Framing {
+    Raw,
+    Zlib,
+    Gzip,
+}
+
+/// Streaming DEFLATE encoder that writes compressed output to `W`.
+///
+/// Unlike flate2's encoders this honours the compression strategy.
+pub(crate) struct DeflateEncoder<W: Write> {
+    writer: W,
+    compressor: Box<CompressorOxide>,
+    framing: Framing,
+    extra_flags: u8,
+    header_written: bool,
+    crc: Crc,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> DeflateEncoder<W> {
+    pub(crate) fn new(writer: W, framing: Framing, options: CompressionOptions) -> Self {
+        // Negative window bits select a raw stream; zlib framing is added by miniz itself
+        let window_bits = match framing {
+            Framing::Zlib => 15,
+            Framing::Raw | Framing::Gzip => -15,
+        };
+        let flags =
+            create_comp_flags_from_zip_params(options.level, window_bits, options.strategy as i32);
+
+        // XFL advertises the slowest / fastest settings as gzip does
+        let extra_flags = match options.level {
+            9 => 2,
+            0 | 1 => 4,
+            _ => 0,
+        };
+
+        Self {
+            writer,
+            compressor: Box::new(CompressorOxide::new(flags)),
+            framing,
+            extra_flags,
+            header_written: false,
+            crc: Crc::new(),
+            buffer: vec![0u8; OUTPUT_CHUNK],
+        }
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if !self.header_written {
+            if self.framing == Framing::Gzip {
+                // Same fields as flate2's `GzEncoder`: no flags, zero mtime, unknown OS
+                let header = GzipHeader {
+                    extra_flags: self.extra_flags,
+                    ..GzipHeader::default()
+                };
+                self.writer.write_all(&header.to_bytes())?;
+            }
+            self.header_written = true;
+        }
+        Ok(())
+    }
+
+    /// Feeds `input` to the compressor, returning whether the stream is done.
+    fn pump(&mut self, mut input: &[u8], flush: TDEFLFlush) -> io::Result<bool> {
+        loop {
+            let (status, bytes_in, bytes_out) =
+                compress(&mut self.compressor, input, &mut self.buffer, flush);
+            self.writer.write_all(&self.buffer[..bytes_out])?;
+            input = &input[bytes_in..];
+
+            match status {
+                TDEFLStatus::Done => return Ok(true),
+                TDEFLStatus::Okay if input.is_empty() && bytes_out < self.buffer.len() => {
+                    return Ok(false);
+                }
+                TDEFLStatus::Okay => {}
+                _ => return Err(io::Error::other("Compression failed")),
+            }
+        }
+    }
+
+    /// The underlying writer, holding the output produced so far.
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Completes the stream, writes any trailer and returns the writer.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        self.write_header()?;
+        while !self.pump(&[], TDEFLFlush::Finish)? {}
+
+        if self.framing == Framing::Gzip {
+            let crc = self.crc.sum().to_le_bytes();
+            let size = self.crc.amount().to_le_bytes();
+            self.writer.write_all(&crc)?;
+            self.writer.write_all(&size)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for DeflateEncoder<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.write_header()?;
+        self.crc.update(data);
+        self.pump(data, TDEFLFlush::None)?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_header()?;
+        self.pump(&[], TDEFLFlush::Sync)?;
+        self.writer.flush()
+    }
+}
+
+fn encode(input: &[u8], framing: Framing, options: CompressionOptions) -> Result<Vec<u8>, String> {
+    let mut encoder =
+        DeflateEncoder::new(Vec::with_capacity(input.len() / 2 + 64), framing, options);
+    encoder
+        .write_all(input)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| format!("Compression failed: {}", e))
+}
+
+/// Compresses `input` into a raw DEFLATE stream (RFC 1951).
+pub(crate) fn deflate_raw(input: &[u8], options: CompressionOptions) -> Result<Vec<u8>, String> {
+    encode(input, Framing::Raw, options)
+}
+
+/// Compresses `input` into a zlib stream (RFC 1950).
+pub(crate) fn zlib_compress(input: &[u8], options: CompressionOptions) -> Result<Vec<u8>, String> {
+    encode(input, Framing::Zlib, options)
+}
+
+/// Compresses `input` into a single-member Gzip stream (RFC 1952).
+pub(crate) fn gzip_compress(input: &[u8], options: CompressionOptions) -> Result<Vec<u8>, String> {
+    encode(input, Framing::Gzip, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use miniz_oxide::deflate::core::CompressionStrategy;
+    use std::io::Read;
+
+    fn gunzip(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut out).unwrap();
+        out
+    }
+
+    fn sample() -> Vec<u8> {
+        (0..20_000u32)
+            .map(|i| (i % 251) as u8 ^ (i / 97) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_gzip_round_trips_every_level_and_strategy() {
+        let input = sample();
+        let strategies = [
+            CompressionStrategy::Default,
+            CompressionStrategy::Filtered,
+            CompressionStrategy::HuffmanOnly,
+            CompressionStrategy::RLE,
+            CompressionStrategy::Fixed,
+        ];
+        for level in 0..=9 {
+            for strategy in strategies {
+                let compressed =
+                    gzip_compress(&input, CompressionOptions { level, strategy }).unwrap();
+                assert_eq!(gunzip(&compressed), input, "level {level} {strategy:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_level_zero_stores_and_nine_shrinks() {
+        let input = "log line repeated\n".repeat(500).into_bytes();
+        let stored = gzip_compress(
+            &input,
+            CompressionOptions {
+                level: 0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let best = gzip_compress(
+            &input,
+            CompressionOptions {
+                level: 9,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(stored.len() > input.len());
+        assert!(best.len() < input.len() / 20);
+    }
+
+    #[test]
+    fn test_gzip_header_matches_flate2_encoder() {
+        let input = b"header check";
+        let ours = gzip_compress(input, CompressionOptions::default()).unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, input).unwrap();
+        let theirs = encoder.finish().unwrap();
+
+        assert_eq!(&ours[..10], &theirs[..10]);
+    }
+
+    #[test]
+    fn test_deflate_raw_round_trip_empty() {
+        let compressed = deflate_raw(&[], CompressionOptions::default()).unwrap();
+        let mut out = Vec::new();
+        DeflateDecoder::new(&compressed[..])
+            .read_to_end(&mut out)
+            .unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_streaming_writes_match_single_write() {
+        let input = sample();
+        let whole = gzip_compress(&input, CompressionOptions::default()).unwrap();
+
+        let mut encoder =
+            DeflateEncoder::new(Vec::new(), Framing::Gzip, CompressionOptions::default());
+        for chunk in input.chunks(777) {
+            encoder.write_all(chunk).unwrap();
+        }
+        let chunked = encoder.finish().unwrap();
+
+        assert_eq!(gunzip(&chunked), input);
+        assert_eq!(chunked, whole);
+    }
+}
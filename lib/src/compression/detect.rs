@@ -0,0 +1,214 @@
+//! Compression format detection from magic bytes and headers
+//!
+//! Only self-describing formats can be recognised: Brotli, raw DEFLATE, LZ4
+//! blocks and raw Snappy have no signature. zlib and LZMA-alone headers are
+//! short enough to occur in plain text, so they are confirmed by decoding the
+//! start of the stream.
+
+use std::io::Read;
+
+use super::codec::{Format, decompress_data};
+use super::limits::DecompressionLimits;
+use super::stream;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const XZ_MAGIC: [u8; 6] = [0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+const SNAPPY_STREAM_IDENTIFIER: [u8; 10] =
+    [0xff, 0x06, 0x00, 0x00, b's', b'N', b'a', b'P', b'p', b'Y'];
+
+/// Name reported by `detect_compression` for data with no recognised header.
+pub(crate) const UNCOMPRESSED_NAME: &str = "NONE";
+
+/// Identifies the compression format of `data` from its leading bytes, or
+/// `None` if it does not start with a recognised header.
+pub(crate) fn detect_format(data: &[u8]) -> Option<Format> {
+    if data.starts_with(&GZIP_MAGIC) {
+        Some(Format::Gzip)
+    } else if data.starts_with(&ZSTD_MAGIC) || is_zstd_skippable_frame(data) {
+        Some(Format::Zstd)
+    } else if data.starts_with(&XZ_MAGIC) {
+        Some(Format::Xz)
+    } else if is_bzip2_header(data) {
+        Some(Format::Bzip2)
+    } else if data.starts_with(&LZ4_FRAME_MAGIC) {
+        Some(Format::Lz4)
+    } else if data.starts_with(&SNAPPY_STREAM_IDENTIFIER) {
+        Some(Format::Snappy)
+    } else if is_zlib_header(data) && decodes_as(data, Format::Zlib) {
+        Some(Format::Zlib)
+    } else if is_lzma_alone_header(data) && decodes_as(data, Format::Lzma) {
+        Some(Format::Lzma)
+    } else {
+        None
+    }
+}
+
+/// Skippable frames (magic `0x184D2A50`-`0x184D2A5F`) may precede Zstandard data.
+fn is_zstd_skippable_frame(data: &[u8]) -> bool {
+    matches!(data, [first, 0x2a, 0x4d, 0x18, ..] if first & 0xf0 == 0x50)
+}
+
+/// `BZh` followed by the block size digit.
+fn is_bzip2_header(data: &[u8]) -> bool {
+    matches!(data, [b'B', b'Z', b'h', b'1'..=b'9', ..])
+}
+
+/// RFC 1950 header: DEFLATE method, window of at most 32 KiB, valid check
+/// bits and no preset dictionary.
+fn is_zlib_header(data: &[u8]) -> bool {
+    let [cmf, flg, ..] = *data else {
+        return false;
+    };
+    cmf & 0x0f == 8 && cmf >> 4 <= 7 && flg & 0x20 == 0 && u16::from_be_bytes([cmf, flg]) % 31 == 0
+}
+
+/// `.lzma` header: properties byte, dictionary size as written by xz-utils and
+/// 7-Zip (2^n or 2^n + 2^(n-1)), and an unknown or plausible uncompressed size.
+fn is_lzma_alone_header(data: &[u8]) -> bool {
+    let Some(header) = data.first_chunk::<13>() else {
+        return false;
+    };
+    let properties = header[0];
+    let dictionary_size = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+    let uncompressed_size = u64::from_le_bytes(header[5..13].try_into().unwrap());
+
+    let trimmed = dictionary_size >> dictionary_size.trailing_zeros();
+    properties < 9 * 5 * 5
+        && dictionary_size >= 4096
+        && (trimmed == 1 || trimmed == 3)
+        && (uncompressed_size == u64::MAX || uncompressed_size < 1 << 40)
+}
+
+/// Whether the start of `data` decodes without error as `format`.
+fn decodes_as(data: &[u8], format: Format) -> bool {
+    let mut probe = [0u8; 64];
+    stream::decoder(data, format, DecompressionLimits::default())
+        .and_then(|mut decoder| decoder.read(&mut probe).map_err(|e| e.to_string()))
+        .is_ok()
+}
+
+/// The format argument of the decompression exports: a fixed format, or
+/// `AUTO` to detect it from the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DecompressionFormat {
+    Fixed(Format),
+    Auto,
+}
+
+impl DecompressionFormat {
+    /// Parses a format name as `Format::parse` does, additionally accepting `AUTO`.
+    pub(crate) fn parse(name: Option<&str>) -> Result<Self, String> {
+        match name {
+            Some(name) if name.trim().eq_ignore_ascii_case("AUTO") => Ok(Self::Auto),
+            _ => Format::parse(name)
+                .map(Self::Fixed)
+                .map_err(|e| format!("{}, AUTO", e)),
+        }
+    }
+
+    /// The format to decode `data` with; `None` means it is not compressed.
+    pub(crate) fn resolve(self, data: &[u8]) -> Option<Format> {
+        match self {
+            Self::Fixed(format) => Some(format),
+            Self::Auto => detect_format(data),
+        }
+    }
+
+    /// Decompresses `data` within `limits`; data that `AUTO` finds to be
+    /// uncompressed is returned unchanged.
+    pub(crate) fn decompress(
+        self,
+        data: &[u8],
+        limits: DecompressionLimits,
+    ) -> Result<Vec<u8>, String> {
+        match self.resolve(data) {
+            Some(format) => decompress_data(data, format, limits),
+            None => {
+                limits.check(data.len() as u64, data.len() as u64)?;
+                Ok(data.to_vec())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::codec::compress_data;
+    use crate::compression::options::CompressionOptions;
+
+    #[test]
+    fn test_detects_self_describing_formats() {
+        let input = b"detect me, detect me, detect me".repeat(10);
+        for format in [
+            Format::Gzip,
+            Format::Zlib,
+            Format::Zstd,
+            Format::Lz4,
+            Format::Snappy,
+            Format::Bzip2,
+            Format::Xz,
+            Format::Lzma,
+        ] {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let compressed = compress_data(&input, format, options).unwrap();
+            assert_eq!(detect_format(&compressed), Some(format), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_zlib_levels_detected() {
+        for level in ["0", "1", "6", "9"] {
+            let options = CompressionOptions::parse(Format::Zlib, Some(level), None).unwrap();
+            let compressed = compress_data(b"zlib", Format::Zlib, options).unwrap();
+            assert_eq!(detect_format(&compressed), Some(Format::Zlib), "{}", level);
+        }
+    }
+
+    #[test]
+    fn test_plain_data_not_detected() {
+        for data in [
+            &b""[..],
+            b"Hello, World!",
+            b"x^ looks like a zlib header",
+            b"HK also has valid zlib check bits",
+            b"BZ",
+            b"{\"json\": true}",
+        ] {
+            assert_eq!(detect_format(data), None, "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_zstd_skippable_frame() {
+        let mut data = vec![0x50, 0x2a, 0x4d, 0x18, 0, 0, 0, 0];
+        data.extend(
+            compress_data(
+                b"after",
+                Format::Zstd,
+                CompressionOptions::parse(Format::Zstd, None, None).unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(detect_format(&data), Some(Format::Zstd));
+    }
+
+    #[test]
+    fn test_decompression_format_parse() {
+        assert_eq!(
+            DecompressionFormat::parse(Some(" auto ")).unwrap(),
+            DecompressionFormat::Auto
+        );
+        assert_eq!(
+            DecompressionFormat::parse(None).unwrap(),
+            DecompressionFormat::Fixed(Format::Gzip)
+        );
+        assert!(
+            DecompressionFormat::parse(Some("zip"))
+                .unwrap_err()
+                .ends_with("LZMA, AUTO")
+        );
+    }
+}
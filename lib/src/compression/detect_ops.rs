@@ -0,0 +1,126 @@
+//! Compression format detection operations
+
+use std::os::raw::c_char;
+
+use super::detect::{UNCOMPRESSED_NAME, detect_format};
+use crate::encoding::slice_from_raw;
+use crate::helpers::finish_string;
+
+/// Detect the compression format of a byte array from its magic bytes
+///
+/// Returns `GZIP`, `ZLIB`, `ZSTD`, `BZIP2`, `XZ`, `LZMA`, `LZ4` or `SNAPPY`
+/// (the framed formats), or `NONE` when the data has no recognised header.
+/// Brotli, raw DEFLATE, LZ4 blocks and raw Snappy carry no signature and are
+/// reported as `NONE`. The same detection is used by the `AUTO` format of the
+/// decompression exports.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn detect_compression(bytes: *const u8, length: usize) -> *mut c_char {
+    let result = unsafe { slice_from_raw(bytes, length, "Byte array") }.map(|data| {
+        detect_format(data)
+            .map_or(UNCOMPRESSED_NAME, |format| format.name())
+            .to_string()
+    });
+
+    finish_string(result, "format name")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    fn detect(data: &[u8]) -> Option<String> {
+        take_string(unsafe { detect_compression(data.as_ptr(), data.len()) })
+    }
+
+    #[test]
+    fn test_detect_compression_names() {
+        let gzip = [0x1f, 0x8b, 0x08, 0x00];
+        assert_eq!(detect(&gzip).as_deref(), Some("GZIP"));
+        assert_eq!(detect(b"BZh91AY&SY").as_deref(), Some("BZIP2"));
+        assert_eq!(detect(b"\xfd7zXZ\x00\x00").as_deref(), Some("XZ"));
+        assert_eq!(detect(b"plain text").as_deref(), Some("NONE"));
+        assert_eq!(
+            take_string(unsafe { detect_compression(std::ptr::null(), 0) }).as_deref(),
+            Some("NONE")
+        );
+    }
+
+    #[test]
+    fn test_detect_compression_null_with_length() {
+        assert!(unsafe { detect_compression(std::ptr::null(), 4) }.is_null());
+    }
+
+    #[test]
+    fn test_auto_format_decompresses_detected_and_plain_data() {
+        use crate::compression::{compress_string_with_options, decompress_string_with_format};
+
+        let encoding = CString::new("UTF8").unwrap();
+        let auto = CString::new("AUTO").unwrap();
+        let text = CString::new("log bundle contents").unwrap();
+
+        for name in ["GZIP", "ZLIB", "ZSTD", "BZIP2", "XZ"] {
+            let format = CString::new(name).unwrap();
+            let mut len = 0usize;
+            let ptr = unsafe {
+                compress_string_with_options(
+                    text.as_ptr(),
+                    encoding.as_ptr(),
+                    format.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    &mut len,
+                )
+            };
+            let compressed = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+            unsafe { crate::memory::free_bytes(ptr) };
+
+            let decompressed = take_string(unsafe {
+                decompress_string_with_format(
+                    compressed.as_ptr(),
+                    compressed.len(),
+                    encoding.as_ptr(),
+                    auto.as_ptr(),
+                    false,
+                    0,
+                    0,
+                )
+            });
+            assert_eq!(
+                decompressed.as_deref(),
+                Some("log bundle contents"),
+                "{}",
+                name
+            );
+        }
+
+        let plain = text.as_bytes();
+        let passed_through = take_string(unsafe {
+            decompress_string_with_format(
+                plain.as_ptr(),
+                plain.len(),
+                encoding.as_ptr(),
+                auto.as_ptr(),
+                false,
+                0,
+                0,
+            )
+        });
+        assert_eq!(passed_through.as_deref(), Some("log bundle contents"));
+    }
+}
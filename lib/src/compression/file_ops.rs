@@ -0,0 +1,413 @@
+//! Streaming file compression operations for large inputs
+
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+
+use super::compress::read_compression_args;
+use super::detect::DecompressionFormat;
+use super::limits::{DecompressionLimits, LimitedReader, error_message};
+use super::stream;
+use crate::helpers::{finish_bool, read_optional_str};
+
+/// Reads a required path argument.
+fn read_path<'a>(ptr: *const c_char, name: &str) -> Result<&'a Path, String> {
+    read_optional_str(ptr, name)?
+        .map(Path::new)
+        .ok_or_else(|| format!("{} pointer is null", name))
+}
+
+/// Fails when `input_path` and `output_path` name the same file, directly or
+/// through a different spelling of the path.
+fn check_distinct_paths(input_path: &Path, output_path: &Path) -> Result<(), String> {
+    let same = input_path == output_path
+        || match (input_path.canonicalize(), output_path.canonicalize()) {
+            (Ok(input), Ok(output)) => input == output,
+            _ => false,
+        };
+    if same {
+        return Err(format!(
+            "Input and output paths refer to the same file: {}",
+            output_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a new uniquely named temporary file next to `output_path`, so it
+/// can later be renamed over the output on the same file system.
+fn create_temp_file(output_path: &Path) -> Result<(PathBuf, File), String> {
+    let create_error = |e: io::Error| format!("Failed to create {}: {}", output_path.display(), e);
+    let name = output_path
+        .file_name()
+        .ok_or_else(|| format!("Output path has no file name: {}", output_path.display()))?;
+    let mut suffix = [0u8; 8];
+    crate::random::fill_random(&mut suffix)?;
+    let suffix: String = suffix.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", suffix));
+    let temp_path = output_path.with_file_name(temp_name);
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)
+        .map_err(create_error)?;
+    Ok((temp_path, file))
+}
+
+/// Runs `transform` from `input_path` into a temporary file beside
+/// `output_path` and renames it into place only on success. An existing
+/// output file is left untouched when the transform fails.
+fn transform_file(
+    input_path: &Path,
+    output_path: &Path,
+    transform: impl FnOnce(BufReader<File>, BufWriter<File>) -> Result<(), String>,
+) -> Result<(), String> {
+    check_distinct_paths(input_path, output_path)?;
+    let input = File::open(input_path)
+        .map_err(|e| format!("Failed to open {}: {}", input_path.display(), e))?;
+    let (temp_path, output) = create_temp_file(output_path)?;
+
+    let result = transform(BufReader::new(input), BufWriter::new(output)).and_then(|()| {
+        std::fs::rename(&temp_path, output_path)
+            .map_err(|e| format!("Failed to replace {}: {}", output_path.display(), e))
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Compress a file to another file without loading it into memory
+///
+/// `format` is any format accepted by `compress_string_with_options` (null for
+/// GZIP) and `level` is the format's level or preset name (null for its
+/// default). The output is written to a temporary file in the same directory
+/// and renamed over `output_path` only when compression succeeds, so a failure
+/// leaves any existing output untouched. The two paths must name different
+/// files.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_path` and `output_path` are valid null-terminated C strings
+/// - `format` is a valid null-terminated C string or null
+/// - `level` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compress_file(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    format: *const c_char,
+    level: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    let result = (|| {
+        let input_path = read_path(input_path, "Input path")?;
+        let output_path = read_path(output_path, "Output path")?;
+        let (format, options) = read_compression_args(format, level, std::ptr::null())?;
+
+        transform_file(input_path, output_path, |mut reader, writer| {
+            let mut encoder = stream::encoder(writer, format, options)?;
+            io::copy(&mut reader, &mut encoder)
+                .and_then(|_| encoder.finish())
+                .and_then(|mut writer| writer.flush())
+                .map_err(|e| format!("Compression failed: {}", e))
+        })
+    })();
+
+    finish_bool(result)
+}
+
+/// Decompress a file to another file without loading it into memory
+///
+/// `format` is any format accepted by `decompress_string_with_format`,
+/// including `AUTO`, or null for GZIP; with `AUTO` a file without a recognised
+/// header is copied unchanged. Concatenated bzip2 and xz streams, as produced by parallel
+/// compressors, are decoded in full. As with `compress_file`, the output
+/// replaces `output_path` only on success and the two paths must differ.
+///
+/// `max_output_size` (0 for the default of 256 MiB) and `max_ratio` (0 for
+/// none) bound the decompressed file size as in `decompress_string_with_format`;
+/// pass `u64::MAX` to decompress files of any size.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_path` and `output_path` are valid null-terminated C strings
+/// - `format` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompress_file(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    format: *const c_char,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> bool {
+    crate::error::clear_error();
+
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+
+    let result = (|| {
+        let input_path = read_path(input_path, "Input path")?;
+        let output_path = read_path(output_path, "Output path")?;
+        let format = DecompressionFormat::parse(read_optional_str(format, "Format")?)?;
+
+        transform_file(input_path, output_path, |mut reader, mut writer| {
+            let read_error =
+                |e: io::Error| format!("Failed to read {}: {}", input_path.display(), e);
+            let input_len = reader.get_ref().metadata().map_err(read_error)?.len();
+            // The first buffered block is plenty for any header
+            let detected = format.resolve(reader.fill_buf().map_err(read_error)?);
+            let decoder: Box<dyn Read> = match detected {
+                Some(format) => stream::decoder(reader, format, limits)?,
+                None => Box::new(reader),
+            };
+            io::copy(
+                &mut LimitedReader::new(decoder, limits, input_len),
+                &mut writer,
+            )
+            .and_then(|_| writer.flush())
+            .map_err(|e| error_message(&e, "Decompression failed"))
+        })
+    })();
+
+    finish_bool(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "convert_core_file_ops_{}_{}",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn c_path(path: &Path) -> CString {
+        CString::new(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_file_round_trip_bzip2_and_xz() {
+        let contents = b"2024-01-01 vendor log line\n".repeat(5000);
+        for (name, level) in [("BZIP2", "FASTEST"), ("XZ", "BEST"), ("LZMA", "1")] {
+            let source = temp_path(&format!("{}.log", name));
+            let compressed = temp_path(&format!("{}.log.z", name));
+            let restored = temp_path(&format!("{}.out", name));
+            std::fs::write(&source, &contents).unwrap();
+
+            let format = CString::new(name).unwrap();
+            let level = CString::new(level).unwrap();
+            let compressed_ok = unsafe {
+                compress_file(
+                    c_path(&source).as_ptr(),
+                    c_path(&compressed).as_ptr(),
+                    format.as_ptr(),
+                    level.as_ptr(),
+                )
+            };
+            let decompressed_ok = unsafe {
+                decompress_file(
+                    c_path(&compressed).as_ptr(),
+                    c_path(&restored).as_ptr(),
+                    format.as_ptr(),
+                    0,
+                    0,
+                )
+            };
+            let output = std::fs::read(&restored).unwrap_or_default();
+            let compressed_len = std::fs::metadata(&compressed).map_or(0, |m| m.len());
+            for path in [&source, &compressed, &restored] {
+                let _ = std::fs::remove_file(path);
+            }
+
+            assert!(compressed_ok && decompressed_ok, "{}", name);
+            assert!(compressed_len < contents.len() as u64 / 10);
+            assert_eq!(output, contents);
+        }
+    }
+
+    #[test]
+    fn test_decompress_file_failure_removes_output() {
+        let source = temp_path("garbage.xz");
+        let output = temp_path("garbage.out");
+        std::fs::write(&source, b"not xz data").unwrap();
+        let format = CString::new("XZ").unwrap();
+
+        let ok = unsafe {
+            decompress_file(
+                c_path(&source).as_ptr(),
+                c_path(&output).as_ptr(),
+                format.as_ptr(),
+                0,
+                0,
+            )
+        };
+        let _ = std::fs::remove_file(&source);
+
+        assert!(!ok);
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_failure_keeps_existing_output() {
+        let source = temp_path("keep.xz");
+        let output = temp_path("keep.out");
+        std::fs::write(&source, b"not xz data").unwrap();
+        std::fs::write(&output, b"previous contents").unwrap();
+        let format = CString::new("XZ").unwrap();
+
+        let ok = unsafe {
+            decompress_file(
+                c_path(&source).as_ptr(),
+                c_path(&output).as_ptr(),
+                format.as_ptr(),
+                0,
+                0,
+            )
+        };
+        let kept = std::fs::read(&output).unwrap_or_default();
+        let prefix = format!(".{}.", output.file_name().unwrap().to_str().unwrap());
+        let leftovers = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .count();
+        for path in [&source, &output] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(!ok);
+        assert_eq!(kept, b"previous contents");
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_success_replaces_existing_output() {
+        let source = temp_path("replace.log");
+        let output = temp_path("replace.gz");
+        std::fs::write(&source, b"fresh data").unwrap();
+        std::fs::write(&output, b"stale output that is longer than the new one").unwrap();
+
+        let ok = unsafe {
+            compress_file(
+                c_path(&source).as_ptr(),
+                c_path(&output).as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        let compressed = std::fs::read(&output).unwrap_or_default();
+        for path in [&source, &output] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(ok);
+        assert_eq!(&compressed[..2], [0x1f, 0x8b]);
+    }
+
+    #[test]
+    fn test_same_input_and_output_rejected() {
+        let source = temp_path("same.log");
+        std::fs::write(&source, b"must survive").unwrap();
+        let respelled = source
+            .parent()
+            .unwrap()
+            .join(".")
+            .join(source.file_name().unwrap());
+
+        let results = unsafe {
+            [
+                compress_file(
+                    c_path(&source).as_ptr(),
+                    c_path(&source).as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                ),
+                compress_file(
+                    c_path(&source).as_ptr(),
+                    c_path(&respelled).as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                ),
+            ]
+        };
+        let contents = std::fs::read(&source).unwrap_or_default();
+        let _ = std::fs::remove_file(&source);
+
+        assert_eq!(results, [false; 2]);
+        assert_eq!(contents, b"must survive");
+    }
+
+    #[test]
+    fn test_missing_input_file() {
+        let missing = temp_path("missing.gz");
+        let output = temp_path("missing.out");
+        let ok = unsafe {
+            decompress_file(
+                c_path(&missing).as_ptr(),
+                c_path(&output).as_ptr(),
+                std::ptr::null(),
+                0,
+                0,
+            )
+        };
+        assert!(!ok);
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_decompress_file_auto_detects_format() {
+        let source = temp_path("auto.log");
+        let compressed = temp_path("auto.log.bz2");
+        let restored = temp_path("auto.out");
+        let copied = temp_path("auto.copy");
+        std::fs::write(&source, b"auto-detected bundle").unwrap();
+
+        let bzip2 = CString::new("BZIP2").unwrap();
+        let auto = CString::new("AUTO").unwrap();
+        let results = unsafe {
+            [
+                compress_file(
+                    c_path(&source).as_ptr(),
+                    c_path(&compressed).as_ptr(),
+                    bzip2.as_ptr(),
+                    std::ptr::null(),
+                ),
+                decompress_file(
+                    c_path(&compressed).as_ptr(),
+                    c_path(&restored).as_ptr(),
+                    auto.as_ptr(),
+                    0,
+                    0,
+                ),
+                // Uncompressed input is copied as-is
+                decompress_file(
+                    c_path(&source).as_ptr(),
+                    c_path(&copied).as_ptr(),
+                    auto.as_ptr(),
+                    0,
+                    0,
+                ),
+            ]
+        };
+        let outputs = [&restored, &copied].map(|path| std::fs::read(path).unwrap_or_default());
+        for path in [&source, &compressed, &restored, &copied] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert_eq!(results, [true; 3]);
+        assert_eq!(outputs[0], b"auto-detected bundle");
+        assert_eq!(outputs[1], b"auto-detected bundle");
+    }
+}
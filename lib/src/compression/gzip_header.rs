@@ -0,0 +1,214 @@
+//! RFC 1952 Gzip member header parsing and serialisation
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const METHOD_DEFLATE: u8 = 8;
+
+const FLAG_TEXT: u8 = 0x01;
+const FLAG_HEADER_CRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+const RESERVED_FLAGS: u8 = 0xe0;
+
+/// OS byte for "unknown", as written by flate2 and .NET's `GZipStream`.
+pub(crate) const OS_UNKNOWN: u8 = 255;
+
+/// The metadata fields of a Gzip member header.
+///
+/// The file name and comment are stored as ISO-8859-1 bytes without their
+/// terminating zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GzipHeader {
+    pub text: bool,
+    pub mtime: u32,
+    pub extra_flags: u8,
+    pub os: u8,
+    pub extra: Option<Vec<u8>>,
+    pub file_name: Option<Vec<u8>>,
+    pub comment: Option<Vec<u8>>,
+}
+
+impl Default for GzipHeader {
+    /// The minimal header: no optional fields, zero mtime and unknown OS.
+    fn default() -> Self {
+        Self {
+            text: false,
+            mtime: 0,
+            extra_flags: 0,
+            os: OS_UNKNOWN,
+            extra: None,
+            file_name: None,
+            comment: None,
+        }
+    }
+}
+
+impl GzipHeader {
+    /// Parses the header at the start of `data`, returning it with its length in bytes.
+    pub(crate) fn parse(data: &[u8]) -> Result<(Self, usize), String> {
+        let truncated = || "Gzip header is truncated".to_string();
+        let fixed = data.first_chunk::<10>().ok_or_else(truncated)?;
+        if fixed[..2] != GZIP_MAGIC {
+            return Err("Data is not in Gzip format".to_string());
+        }
+        if fixed[2] != METHOD_DEFLATE {
+            return Err(format!("Unsupported Gzip compression method: {}", fixed[2]));
+        }
+        let flags = fixed[3];
+        if flags & RESERVED_FLAGS != 0 {
+            return Err("Gzip header has reserved flags set".to_string());
+        }
+
+        let mut header = Self {
+            text: flags & FLAG_TEXT != 0,
+            mtime: u32::from_le_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]),
+            extra_flags: fixed[8],
+            os: fixed[9],
+            ..Self::default()
+        };
+        let mut rest = &data[10..];
+
+        if flags & FLAG_EXTRA != 0 {
+            let (length, tail) = rest.split_first_chunk::<2>().ok_or_else(truncated)?;
+            let length = u16::from_le_bytes(*length) as usize;
+            if tail.len() < length {
+                return Err(truncated());
+            }
+            header.extra = Some(tail[..length].to_vec());
+            rest = &tail[length..];
+        }
+        for (flag, field) in [
+            (FLAG_NAME, &mut header.file_name),
+            (FLAG_COMMENT, &mut header.comment),
+        ] {
+            if flags & flag != 0 {
+                let end = rest.iter().position(|&b| b == 0).ok_or_else(truncated)?;
+                *field = Some(rest[..end].to_vec());
+                rest = &rest[end + 1..];
+            }
+        }
+        if flags & FLAG_HEADER_CRC != 0 {
+            rest = rest.get(2..).ok_or_else(truncated)?;
+        }
+
+        Ok((header, data.len() - rest.len()))
+    }
+
+    /// Serialises the header; no header CRC is written.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut flags = 0;
+        if self.text {
+            flags |= FLAG_TEXT;
+        }
+        if self.extra.is_some() {
+            flags |= FLAG_EXTRA;
+        }
+        if self.file_name.is_some() {
+            flags |= FLAG_NAME;
+        }
+        if self.comment.is_some() {
+            flags |= FLAG_COMMENT;
+        }
+
+        let mut bytes = Vec::with_capacity(10);
+        bytes.extend_from_slice(&GZIP_MAGIC);
+        bytes.extend_from_slice(&[METHOD_DEFLATE, flags]);
+        bytes.extend_from_slice(&self.mtime.to_le_bytes());
+        bytes.extend_from_slice(&[self.extra_flags, self.os]);
+        if let Some(extra) = &self.extra {
+            bytes.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(extra);
+        }
+        for field in [&self.file_name, &self.comment].into_iter().flatten() {
+            bytes.extend_from_slice(field);
+            bytes.push(0);
+        }
+        bytes
+    }
+}
+
+/// Encodes a header string as ISO-8859-1, as RFC 1952 requires.
+pub(crate) fn latin1_field(value: &str, name: &str) -> Result<Vec<u8>, String> {
+    value
+        .chars()
+        .map(|c| match u8::try_from(u32::from(c)) {
+            Ok(0) => Err(format!("{} cannot contain NUL characters", name)),
+            Ok(byte) => Ok(byte),
+            Err(_) => Err(format!(
+                "{} contains '{}', which is not representable in ISO-8859-1",
+                name, c
+            )),
+        })
+        .collect()
+}
+
+/// Decodes an ISO-8859-1 header field.
+pub(crate) fn latin1_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Validates an extra field's length, which is stored in 16 bits.
+pub(crate) fn check_extra_length(extra: &[u8]) -> Result<(), String> {
+    if extra.len() > u16::MAX as usize {
+        return Err(format!("Gzip extra field is limited to {} bytes", u16::MAX));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_all_fields() {
+        let header = GzipHeader {
+            text: true,
+            mtime: 1_700_000_000,
+            extra_flags: 2,
+            os: 3,
+            extra: Some(b"AP\x02\x00hi".to_vec()),
+            file_name: Some(b"caf\xe9.log".to_vec()),
+            comment: Some(b"rotated".to_vec()),
+        };
+        let mut bytes = header.to_bytes();
+        let header_len = bytes.len();
+        bytes.extend_from_slice(b"deflate data follows");
+
+        assert_eq!(GzipHeader::parse(&bytes).unwrap(), (header, header_len));
+    }
+
+    #[test]
+    fn test_default_header_matches_flate2() {
+        assert_eq!(
+            GzipHeader::default().to_bytes(),
+            [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_header_crc() {
+        let mut bytes = GzipHeader::default().to_bytes();
+        bytes[3] = FLAG_HEADER_CRC;
+        bytes.extend_from_slice(&[0xaa, 0xbb, 0x03]);
+        assert_eq!(GzipHeader::parse(&bytes).unwrap().1, 12);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_headers() {
+        assert!(GzipHeader::parse(b"\x1f\x8b\x08").is_err());
+        assert!(GzipHeader::parse(b"PK\x03\x04 not gzip at all").is_err());
+
+        let mut unterminated = GzipHeader::default().to_bytes();
+        unterminated[3] = FLAG_NAME;
+        unterminated.extend_from_slice(b"name");
+        assert!(GzipHeader::parse(&unterminated).is_err());
+    }
+
+    #[test]
+    fn test_latin1_fields() {
+        assert_eq!(latin1_field("café", "File name").unwrap(), b"caf\xe9");
+        assert_eq!(latin1_string(b"caf\xe9"), "café");
+        assert!(latin1_field("日本", "File name").is_err());
+        assert!(latin1_field("a\0b", "Comment").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Gzip header metadata operations
+
+use base64::Engine as _;
+use serde_json::json;
+use std::os::raw::c_char;
+
+use super::gzip_header::{GzipHeader, check_extra_length, latin1_field, latin1_string};
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bytes, finish_string, read_optional_str};
+
+/// Read the header fields of a Gzip member as JSON
+///
+/// Returns an object with `fileName` and `comment` (strings or null), `mtime`
+/// (Unix seconds, 0 when not set), `os` (the RFC 1952 OS code: 0 FAT, 3 Unix,
+/// 11 NTFS, 255 unknown), `extra` (the extra field as Base64, or null) and
+/// `text`. Only the first member of concatenated Gzip data is read.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gzip_read_header(bytes: *const u8, length: usize) -> *mut c_char {
+    let result = unsafe { slice_from_raw(bytes, length, "Byte array") }.and_then(|data| {
+        let (header, _) = GzipHeader::parse(data)?;
+        Ok(json!({
+            "fileName": header.file_name.as_deref().map(latin1_string),
+            "comment": header.comment.as_deref().map(latin1_string),
+            "mtime": header.mtime,
+            "os": header.os,
+            "extra": header
+                .extra
+                .map(|extra| base64::engine::general_purpose::STANDARD.encode(extra)),
+            "text": header.text,
+        })
+        .to_string())
+    });
+
+    finish_string(result, "Gzip header")
+}
+
+/// Replace the header fields of Gzip data without recompressing it
+///
+/// The first member's header is rewritten with the given `file_name`,
+/// `comment`, `mtime`, `os` and `extra` field; a null pointer leaves that
+/// field out. The compressed data and trailer are copied unchanged. Output
+/// from `compress_bytes` already has a zero mtime and no file name, so this is
+/// mainly useful for normalising Gzip files from other tools into reproducible
+/// ones with `mtime` 0.
+///
+/// The file name and comment must be representable in ISO-8859-1.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `file_name` and `comment` are valid null-terminated C strings or null
+/// - `extra` is a valid pointer to at least `extra_length` bytes, or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gzip_set_header(
+    bytes: *const u8,
+    length: usize,
+    file_name: *const c_char,
+    comment: *const c_char,
+    mtime: u32,
+    os: u8,
+    extra: *const u8,
+    extra_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = unsafe { slice_from_raw(bytes, length, "Byte array") }.and_then(|data| {
+        let (existing, header_len) = GzipHeader::parse(data)?;
+        let extra = if extra.is_null() {
+            None
+        } else {
+            let extra = unsafe { slice_from_raw(extra, extra_length, "Extra field") }?;
+            check_extra_length(extra)?;
+            Some(extra.to_vec())
+        };
+
+        let header = GzipHeader {
+            mtime,
+            os,
+            extra,
+            file_name: read_optional_str(file_name, "File name")?
+                .map(|name| latin1_field(name, "File name"))
+                .transpose()?,
+            comment: read_optional_str(comment, "Comment")?
+                .map(|comment| latin1_field(comment, "Comment"))
+                .transpose()?,
+            ..existing
+        };
+
+        let mut output = header.to_bytes();
+        output.extend_from_slice(&data[header_len..]);
+        Ok(output)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::{compress_bytes, decompress_bytes, decompress_string};
+    use serde_json::Value;
+    use std::ffi::{CStr, CString};
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Option<Vec<u8>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        Some(bytes)
+    }
+
+    fn read_header(data: &[u8]) -> Value {
+        let ptr = unsafe { gzip_read_header(data.as_ptr(), data.len()) };
+        assert!(!ptr.is_null());
+        let json = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut len = 0usize;
+        take_bytes(
+            unsafe {
+                compress_bytes(
+                    data.as_ptr(),
+                    data.len(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap()
+    }
+
+    fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+        let mut len = 0usize;
+        take_bytes(
+            unsafe {
+                decompress_bytes(data.as_ptr(), data.len(), std::ptr::null(), 0, 0, &mut len)
+            },
+            len,
+        )
+    }
+
+    #[test]
+    fn test_read_default_header() {
+        let header = read_header(&gzip(b"payload"));
+        assert_eq!(header["fileName"], Value::Null);
+        assert_eq!(header["comment"], Value::Null);
+        assert_eq!(header["mtime"], 0);
+        assert_eq!(header["os"], 255);
+        assert_eq!(header["extra"], Value::Null);
+        assert_eq!(header["text"], false);
+    }
+
+    #[test]
+    fn test_set_header_round_trip() {
+        let original = gzip(b"payload");
+        let name = CString::new("résumé.txt").unwrap();
+        let comment = CString::new("nightly export").unwrap();
+        let extra = b"AB\x02\x00xy";
+        let mut len = 0usize;
+
+        let updated = take_bytes(
+            unsafe {
+                gzip_set_header(
+                    original.as_ptr(),
+                    original.len(),
+                    name.as_ptr(),
+                    comment.as_ptr(),
+                    1_700_000_000,
+                    3,
+                    extra.as_ptr(),
+                    extra.len(),
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap();
+
+        let header = read_header(&updated);
+        assert_eq!(header["fileName"], "résumé.txt");
+        assert_eq!(header["comment"], "nightly export");
+        assert_eq!(header["mtime"], 1_700_000_000);
+        assert_eq!(header["os"], 3);
+        assert_eq!(header["extra"], "QUICAHh5");
+        assert_eq!(gunzip(&updated).as_deref(), Some(&b"payload"[..]));
+
+        // Clearing everything again restores the original bytes
+        let cleared = take_bytes(
+            unsafe {
+                gzip_set_header(
+                    updated.as_ptr(),
+                    updated.len(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    0,
+                    255,
+                    std::ptr::null(),
+                    0,
+                    &mut len,
+                )
+            },
+            len,
+        )
+        .unwrap();
+        assert_eq!(cleared, original);
+    }
+
+    #[test]
+    fn test_set_header_rejects_non_latin1_name() {
+        let original = gzip(b"payload");
+        let name = CString::new("ファイル.txt").unwrap();
+        let mut len = 0usize;
+        let ptr = unsafe {
+            gzip_set_header(
+                original.as_ptr(),
+                original.len(),
+                name.as_ptr(),
+                std::ptr::null(),
+                0,
+                255,
+                std::ptr::null(),
+                0,
+                &mut len,
+            )
+        };
+        assert!(ptr.is_null());
+    }
+
+    #[test]
+    fn test_read_header_rejects_non_gzip() {
+        let data = b"plain text";
+        assert!(unsafe { gzip_read_header(data.as_ptr(), data.len()) }.is_null());
+    }
+
+    #[test]
+    fn test_concatenated_members_all_decompressed() {
+        let mut joined = gzip(b"first ");
+        joined.extend(gzip(b"second"));
+        assert_eq!(gunzip(&joined).as_deref(), Some(&b"first second"[..]));
+
+        let encoding = CString::new("UTF8").unwrap();
+        let ptr = unsafe { decompress_string(joined.as_ptr(), joined.len(), encoding.as_ptr()) };
+        assert!(!ptr.is_null());
+        let text = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        assert_eq!(text, "first second");
+    }
+}
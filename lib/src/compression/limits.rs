@@ -0,0 +1,184 @@
+//! Decompression bomb protection: bounds on decompressed size and expansion ratio
+
+use std::fmt;
+use std::io::{self, Read};
+
+/// Output cap applied when the caller passes 0, or uses an export without limit parameters.
+pub(crate) const DEFAULT_MAX_OUTPUT_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Limits on how much output decompressing one input may produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DecompressionLimits {
+    /// Maximum number of decompressed bytes.
+    pub max_output_size: u64,
+    /// Maximum decompressed-to-compressed size ratio; 0 disables the check.
+    pub max_ratio: u32,
+}
+
+impl Default for DecompressionLimits {
+    fn default() -> Self {
+        Self {
+            max_output_size: DEFAULT_MAX_OUTPUT_SIZE,
+            max_ratio: 0,
+        }
+    }
+}
+
+impl DecompressionLimits {
+    /// Builds limits from export arguments; a `max_output_size` of 0 selects the default.
+    pub(crate) fn new(max_output_size: u64, max_ratio: u32) -> Self {
+        Self {
+            max_output_size: match max_output_size {
+                0 => DEFAULT_MAX_OUTPUT_SIZE,
+                size => size,
+            },
+            max_ratio,
+        }
+    }
+
+    /// Checks that `output_len` decompressed bytes from `input_len` compressed
+    /// bytes stay within the limits.
+    pub(crate) fn check(&self, input_len: u64, output_len: u64) -> Result<(), String> {
+        if output_len > self.max_output_size {
+            return Err(format!(
+                "Decompression limit exceeded: output is larger than {} bytes",
+                self.max_output_size
+            ));
+        }
+        if self.max_ratio != 0 && output_len > input_len.saturating_mul(self.max_ratio as u64) {
+            return Err(format!(
+                "Decompression limit exceeded: output is more than {} times the {}-byte input",
+                self.max_ratio, input_len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads all output from a decoder over `input_len` compressed bytes,
+    /// stopping as soon as a limit is exceeded.
+    pub(crate) fn read_to_end<R: Read>(
+        &self,
+        decoder: R,
+        input_len: usize,
+    ) -> Result<Vec<u8>, String> {
+        let mut decompressed = Vec::new();
+        LimitedReader::new(decoder, *self, input_len as u64)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| error_message(&e, "Decompression failed"))?;
+        Ok(decompressed)
+    }
+}
+
+/// The I/O error payload raised by `LimitedReader`.
+#[derive(Debug)]
+struct LimitExceeded(String);
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// Formats an I/O error from a limited read, passing limit errors through
+/// unchanged so callers can tell them apart from corrupt input.
+pub(crate) fn error_message(error: &io::Error, context: &str) -> String {
+    match error
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<LimitExceeded>())
+    {
+        Some(LimitExceeded(message)) => message.clone(),
+        None => format!("{}: {}", context, error),
+    }
+}
+
+/// Wraps a decoder and fails once its output exceeds the limits.
+pub(crate) struct LimitedReader<R> {
+    inner: R,
+    limits: DecompressionLimits,
+    input_len: u64,
+    output_len: u64,
+}
+
+impl<R: Read> LimitedReader<R> {
+    pub(crate) fn new(inner: R, limits: DecompressionLimits, input_len: u64) -> Self {
+        Self {
+            inner,
+            limits,
+            input_len,
+            output_len: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Never ask for more than one byte past the size cap
+        let allowed = self
+            .limits
+            .max_output_size
+            .saturating_sub(self.output_len)
+            .saturating_add(1);
+        let len = buf
+            .len()
+            .min(usize::try_from(allowed).unwrap_or(usize::MAX));
+
+        let n = self.inner.read(&mut buf[..len])?;
+        self.output_len += n as u64;
+        self.limits
+            .check(self.input_len, self.output_len)
+            .map_err(|message| {
+                io::Error::new(io::ErrorKind::InvalidData, LimitExceeded(message))
+            })?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_selects_default_cap() {
+        assert_eq!(
+            DecompressionLimits::new(0, 0),
+            DecompressionLimits::default()
+        );
+        assert_eq!(DecompressionLimits::new(10, 5).max_output_size, 10);
+    }
+
+    #[test]
+    fn test_check_size_and_ratio() {
+        let limits = DecompressionLimits::new(1000, 10);
+        assert!(limits.check(100, 1000).is_ok());
+        assert!(
+            limits
+                .check(100, 1001)
+                .unwrap_err()
+                .contains("larger than 1000 bytes")
+        );
+        assert!(limits.check(10, 101).unwrap_err().contains("10 times"));
+        assert!(DecompressionLimits::new(1000, 0).check(1, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_read_to_end_stops_at_cap() {
+        let data = vec![7u8; 5000];
+        let limits = DecompressionLimits::new(4096, 0);
+        let error = limits.read_to_end(&data[..], 10).unwrap_err();
+        assert!(error.starts_with("Decompression limit exceeded"));
+
+        let limits = DecompressionLimits::new(5000, 0);
+        assert_eq!(limits.read_to_end(&data[..], 10).unwrap(), data);
+    }
+
+    #[test]
+    fn test_other_errors_keep_context() {
+        let error = io::Error::new(io::ErrorKind::InvalidData, "corrupt deflate stream");
+        assert_eq!(
+            error_message(&error, "Decompression failed"),
+            "Decompression failed: corrupt deflate stream"
+        );
+    }
+}
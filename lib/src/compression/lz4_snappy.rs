@@ -0,0 +1,172 @@
+//! LZ4 (frame and block) and Snappy (framed and raw) codecs
+//!
+//! Neither codec has a compression level. LZ4 blocks carry no length of their
+//! own, so block mode uses the common convention of a 4-byte little-endian
+//! uncompressed size before the block (as written by `lz4_flex` and many
+//! language bindings).
+
+use std::io::Write;
+
+use super::limits::DecompressionLimits;
+
+/// Compresses `data` into an LZ4 frame, or a size-prepended block.
+pub(crate) fn lz4_compress(data: &[u8], framed: bool) -> Result<Vec<u8>, String> {
+    if !framed {
+        return Ok(lz4_flex::block::compress_prepend_size(data));
+    }
+
+    let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+    encoder
+        .write_all(data)
+        .map_err(|e| format!("LZ4 compression failed: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("LZ4 compression failed: {}", e))
+}
+
+/// Decompresses an LZ4 frame, or a size-prepended block.
+pub(crate) fn lz4_decompress(
+    data: &[u8],
+    framed: bool,
+    limits: DecompressionLimits,
+) -> Result<Vec<u8>, String> {
+    if !framed {
+        // Check the size prefix before lz4_flex allocates that much
+        if let Some(prefix) = data.first_chunk::<4>() {
+            limits.check(data.len() as u64, u32::from_le_bytes(*prefix) as u64)?;
+        }
+        return lz4_flex::block::decompress_size_prepended(data)
+            .map_err(|e| format!("Decompression failed: {}", e));
+    }
+
+    limits.read_to_end(lz4_flex::frame::FrameDecoder::new(data), data.len())
+}
+
+/// Compresses `data` with the Snappy framing format, or as a raw Snappy block.
+pub(crate) fn snappy_compress(data: &[u8], framed: bool) -> Result<Vec<u8>, String> {
+    if !framed {
+        return snap::raw::Encoder::new()
+            .compress_vec(data)
+            .map_err(|e| format!("Snappy compression failed: {}", e));
+    }
+
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder
+        .write_all(data)
+        .map_err(|e| format!("Snappy compression failed: {}", e))?;
+    encoder
+        .into_inner()
+        .map_err(|e| format!("Snappy compression failed: {}", e.error()))
+}
+
+/// Decompresses Snappy framed data, or a raw Snappy block.
+pub(crate) fn snappy_decompress(
+    data: &[u8],
+    framed: bool,
+    limits: DecompressionLimits,
+) -> Result<Vec<u8>, String> {
+    if !framed {
+        // Check the declared length before the output buffer is allocated
+        let declared =
+            snap::raw::decompress_len(data).map_err(|e| format!("Decompression failed: {}", e))?;
+        limits.check(data.len() as u64, declared as u64)?;
+        return snap::raw::Decoder::new()
+            .decompress_vec(data)
+            .map_err(|e| format!("Decompression failed: {}", e));
+    }
+
+    limits.read_to_end(snap::read::FrameDecoder::new(data), data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        b"offset=42 key=user-17 value={\"clicks\":3}\n".repeat(200)
+    }
+
+    #[test]
+    fn test_lz4_round_trip_both_modes() {
+        let input = sample();
+        for framed in [true, false] {
+            let compressed = lz4_compress(&input, framed).unwrap();
+            assert!(compressed.len() < input.len() / 5);
+            assert_eq!(
+                lz4_decompress(&compressed, framed, DecompressionLimits::default()).unwrap(),
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_lz4_frame_magic_and_block_prefix() {
+        let frame = lz4_compress(b"hello", true).unwrap();
+        assert_eq!(&frame[..4], &[0x04, 0x22, 0x4d, 0x18]);
+
+        let block = lz4_compress(b"hello", false).unwrap();
+        assert_eq!(&block[..4], &5u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_forged_block_sizes_rejected_before_allocation() {
+        let limits = DecompressionLimits::new(1024 * 1024, 0);
+
+        let mut lz4_block = u32::MAX.to_le_bytes().to_vec();
+        lz4_block.extend_from_slice(&[0x10, 0x61]);
+        let error = lz4_decompress(&lz4_block, false, limits).unwrap_err();
+        assert!(
+            error.starts_with("Decompression limit exceeded"),
+            "{}",
+            error
+        );
+
+        // Raw Snappy varint length of 2^32 - 1
+        let snappy_block = [0xff, 0xff, 0xff, 0xff, 0x0f, 0x00];
+        let error = snappy_decompress(&snappy_block, false, limits).unwrap_err();
+        assert!(
+            error.starts_with("Decompression limit exceeded"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_snappy_round_trip_both_modes() {
+        let input = sample();
+        for framed in [true, false] {
+            let compressed = snappy_compress(&input, framed).unwrap();
+            assert!(compressed.len() < input.len() / 5);
+            assert_eq!(
+                snappy_decompress(&compressed, framed, DecompressionLimits::default()).unwrap(),
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_snappy_known_raw_block() {
+        // Varint length 5 followed by a 5-byte literal
+        let raw = [0x05, 0x10, b'h', b'e', b'l', b'l', b'o'];
+        assert_eq!(
+            snappy_decompress(&raw, false, DecompressionLimits::default()).unwrap(),
+            b"hello"
+        );
+        assert_eq!(snappy_compress(b"hello", false).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_snappy_framed_stream_identifier() {
+        let framed = snappy_compress(b"hello", true).unwrap();
+        assert_eq!(&framed[..10], b"\xff\x06\x00\x00sNaPpY");
+    }
+
+    #[test]
+    fn test_mode_mismatch_fails() {
+        let frame = lz4_compress(b"some data here", true).unwrap();
+        assert!(lz4_decompress(&frame, false, DecompressionLimits::default()).is_err());
+
+        let framed = snappy_compress(b"some data here", true).unwrap();
+        assert!(snappy_decompress(&framed, false, DecompressionLimits::default()).is_err());
+    }
+}
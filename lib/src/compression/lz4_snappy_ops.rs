@@ -0,0 +1,461 @@
+//! LZ4 and Snappy byte and string compression operations
+
+use std::os::raw::c_char;
+
+use super::compress::encode_input;
+use super::decompress::decoded_to_string;
+use super::limits::DecompressionLimits;
+use super::lz4_snappy::{lz4_compress, lz4_decompress, snappy_compress, snappy_decompress};
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bytes, finish_string, read_optional_str};
+
+type Compressor = fn(&[u8], bool) -> Result<Vec<u8>, String>;
+type Decompressor = fn(&[u8], bool, DecompressionLimits) -> Result<Vec<u8>, String>;
+
+/// Compresses a caller-supplied byte buffer with `codec`.
+unsafe fn compress_bytes_with(
+    codec: Compressor,
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result =
+        unsafe { slice_from_raw(bytes, length, "Byte array") }.and_then(|data| codec(data, framed));
+
+    finish_bytes(result, out_length)
+}
+
+/// Decompresses a caller-supplied byte buffer with `codec` within the given limits.
+unsafe fn decompress_bytes_with(
+    codec: Decompressor,
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    limits: DecompressionLimits,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = unsafe { slice_from_raw(bytes, length, "Byte array") }
+        .and_then(|data| codec(data, framed, limits));
+
+    finish_bytes(result, out_length)
+}
+
+/// Encodes a string with the named encoding and compresses it with `codec`.
+fn compress_string_with(
+    codec: Compressor,
+    input: *const c_char,
+    encoding: *const c_char,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = encode_input(input, encoding).and_then(|bytes| codec(&bytes, framed));
+
+    finish_bytes(result, out_length)
+}
+
+/// Decompresses with `codec` and converts the result to a string.
+unsafe fn decompress_string_with(
+    codec: Decompressor,
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    framed: bool,
+    lenient: bool,
+    limits: DecompressionLimits,
+) -> *mut c_char {
+    let result = read_optional_str(encoding, "Encoding").and_then(|encoding| {
+        let encoding = encoding.ok_or_else(|| "Encoding pointer is null".to_string())?;
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        let decompressed = codec(data, framed, limits)?;
+        decoded_to_string(&decompressed, encoding, lenient)
+    });
+
+    finish_string(result, "decompressed result")
+}
+
+/// Compress a byte array with LZ4
+///
+/// With `framed` set the output is a standard LZ4 frame (`.lz4`, Kafka);
+/// otherwise it is a single LZ4 block preceded by its uncompressed size as a
+/// 4-byte little-endian integer.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lz4_compress_bytes(
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    unsafe { compress_bytes_with(lz4_compress, bytes, length, framed, out_length) }
+}
+
+/// Decompress an LZ4 frame or size-prefixed LZ4 block to a byte array
+///
+/// `max_output_size` (0 for the default of 256 MiB) and `max_ratio` (0 for
+/// none) bound the output as in `decompress_string_with_format`; a block's
+/// size prefix is checked before any output is allocated.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lz4_decompress_bytes(
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe { decompress_bytes_with(lz4_decompress, bytes, length, framed, limits, out_length) }
+}
+
+/// Compress a string with LZ4
+///
+/// The string is converted to bytes with `encoding` first; `framed` selects
+/// the frame or size-prefixed block layout as in `lz4_compress_bytes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lz4_compress_string(
+    input: *const c_char,
+    encoding: *const c_char,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    compress_string_with(lz4_compress, input, encoding, framed, out_length)
+}
+
+/// Decompress LZ4 data to a string
+///
+/// `framed`, `max_output_size` and `max_ratio` are as in `lz4_decompress_bytes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `encoding` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lz4_decompress_string(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe {
+        decompress_string_with(
+            lz4_decompress,
+            bytes,
+            length,
+            encoding,
+            framed,
+            false,
+            limits,
+        )
+    }
+}
+
+/// Decompress LZ4 data to a string with Latin-1 fallback
+///
+/// Lenient version of `lz4_decompress_string`, see `decompress_string_lenient`.
+///
+/// # Safety
+/// Same safety requirements as `lz4_decompress_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lz4_decompress_string_lenient(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe {
+        decompress_string_with(
+            lz4_decompress,
+            bytes,
+            length,
+            encoding,
+            framed,
+            true,
+            limits,
+        )
+    }
+}
+
+/// Compress a byte array with Snappy
+///
+/// With `framed` set the output uses the Snappy framing format (`.sz`, with
+/// CRC-32C checksums); otherwise it is a single raw Snappy block.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn snappy_compress_bytes(
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    unsafe { compress_bytes_with(snappy_compress, bytes, length, framed, out_length) }
+}
+
+/// Decompress framed or raw Snappy data to a byte array
+///
+/// `max_output_size` and `max_ratio` bound the output as in
+/// `lz4_decompress_bytes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn snappy_decompress_bytes(
+    bytes: *const u8,
+    length: usize,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe { decompress_bytes_with(snappy_decompress, bytes, length, framed, limits, out_length) }
+}
+
+/// Compress a string with Snappy
+///
+/// The string is converted to bytes with `encoding` first; `framed` selects
+/// the framing format or a raw block as in `snappy_compress_bytes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn snappy_compress_string(
+    input: *const c_char,
+    encoding: *const c_char,
+    framed: bool,
+    out_length: *mut usize,
+) -> *mut u8 {
+    compress_string_with(snappy_compress, input, encoding, framed, out_length)
+}
+
+/// Decompress Snappy data to a string
+///
+/// `framed`, `max_output_size` and `max_ratio` are as in `snappy_decompress_bytes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `encoding` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn snappy_decompress_string(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe {
+        decompress_string_with(
+            snappy_decompress,
+            bytes,
+            length,
+            encoding,
+            framed,
+            false,
+            limits,
+        )
+    }
+}
+
+/// Decompress Snappy data to a string with Latin-1 fallback
+///
+/// Lenient version of `snappy_decompress_string`, see `decompress_string_lenient`.
+///
+/// # Safety
+/// Same safety requirements as `snappy_decompress_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn snappy_decompress_string_lenient(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    framed: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    unsafe {
+        decompress_string_with(
+            snappy_decompress,
+            bytes,
+            length,
+            encoding,
+            framed,
+            true,
+            limits,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Option<Vec<u8>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        Some(bytes)
+    }
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    #[test]
+    fn test_lz4_bytes_round_trip() {
+        let input: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        for framed in [true, false] {
+            let mut len = 0usize;
+            let compressed = take_bytes(
+                unsafe { lz4_compress_bytes(input.as_ptr(), input.len(), framed, &mut len) },
+                len,
+            )
+            .unwrap();
+            let restored = take_bytes(
+                unsafe {
+                    lz4_decompress_bytes(
+                        compressed.as_ptr(),
+                        compressed.len(),
+                        framed,
+                        0,
+                        0,
+                        &mut len,
+                    )
+                },
+                len,
+            )
+            .unwrap();
+            assert_eq!(restored, input);
+        }
+    }
+
+    #[test]
+    fn test_snappy_string_round_trip() {
+        let input = CString::new("Snappy string ✓").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        for framed in [true, false] {
+            let mut len = 0usize;
+            let compressed = take_bytes(
+                unsafe {
+                    snappy_compress_string(input.as_ptr(), encoding.as_ptr(), framed, &mut len)
+                },
+                len,
+            )
+            .unwrap();
+            let decoded = take_string(unsafe {
+                snappy_decompress_string(
+                    compressed.as_ptr(),
+                    compressed.len(),
+                    encoding.as_ptr(),
+                    framed,
+                    0,
+                    0,
+                )
+            });
+            assert_eq!(decoded.as_deref(), Some("Snappy string ✓"));
+        }
+    }
+
+    #[test]
+    fn test_lz4_decompress_string_strict_vs_lenient() {
+        let binary = [0xffu8, 0xfe, 0x41];
+        let mut len = 0usize;
+        let compressed = take_bytes(
+            unsafe { lz4_compress_bytes(binary.as_ptr(), binary.len(), true, &mut len) },
+            len,
+        )
+        .unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+
+        let strict = unsafe {
+            lz4_decompress_string(
+                compressed.as_ptr(),
+                compressed.len(),
+                encoding.as_ptr(),
+                true,
+                0,
+                0,
+            )
+        };
+        assert!(strict.is_null());
+
+        let lenient = take_string(unsafe {
+            lz4_decompress_string_lenient(
+                compressed.as_ptr(),
+                compressed.len(),
+                encoding.as_ptr(),
+                true,
+                0,
+                0,
+            )
+        });
+        assert_eq!(lenient.as_deref(), Some("ÿþA"));
+    }
+
+    #[test]
+    fn test_snappy_decompress_bytes_invalid_returns_null() {
+        let garbage = [0xffu8; 8];
+        let mut len = 9usize;
+        let result = unsafe {
+            snappy_decompress_bytes(garbage.as_ptr(), garbage.len(), false, 0, 0, &mut len)
+        };
+        assert!(result.is_null());
+        assert_eq!(len, 0);
+    }
+}
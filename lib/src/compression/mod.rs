@@ -1,9 +1,50 @@
-//! Compression and decompression functions using Gzip
+//! Compression and decompression functions (Gzip, zlib, raw DEFLATE, Zstandard, Brotli,
+//! LZ4, Snappy, bzip2, xz and LZMA)
 
+mod base64_compress;
 mod base64_decompress;
+mod brotli_codec;
+mod brotli_ops;
+mod bytes_ops;
+mod bzip2_xz;
+mod codec;
 mod compress;
 mod decompress;
+mod deflate;
+mod detect;
+mod detect_ops;
+mod file_ops;
+mod gzip_header;
+mod gzip_header_ops;
+mod limits;
+mod lz4_snappy;
+mod lz4_snappy_ops;
+mod options;
+mod stream;
+mod stream_ops;
+mod zstandard;
+mod zstandard_ops;
 
-pub use base64_decompress::{base64_to_decompressed_string, base64_to_decompressed_string_lenient};
-pub use compress::compress_string;
-pub use decompress::{decompress_string, decompress_string_lenient};
+pub use base64_compress::string_to_compressed_base64;
+pub use base64_decompress::{
+    base64_to_decompressed_string, base64_to_decompressed_string_lenient,
+    base64_to_decompressed_string_with_format,
+};
+pub use brotli_ops::{brotli_compress_string, brotli_decompress_string};
+pub use bytes_ops::{compress_bytes, decompress_bytes};
+pub use compress::{compress_string, compress_string_with_options};
+pub use decompress::{decompress_string, decompress_string_lenient, decompress_string_with_format};
+pub use detect_ops::detect_compression;
+pub use file_ops::{compress_file, decompress_file};
+pub use gzip_header_ops::{gzip_read_header, gzip_set_header};
+pub use lz4_snappy_ops::{
+    lz4_compress_bytes, lz4_compress_string, lz4_decompress_bytes, lz4_decompress_string,
+    lz4_decompress_string_lenient, snappy_compress_bytes, snappy_compress_string,
+    snappy_decompress_bytes, snappy_decompress_string, snappy_decompress_string_lenient,
+};
+pub use stream_ops::{
+    compressor_create, compressor_finish, compressor_free, compressor_read, compressor_write,
+    decompressor_create, decompressor_finish, decompressor_free, decompressor_read,
+    decompressor_write,
+};
+pub use zstandard_ops::{zstd_compress_string, zstd_decompress_string, zstd_train_dictionary};
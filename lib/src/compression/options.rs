@@ -0,0 +1,161 @@
+//! Compression level and strategy options
+
+use miniz_oxide::deflate::core::CompressionStrategy;
+
+use super::codec::Format;
+
+/// Valid levels and named presets for one compression format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LevelSpec {
+    pub min: i32,
+    pub max: i32,
+    pub fastest: i32,
+    pub default: i32,
+    pub best: i32,
+}
+
+/// DEFLATE-based formats: 0 (store) to 9, defaulting to zlib's 6.
+pub(crate) const DEFLATE_LEVELS: LevelSpec = LevelSpec {
+    min: 0,
+    max: 9,
+    fastest: 1,
+    default: 6,
+    best: 9,
+};
+
+/// Encoder settings shared by the compression exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompressionOptions {
+    pub level: i32,
+    pub strategy: CompressionStrategy,
+}
+
+impl Default for CompressionOptions {
+    /// DEFLATE defaults, as used by `compress_string`.
+    fn default() -> Self {
+        Self {
+            level: DEFLATE_LEVELS.default,
+            strategy: CompressionStrategy::Default,
+        }
+    }
+}
+
+impl CompressionOptions {
+    /// Builds options for `format` from optional level and strategy names;
+    /// `None` keeps the format's default.
+    pub(crate) fn parse(
+        format: Format,
+        level: Option<&str>,
+        strategy: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut options = Self {
+            level: format.level_spec().map_or(0, |spec| spec.default),
+            ..Self::default()
+        };
+        if let Some(level) = level {
+            options.level = parse_level(level, format)?;
+        }
+        if let Some(strategy) = strategy {
+            if !format.supports_strategy() {
+                return Err(format!(
+                    "Compression strategy is only supported for GZIP, ZLIB and DEFLATE, not {}",
+                    format.name()
+                ));
+            }
+            options.strategy = parse_strategy(strategy)?;
+        }
+        Ok(options)
+    }
+}
+
+/// Parses a compression level for `format`: a number in its range or a named preset.
+pub(crate) fn parse_level(level: &str, format: Format) -> Result<i32, String> {
+    let Some(spec) = format.level_spec() else {
+        return Err(format!(
+            "Compression level is not supported for {}",
+            format.name()
+        ));
+    };
+    let trimmed = level.trim();
+    if let Ok(value) = trimmed.parse::<i32>()
+        && (spec.min..=spec.max).contains(&value)
+    {
+        return Ok(value);
+    }
+
+    match trimmed.to_uppercase().as_str() {
+        "NONE" | "STORE" if spec.min == 0 => Ok(0),
+        "FASTEST" => Ok(spec.fastest),
+        "DEFAULT" => Ok(spec.default),
+        "BEST" | "SMALLEST" => Ok(spec.best),
+        _ => Err(format!(
+            "Invalid compression level for {}: {}. Expected {}-{} or {}FASTEST, DEFAULT, BEST",
+            format.name(),
+            level,
+            spec.min,
+            spec.max,
+            if spec.min == 0 { "NONE, " } else { "" }
+        )),
+    }
+}
+
+/// Parses a DEFLATE strategy name.
+pub(crate) fn parse_strategy(strategy: &str) -> Result<CompressionStrategy, String> {
+    let normalized: String = strategy
+        .trim()
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+
+    match normalized.as_str() {
+        "DEFAULT" => Ok(CompressionStrategy::Default),
+        "FILTERED" => Ok(CompressionStrategy::Filtered),
+        "HUFFMANONLY" => Ok(CompressionStrategy::HuffmanOnly),
+        "RLE" => Ok(CompressionStrategy::RLE),
+        "FIXED" => Ok(CompressionStrategy::Fixed),
+        _ => Err(format!(
+            "Invalid compression strategy: {}. Supported: DEFAULT, FILTERED, HUFFMAN_ONLY, RLE, FIXED",
+            strategy
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level_digits_and_presets() {
+        assert_eq!(parse_level("0", Format::Gzip).unwrap(), 0);
+        assert_eq!(parse_level("9", Format::Gzip).unwrap(), 9);
+        assert_eq!(parse_level("fastest", Format::Zlib).unwrap(), 1);
+        assert_eq!(parse_level("Best", Format::Deflate).unwrap(), 9);
+        assert_eq!(parse_level("DEFAULT", Format::Gzip).unwrap(), 6);
+        assert!(parse_level("10", Format::Gzip).is_err());
+        assert!(parse_level("-1", Format::Gzip).is_err());
+        assert!(parse_level("max", Format::Gzip).is_err());
+        assert!(parse_level("1", Format::Lz4).is_err());
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(
+            parse_strategy("huffman_only").unwrap(),
+            CompressionStrategy::HuffmanOnly
+        );
+        assert_eq!(parse_strategy("RLE").unwrap(), CompressionStrategy::RLE);
+        assert!(parse_strategy("lz77").is_err());
+    }
+
+    #[test]
+    fn test_options_parse_defaults() {
+        assert_eq!(
+            CompressionOptions::parse(Format::Gzip, None, None).unwrap(),
+            CompressionOptions::default()
+        );
+        let options = CompressionOptions::parse(Format::Gzip, Some("1"), Some("filtered")).unwrap();
+        assert_eq!(options.level, 1);
+        assert_eq!(options.strategy, CompressionStrategy::Filtered);
+    }
+}
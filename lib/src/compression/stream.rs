@@ -0,0 +1,251 @@
+//! Streaming encoders and decoders for every supported format
+
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use std::io::{self, Cursor, Read, Write};
+
+use super::brotli_codec::DEFAULT_WINDOW_BITS;
+use super::bzip2_xz::{
+    bzip2_compression, lzma_alone_decoder, lzma_alone_encoder, xz_decoder, xz_preset,
+};
+use super::codec::{Format, compress_data, decompress_data};
+use super::deflate::{DeflateEncoder, Framing};
+use super::limits::DecompressionLimits;
+use super::options::CompressionOptions;
+
+/// Buffer size used by codecs that take one.
+const BUFFER_SIZE: usize = 32 * 1024;
+
+/// A compressor that accepts input through `Write` and produces output into `W`.
+pub(crate) trait StreamEncoder<W>: Write {
+    /// The writer receiving compressed output.
+    fn get_mut(&mut self) -> &mut W;
+
+    /// Writes any buffered data and trailer, returning the writer.
+    fn finish(self: Box<Self>) -> io::Result<W>;
+}
+
+impl<W: Write> StreamEncoder<W> for DeflateEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        DeflateEncoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        DeflateEncoder::finish(*self)
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for zstd::stream::write::Encoder<'static, W> {
+    fn get_mut(&mut self) -> &mut W {
+        zstd::stream::write::Encoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        zstd::stream::write::Encoder::finish(*self)
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for brotli::CompressorWriter<W> {
+    fn get_mut(&mut self) -> &mut W {
+        brotli::CompressorWriter::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        Ok(self.into_inner())
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for lz4_flex::frame::FrameEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        lz4_flex::frame::FrameEncoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        lz4_flex::frame::FrameEncoder::finish(*self).map_err(io::Error::other)
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for snap::write::FrameEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        snap::write::FrameEncoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        self.into_inner()
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for bzip2::write::BzEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        bzip2::write::BzEncoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        bzip2::write::BzEncoder::finish(*self)
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for liblzma::write::XzEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        liblzma::write::XzEncoder::get_mut(self)
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<W> {
+        liblzma::write::XzEncoder::finish(*self)
+    }
+}
+
+/// Collects all input and compresses it in one go, for block formats that
+/// cannot be produced incrementally (LZ4 blocks, raw Snappy).
+struct BufferedEncoder<W: Write> {
+    writer: W,
+    format: Format,
+    options: CompressionOptions,
+    input: Vec<u8>,
+}
+
+impl<W: Write> Write for BufferedEncoder<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.input.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: Write> StreamEncoder<W> for BufferedEncoder<W> {
+    fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    fn finish(mut self: Box<Self>) -> io::Result<W> {
+        let compressed =
+            compress_data(&self.input, self.format, self.options).map_err(io::Error::other)?;
+        self.writer.write_all(&compressed)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Creates a streaming compressor for `format` that writes to `writer`.
+pub(crate) fn encoder<W: Write + 'static>(
+    writer: W,
+    format: Format,
+    options: CompressionOptions,
+) -> Result<Box<dyn StreamEncoder<W>>, String> {
+    let level = options.level;
+    Ok(match format {
+        Format::Gzip => Box::new(DeflateEncoder::new(writer, Framing::Gzip, options)),
+        Format::Zlib => Box::new(DeflateEncoder::new(writer, Framing::Zlib, options)),
+        Format::Deflate => Box::new(DeflateEncoder::new(writer, Framing::Raw, options)),
+        Format::Zstd => Box::new(
+            zstd::stream::write::Encoder::new(writer, level)
+                .map_err(|e| format!("Failed to initialize Zstandard compressor: {}", e))?,
+        ),
+        Format::Brotli => Box::new(brotli::CompressorWriter::new(
+            writer,
+            BUFFER_SIZE,
+            level as u32,
+            DEFAULT_WINDOW_BITS,
+        )),
+        Format::Lz4 => Box::new(lz4_flex::frame::FrameEncoder::new(writer)),
+        Format::Snappy => Box::new(snap::write::FrameEncoder::new(writer)),
+        Format::Bzip2 => Box::new(bzip2::write::BzEncoder::new(
+            writer,
+            bzip2_compression(level),
+        )),
+        Format::Xz => Box::new(liblzma::write::XzEncoder::new(writer, xz_preset(level))),
+        Format::Lzma => Box::new(liblzma::write::XzEncoder::new_stream(
+            writer,
+            lzma_alone_encoder(level)?,
+        )),
+        Format::Lz4Block | Format::SnappyRaw => Box::new(BufferedEncoder {
+            writer,
+            format,
+            options,
+            input: Vec::new(),
+        }),
+    })
+}
+
+/// Creates a streaming decompressor for `format` that reads from `reader`.
+///
+/// Block formats (LZ4 blocks, raw Snappy) are read fully and decoded up front
+/// within `limits`; for the others the caller bounds the output, usually with
+/// a `LimitedReader`.
+pub(crate) fn decoder<'a, R: Read + 'a>(
+    mut reader: R,
+    format: Format,
+    limits: DecompressionLimits,
+) -> Result<Box<dyn Read + 'a>, String> {
+    Ok(match format {
+        Format::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        Format::Zlib => Box::new(ZlibDecoder::new(reader)),
+        Format::Deflate => Box::new(DeflateDecoder::new(reader)),
+        Format::Zstd => Box::new(
+            zstd::stream::read::Decoder::new(reader)
+                .map_err(|e| format!("Failed to initialize Zstandard decoder: {}", e))?,
+        ),
+        Format::Brotli => Box::new(brotli::Decompressor::new(reader, BUFFER_SIZE)),
+        Format::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(reader)),
+        Format::Snappy => Box::new(snap::read::FrameDecoder::new(reader)),
+        Format::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        Format::Xz => Box::new(liblzma::read::XzDecoder::new_stream(
+            reader,
+            xz_decoder(limits)?,
+        )),
+        Format::Lzma => Box::new(liblzma::read::XzDecoder::new_stream(
+            reader,
+            lzma_alone_decoder(limits)?,
+        )),
+        Format::Lz4Block | Format::SnappyRaw => {
+            let mut data = Vec::new();
+            reader
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read input: {}", e))?;
+            Box::new(Cursor::new(decompress_data(&data, format, limits)?))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::codec::tests::ALL_FORMATS;
+
+    #[test]
+    fn test_stream_round_trip_every_format() {
+        let input: Vec<u8> = (0..50_000u32).map(|i| (i % 7 + i / 1000) as u8).collect();
+
+        for format in ALL_FORMATS {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let mut encoder = encoder(Vec::new(), format, options).unwrap();
+            for chunk in input.chunks(4093) {
+                encoder.write_all(chunk).unwrap();
+            }
+            let compressed = encoder.finish().unwrap();
+
+            let mut output = Vec::new();
+            decoder(&compressed[..], format, DecompressionLimits::default())
+                .unwrap()
+                .read_to_end(&mut output)
+                .unwrap();
+            assert_eq!(output, input, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_streamed_output_matches_buffered() {
+        let input = b"stream and buffer agree ".repeat(100);
+        for format in [Format::Gzip, Format::Bzip2, Format::Xz] {
+            let options = CompressionOptions::parse(format, None, None).unwrap();
+            let mut encoder = encoder(Vec::new(), format, options).unwrap();
+            encoder.write_all(&input).unwrap();
+            let streamed = encoder.finish().unwrap();
+
+            assert_eq!(streamed, compress_data(&input, format, options).unwrap());
+        }
+    }
+}
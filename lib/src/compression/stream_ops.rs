@@ -0,0 +1,693 @@
+//! Streaming compressor and decompressor handles
+//!
+//! A handle is created with a format, fed input in chunks with `*_write`,
+//! drained with `*_read` and completed with `*_finish`, so a stream of any
+//! size can be processed with only a chunk of it in memory at a time.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+use super::codec::Format;
+use super::compress::read_compression_args;
+use super::detect::DecompressionFormat;
+use super::limits::{DecompressionLimits, error_message};
+use super::stream::{self, StreamEncoder};
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bool, finish_bytes, read_optional_str};
+
+/// Size of the buffer decoded output is read through.
+const READ_BUFFER_SIZE: usize = 32 * 1024;
+
+/// Input collected before `AUTO` detection runs, enough for the zlib and
+/// LZMA probe decodes.
+const DETECT_LENGTH: usize = 64 * 1024;
+
+/// Records the error and returns null, or hands ownership of a new handle to the caller.
+fn finish_handle<T>(result: Result<T, String>) -> *mut T {
+    match result {
+        Ok(handle) => {
+            crate::error::clear_error();
+            Box::into_raw(Box::new(handle))
+        }
+        Err(e) => {
+            crate::error::set_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Borrows the object behind a handle passed in by the caller.
+///
+/// # Safety
+/// `handle` must be null or a live pointer returned by the matching `*_create`.
+unsafe fn handle_mut<'a, T>(handle: *mut T, name: &str) -> Result<&'a mut T, String> {
+    unsafe { handle.as_mut() }.ok_or_else(|| format!("{} handle is null", name))
+}
+
+/// State behind a compressor handle.
+pub struct StreamCompressor {
+    /// `None` once finished or after an error.
+    encoder: Option<Box<dyn StreamEncoder<Vec<u8>>>>,
+}
+
+impl StreamCompressor {
+    fn encoder(&mut self) -> Result<&mut Box<dyn StreamEncoder<Vec<u8>>>, String> {
+        self.encoder.as_mut().ok_or_else(compressor_finished)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        let result = self.encoder()?.write_all(data);
+        result.map_err(|e| {
+            self.encoder = None;
+            format!("Compression failed: {}", e)
+        })
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, String> {
+        Ok(std::mem::take(self.encoder()?.get_mut()))
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, String> {
+        let encoder = self.encoder.take().ok_or_else(compressor_finished)?;
+        encoder
+            .finish()
+            .map_err(|e| format!("Compression failed: {}", e))
+    }
+}
+
+fn compressor_finished() -> String {
+    "Compressor has already been finished".to_string()
+}
+
+/// Compressed input written to a decompressor that its decoder has not read yet.
+#[derive(Clone, Default)]
+struct PendingInput(Rc<RefCell<PendingState>>);
+
+#[derive(Default)]
+struct PendingState {
+    data: VecDeque<u8>,
+    finished: bool,
+}
+
+impl PendingInput {
+    fn len(&self) -> usize {
+        self.0.borrow().data.len()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0.borrow().finished
+    }
+}
+
+impl Read for PendingInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.0.borrow_mut();
+        if state.data.is_empty() && !state.finished {
+            // Not the end of the stream: the decoder stops until more is written
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        state.data.read(buf)
+    }
+}
+
+/// Compressed bytes that must be queued before decoding may continue.
+///
+/// Most decoders can stop at any point when input runs out and resume later,
+/// but LZ4 frames and Snappy streams read each block with `read_exact`, which
+/// loses data if interrupted, so a whole block (4 MiB for LZ4, about 75 KiB
+/// for Snappy) is kept queued until the input is finished.
+fn lookahead(format: Format) -> usize {
+    match format {
+        Format::Lz4 => 4 * 1024 * 1024 + 64,
+        Format::Snappy => 128 * 1024,
+        _ => 0,
+    }
+}
+
+/// A decoder reading from a decompressor's queued input.
+struct QueuedDecoder {
+    reader: Box<dyn Read>,
+    /// See `lookahead`; 0 when decoding may run until the queue is empty.
+    lookahead: usize,
+}
+
+/// State behind a decompressor handle.
+pub struct StreamDecompressor {
+    format: DecompressionFormat,
+    limits: DecompressionLimits,
+    input: PendingInput,
+    /// Created once the format is known and enough input is queued.
+    decoder: Option<QueuedDecoder>,
+    input_len: u64,
+    output_len: u64,
+    /// Set once finished or after an error.
+    closed: bool,
+}
+
+impl StreamDecompressor {
+    fn check_open(&self) -> Result<(), String> {
+        if self.closed {
+            return Err("Decompressor has already been finished".to_string());
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        self.check_open()?;
+        self.input.0.borrow_mut().data.extend(data);
+        self.input_len += data.len() as u64;
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, String> {
+        self.check_open()?;
+        let result = self.decode();
+        if result.is_err() {
+            self.close();
+        }
+        result
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, String> {
+        self.check_open()?;
+        self.input.0.borrow_mut().finished = true;
+        let result = self.decode();
+        self.close();
+        result
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+        self.decoder = None;
+        self.input = PendingInput::default();
+    }
+
+    /// Creates the decoder once the format can be resolved. Block formats
+    /// and detection wait for more input; uncompressed data under `AUTO`
+    /// passes straight through.
+    fn create_decoder(&self) -> Result<Option<QueuedDecoder>, String> {
+        let finished = self.input.is_finished();
+        let format = match self.format {
+            DecompressionFormat::Fixed(format) => Some(format),
+            DecompressionFormat::Auto if finished || self.input.len() >= DETECT_LENGTH => {
+                let mut queued = self.input.0.borrow_mut();
+                let data = queued.data.make_contiguous();
+                self.format.resolve(&data[..data.len().min(DETECT_LENGTH)])
+            }
+            DecompressionFormat::Auto => return Ok(None),
+        };
+
+        match format {
+            Some(Format::Lz4Block | Format::SnappyRaw) if !finished => Ok(None),
+            Some(format) => Ok(Some(QueuedDecoder {
+                reader: stream::decoder(self.input.clone(), format, self.limits)?,
+                lookahead: lookahead(format),
+            })),
+            None => Ok(Some(QueuedDecoder {
+                reader: Box::new(self.input.clone()),
+                lookahead: 0,
+            })),
+        }
+    }
+
+    /// Reads all output the queued input can produce without running a
+    /// decoder past the input written so far.
+    fn decode(&mut self) -> Result<Vec<u8>, String> {
+        if self.decoder.is_none() {
+            self.decoder = self.create_decoder()?;
+        }
+        let Some(decoder) = self.decoder.as_mut() else {
+            return Ok(Vec::new());
+        };
+
+        let mut output = Vec::new();
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+        loop {
+            if decoder.lookahead > 0
+                && !self.input.is_finished()
+                && self.input.len() <= decoder.lookahead
+            {
+                break;
+            }
+            match decoder.reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    self.output_len += n as u64;
+                    self.limits.check(self.input_len, self.output_len)?;
+                    output.extend_from_slice(&buffer[..n]);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(error_message(&e, "Decompression failed")),
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Create a streaming compressor
+///
+/// `format` and `level` accept the same values as `compress_file` (null for
+/// GZIP at its default level). Feed input with `compressor_write`, collect
+/// output with `compressor_read` as it becomes available and end the stream
+/// with `compressor_finish`. LZ4 blocks and raw Snappy have no streaming form,
+/// so for those the whole input is held until `compressor_finish`.
+///
+/// Returns null on error.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `format` and `level` are valid null-terminated C strings or null
+/// - The returned handle must be freed using `compressor_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compressor_create(
+    format: *const c_char,
+    level: *const c_char,
+) -> *mut StreamCompressor {
+    let result = read_compression_args(format, level, std::ptr::null())
+        .and_then(|(format, options)| stream::encoder(Vec::new(), format, options))
+        .map(|encoder| StreamCompressor {
+            encoder: Some(encoder),
+        });
+
+    finish_handle(result)
+}
+
+/// Write a chunk of input to a compressor
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `compressor_create` that has not been freed
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compressor_write(
+    handle: *mut StreamCompressor,
+    bytes: *const u8,
+    length: usize,
+) -> bool {
+    let result = unsafe { handle_mut(handle, "Compressor") }.and_then(|compressor| {
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        compressor.write(data)
+    });
+
+    finish_bool(result)
+}
+
+/// Take the compressed output produced so far
+///
+/// Encoders buffer internally, so the result is often empty after small
+/// writes; the rest is returned by `compressor_finish`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `compressor_create` that has not been freed
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compressor_read(
+    handle: *mut StreamCompressor,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result =
+        unsafe { handle_mut(handle, "Compressor") }.and_then(|compressor| compressor.read());
+    finish_bytes(result, out_length)
+}
+
+/// End the compressed stream and take the remaining output
+///
+/// The result includes any output not yet taken with `compressor_read`, and
+/// the trailer. The handle accepts no more input afterwards but must still be
+/// freed.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `compressor_create` that has not been freed
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compressor_finish(
+    handle: *mut StreamCompressor,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result =
+        unsafe { handle_mut(handle, "Compressor") }.and_then(|compressor| compressor.finish());
+    finish_bytes(result, out_length)
+}
+
+/// Free a compressor handle
+///
+/// # Safety
+/// This function is unsafe because it takes ownership of a raw pointer.
+/// The caller must ensure that:
+/// - `handle` was returned by `compressor_create` and has not been freed, or is null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compressor_free(handle: *mut StreamCompressor) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Create a streaming decompressor
+///
+/// `format` accepts the same values as `decompress_file`, including `AUTO`,
+/// or null for GZIP. `AUTO` waits for 64 KiB of input, or the end of the
+/// stream, before detecting the format. `max_output_size` (0 for the default
+/// of 256 MiB) and `max_ratio` (0 for none) bound the total output as in
+/// `decompress_string_with_format`; pass `u64::MAX` for streams of any size.
+///
+/// Output is returned as soon as it can be decoded, except that LZ4 frames and
+/// Snappy streams keep one block of input queued, and LZ4 blocks and raw
+/// Snappy are decoded only by `decompressor_finish`.
+///
+/// Returns null on error.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `format` is a valid null-terminated C string or null
+/// - The returned handle must be freed using `decompressor_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompressor_create(
+    format: *const c_char,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut StreamDecompressor {
+    let result = read_optional_str(format, "Format")
+        .and_then(DecompressionFormat::parse)
+        .map(|format| StreamDecompressor {
+            format,
+            limits: DecompressionLimits::new(max_output_size, max_ratio),
+            input: PendingInput::default(),
+            decoder: None,
+            input_len: 0,
+            output_len: 0,
+            closed: false,
+        });
+
+    finish_handle(result)
+}
+
+/// Write a chunk of compressed input to a decompressor
+///
+/// The input is only queued here; decoding errors are reported by
+/// `decompressor_read` and `decompressor_finish`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `decompressor_create` that has not been freed
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompressor_write(
+    handle: *mut StreamDecompressor,
+    bytes: *const u8,
+    length: usize,
+) -> bool {
+    let result = unsafe { handle_mut(handle, "Decompressor") }.and_then(|decompressor| {
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        decompressor.write(data)
+    });
+
+    finish_bool(result)
+}
+
+/// Decode the input written so far and take the output
+///
+/// Call this after each `decompressor_write` to keep memory use bounded. An
+/// error, including an exceeded limit, ends the stream.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `decompressor_create` that has not been freed
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompressor_read(
+    handle: *mut StreamDecompressor,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result =
+        unsafe { handle_mut(handle, "Decompressor") }.and_then(|decompressor| decompressor.read());
+    finish_bytes(result, out_length)
+}
+
+/// Mark the end of the compressed input and take the remaining output
+///
+/// Fails if the input is truncated. The handle must still be freed.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `handle` is a handle from `decompressor_create` that has not been freed
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompressor_finish(
+    handle: *mut StreamDecompressor,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = unsafe { handle_mut(handle, "Decompressor") }
+        .and_then(|decompressor| decompressor.finish());
+    finish_bytes(result, out_length)
+}
+
+/// Free a decompressor handle
+///
+/// # Safety
+/// This function is unsafe because it takes ownership of a raw pointer.
+/// The caller must ensure that:
+/// - `handle` was returned by `decompressor_create` and has not been freed, or is null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decompressor_free(handle: *mut StreamDecompressor) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::codec::tests::ALL_FORMATS;
+    use std::ffi::{CStr, CString};
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Option<Vec<u8>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        Some(bytes)
+    }
+
+    fn last_error() -> String {
+        let ptr = unsafe { crate::error::get_last_error() };
+        assert!(!ptr.is_null());
+        let message = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        message
+    }
+
+    /// Deterministic bytes that barely compress.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn compress_stream(input: &[u8], format: &str, chunk_size: usize) -> Vec<u8> {
+        let format = CString::new(format).unwrap();
+        let handle = unsafe { compressor_create(format.as_ptr(), std::ptr::null()) };
+        assert!(!handle.is_null());
+
+        let mut output = Vec::new();
+        let mut len = 0usize;
+        for chunk in input.chunks(chunk_size) {
+            assert!(unsafe { compressor_write(handle, chunk.as_ptr(), chunk.len()) });
+            output.extend(take_bytes(unsafe { compressor_read(handle, &mut len) }, len).unwrap());
+        }
+        output.extend(take_bytes(unsafe { compressor_finish(handle, &mut len) }, len).unwrap());
+        unsafe { compressor_free(handle) };
+        output
+    }
+
+    /// Streams `data` through a decompressor, returning the output read
+    /// before finishing and the full output.
+    fn decompress_stream(
+        data: &[u8],
+        format: &str,
+        chunk_size: usize,
+        max_output_size: u64,
+    ) -> Result<(usize, Vec<u8>), String> {
+        let format = CString::new(format).unwrap();
+        let handle = unsafe { decompressor_create(format.as_ptr(), max_output_size, 0) };
+        assert!(!handle.is_null());
+
+        let result = (|| {
+            let mut output = Vec::new();
+            let mut len = 0usize;
+            for chunk in data.chunks(chunk_size) {
+                assert!(unsafe { decompressor_write(handle, chunk.as_ptr(), chunk.len()) });
+                output.extend(
+                    take_bytes(unsafe { decompressor_read(handle, &mut len) }, len)
+                        .ok_or_else(last_error)?,
+                );
+            }
+            let before_finish = output.len();
+            output.extend(
+                take_bytes(unsafe { decompressor_finish(handle, &mut len) }, len)
+                    .ok_or_else(last_error)?,
+            );
+            Ok((before_finish, output))
+        })();
+        unsafe { decompressor_free(handle) };
+        result
+    }
+
+    #[test]
+    fn test_round_trip_every_format_in_small_chunks() {
+        let input: Vec<u8> = (0..20_000u32).map(|i| (i % 13 + i / 700) as u8).collect();
+        for format in ALL_FORMATS {
+            let compressed = compress_stream(&input, format.name(), 1000);
+            let (_, output) = decompress_stream(&compressed, format.name(), 7, 0)
+                .unwrap_or_else(|e| panic!("{:?}: {}", format, e));
+            assert_eq!(output, input, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_output_available_before_finish() {
+        let input = noise(512 * 1024);
+        for format in ["GZIP", "ZSTD", "BROTLI", "BZIP2", "XZ"] {
+            let compressed = compress_stream(&input, format, 64 * 1024);
+            let (before_finish, output) =
+                decompress_stream(&compressed, format, 16 * 1024, 0).unwrap();
+            assert!(before_finish > input.len() / 2, "{}", format);
+            assert_eq!(output, input, "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_framed_block_formats_keep_block_queued() {
+        let input = noise(5 * 1024 * 1024);
+        for format in ["LZ4", "SNAPPY"] {
+            let compressed = compress_stream(&input, format, 256 * 1024);
+            let (before_finish, output) =
+                decompress_stream(&compressed, format, 256 * 1024, 0).unwrap();
+            assert!(
+                before_finish > 0 && before_finish < input.len(),
+                "{}",
+                format
+            );
+            assert_eq!(output, input, "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_compressed_output_read_before_finish() {
+        let format = CString::new("ZSTD").unwrap();
+        let handle = unsafe { compressor_create(format.as_ptr(), std::ptr::null()) };
+        let input = noise(1024 * 1024);
+        assert!(unsafe { compressor_write(handle, input.as_ptr(), input.len()) });
+
+        let mut len = 0usize;
+        let available = take_bytes(unsafe { compressor_read(handle, &mut len) }, len).unwrap();
+        assert!(!available.is_empty());
+        // Taken output is not returned again
+        let rest = take_bytes(unsafe { compressor_finish(handle, &mut len) }, len).unwrap();
+        assert!(available.len() + rest.len() < input.len() + 1024);
+        unsafe { compressor_free(handle) };
+    }
+
+    #[test]
+    fn test_concatenated_gzip_members_byte_by_byte() {
+        let mut joined = compress_stream(b"first ", "GZIP", 64);
+        joined.extend(compress_stream(b"second", "GZIP", 64));
+        let (_, output) = decompress_stream(&joined, "GZIP", 1, 0).unwrap();
+        assert_eq!(output, b"first second");
+    }
+
+    #[test]
+    fn test_auto_detection() {
+        let input = b"detect while streaming ".repeat(5000);
+        let compressed = compress_stream(&input, "XZ", 4096);
+        let (_, output) = decompress_stream(&compressed, "AUTO", 100, 0).unwrap();
+        assert_eq!(output, input);
+
+        let (_, output) = decompress_stream(&input, "AUTO", 100, 0).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_truncated_input_fails_on_finish() {
+        let compressed = compress_stream(&b"truncated ".repeat(100), "GZIP", 64);
+        let error =
+            decompress_stream(&compressed[..compressed.len() - 6], "GZIP", 10, 0).unwrap_err();
+        assert!(error.starts_with("Decompression failed"), "{}", error);
+    }
+
+    #[test]
+    fn test_limit_ends_stream() {
+        let input = vec![0u8; 1024 * 1024];
+        let compressed = compress_stream(&input, "GZIP", 64 * 1024);
+        let error = decompress_stream(&compressed, "GZIP", 1024, 100_000).unwrap_err();
+        assert_eq!(
+            error,
+            "Decompression limit exceeded: output is larger than 100000 bytes"
+        );
+    }
+
+    #[test]
+    fn test_finished_handles_reject_use() {
+        let format = CString::new("GZIP").unwrap();
+        let mut len = 0usize;
+
+        let compressor = unsafe { compressor_create(format.as_ptr(), std::ptr::null()) };
+        assert!(take_bytes(unsafe { compressor_finish(compressor, &mut len) }, len).is_some());
+        assert!(!unsafe { compressor_write(compressor, b"late".as_ptr(), 4) });
+        assert_eq!(last_error(), "Compressor has already been finished");
+        assert!(unsafe { compressor_finish(compressor, &mut len) }.is_null());
+        unsafe { compressor_free(compressor) };
+
+        let decompressor = unsafe { decompressor_create(format.as_ptr(), 0, 0) };
+        let empty = compress_stream(b"", "GZIP", 1);
+        assert!(unsafe { decompressor_write(decompressor, empty.as_ptr(), empty.len()) });
+        let output = take_bytes(unsafe { decompressor_finish(decompressor, &mut len) }, len);
+        assert_eq!(output.as_deref(), Some(&[][..]));
+        assert!(!unsafe { decompressor_write(decompressor, b"late".as_ptr(), 4) });
+        assert!(unsafe { decompressor_read(decompressor, &mut len) }.is_null());
+        unsafe { decompressor_free(decompressor) };
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let format = CString::new("ZIP").unwrap();
+        assert!(unsafe { compressor_create(format.as_ptr(), std::ptr::null()) }.is_null());
+        assert!(unsafe { decompressor_create(format.as_ptr(), 0, 0) }.is_null());
+
+        let mut len = 7usize;
+        assert!(!unsafe { compressor_write(std::ptr::null_mut(), std::ptr::null(), 0) });
+        assert_eq!(last_error(), "Compressor handle is null");
+        assert!(unsafe { decompressor_read(std::ptr::null_mut(), &mut len) }.is_null());
+        assert_eq!(len, 0);
+        assert_eq!(last_error(), "Decompressor handle is null");
+
+        unsafe {
+            compressor_free(std::ptr::null_mut());
+            decompressor_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Zstandard compression, dictionary compression and dictionary training
+
+use super::limits::DecompressionLimits;
+use super::options::LevelSpec;
+
+/// Zstandard levels 1-22; levels above 19 need considerably more memory.
+pub(crate) const ZSTD_LEVELS: LevelSpec = LevelSpec {
+    min: 1,
+    max: 22,
+    fastest: 1,
+    default: 3,
+    best: 19,
+};
+
+/// Compresses `data` into a Zstandard frame, optionally with a dictionary.
+pub(crate) fn zstd_compress(
+    data: &[u8],
+    level: i32,
+    dictionary: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+    let mut compressor = match dictionary {
+        Some(dictionary) => zstd::bulk::Compressor::with_dictionary(level, dictionary),
+        None => zstd::bulk::Compressor::new(level),
+    }
+    .map_err(|e| format!("Failed to initialize Zstandard compressor: {}", e))?;
+
+    compressor
+        .compress(data)
+        .map_err(|e| format!("Zstandard compression failed: {}", e))
+}
+
+/// Decompresses one or more Zstandard frames, optionally with a dictionary.
+pub(crate) fn zstd_decompress(
+    data: &[u8],
+    dictionary: Option<&[u8]>,
+    limits: DecompressionLimits,
+) -> Result<Vec<u8>, String> {
+    let decoder = match dictionary {
+        Some(dictionary) => zstd::stream::read::Decoder::with_dictionary(data, dictionary),
+        None => zstd::stream::read::Decoder::with_buffer(data),
+    }
+    .map_err(|e| format!("Failed to initialize Zstandard decoder: {}", e))?;
+
+    limits.read_to_end(decoder, data.len())
+}
+
+/// Trains a dictionary of at most `max_size` bytes from concatenated samples.
+///
+/// `sample_sizes` gives the length of each sample within `samples`.
+pub(crate) fn train_dictionary(
+    samples: &[u8],
+    sample_sizes: &[usize],
+    max_size: usize,
+) -> Result<Vec<u8>, String> {
+    if sample_sizes.is_empty() {
+        return Err("At least one sample is required".to_string());
+    }
+    let total = sample_sizes
+        .iter()
+        .try_fold(0usize, |sum, &size| sum.checked_add(size))
+        .ok_or_else(|| "Sample lengths overflow".to_string())?;
+    if total != samples.len() {
+        return Err("Sample lengths must add up to the sample buffer length".to_string());
+    }
+    if max_size == 0 {
+        return Err("Maximum dictionary size must be greater than 0".to_string());
+    }
+
+    zstd::dict::from_continuous(samples, sample_sizes, max_size)
+        .map_err(|e| format!("Dictionary training failed: {}", e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Small JSON telemetry records that share most of their structure.
+    pub(crate) fn telemetry_samples(count: usize) -> (Vec<u8>, Vec<usize>) {
+        let mut samples = Vec::new();
+        let mut sizes = Vec::new();
+        for i in 0..count {
+            let record = format!(
+                r#"{{"timestamp":"2024-05-{:02}T12:{:02}:{:02}Z","host":"web-{:02}","level":"{}","service":"checkout","latency_ms":{},"status":{}}}"#,
+                i % 28 + 1,
+                i % 60,
+                (i * 7) % 60,
+                i % 16,
+                ["info", "warn", "error"][i % 3],
+                (i * 37) % 900,
+                [200, 201, 404, 500][i % 4]
+            );
+            sizes.push(record.len());
+            samples.extend_from_slice(record.as_bytes());
+        }
+        (samples, sizes)
+    }
+
+    #[test]
+    fn test_zstd_round_trip() {
+        let input = b"zstandard round trip ".repeat(50);
+        let compressed = zstd_compress(&input, 3, None).unwrap();
+        assert_eq!(&compressed[..4], &[0x28, 0xb5, 0x2f, 0xfd]);
+        assert_eq!(
+            zstd_decompress(&compressed, None, DecompressionLimits::default()).unwrap(),
+            input
+        );
+    }
+
+    #[test]
+    fn test_dictionary_improves_small_records() {
+        let (samples, sizes) = telemetry_samples(500);
+        let dictionary = train_dictionary(&samples, &sizes, 4096).unwrap();
+
+        let record = br#"{"timestamp":"2024-06-01T08:15:30Z","host":"web-03","level":"info","service":"checkout","latency_ms":42,"status":200}"#;
+        let plain = zstd_compress(record, 3, None).unwrap();
+        let with_dict = zstd_compress(record, 3, Some(&dictionary)).unwrap();
+
+        assert!(with_dict.len() * 2 < plain.len());
+        assert_eq!(
+            zstd_decompress(
+                &with_dict,
+                Some(&dictionary),
+                DecompressionLimits::default()
+            )
+            .unwrap(),
+            record
+        );
+        assert!(zstd_decompress(&with_dict, None, DecompressionLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_train_dictionary_validates_sizes() {
+        assert!(train_dictionary(b"abc", &[], 1024).is_err());
+        assert!(train_dictionary(b"abc", &[2], 1024).is_err());
+        assert!(train_dictionary(b"abc", &[3], 0).is_err());
+        // Lengths that wrap around to the buffer length must not pass
+        assert!(train_dictionary(b"abc", &[usize::MAX, 4], 1024).is_err());
+    }
+}
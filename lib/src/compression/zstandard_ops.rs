@@ -0,0 +1,242 @@
+//! Zstandard string compression and dictionary training operations
+
+use std::os::raw::c_char;
+
+use super::codec::Format;
+use super::compress::encode_input;
+use super::decompress::decoded_to_string;
+use super::limits::DecompressionLimits;
+use super::options::parse_level;
+use super::zstandard::{ZSTD_LEVELS, train_dictionary, zstd_compress, zstd_decompress};
+use crate::encoding::slice_from_raw;
+use crate::helpers::{finish_bytes, finish_string, read_optional_str};
+
+/// Reads an optional dictionary buffer; a zero length means no dictionary.
+unsafe fn read_dictionary<'a>(
+    dictionary: *const u8,
+    dictionary_length: usize,
+) -> Result<Option<&'a [u8]>, String> {
+    let dictionary = unsafe { slice_from_raw(dictionary, dictionary_length, "Dictionary") }?;
+    Ok((!dictionary.is_empty()).then_some(dictionary))
+}
+
+/// Compress a string using Zstandard, optionally with a trained dictionary
+///
+/// `level` is `1`-`22` or one of the presets `FASTEST` (1), `DEFAULT` (3) and
+/// `BEST` (19), and may be null for the default. Pass a dictionary from
+/// `zstd_train_dictionary` to shrink small, similar payloads such as JSON
+/// records; the same dictionary is required to decompress.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `level` is a valid null-terminated C string or null
+/// - `dictionary` is a valid pointer to at least `dictionary_length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zstd_compress_string(
+    input: *const c_char,
+    encoding: *const c_char,
+    level: *const c_char,
+    dictionary: *const u8,
+    dictionary_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    let result = read_optional_str(level, "Level").and_then(|level| {
+        let level = match level {
+            Some(level) => parse_level(level, Format::Zstd)?,
+            None => ZSTD_LEVELS.default,
+        };
+        let dictionary = unsafe { read_dictionary(dictionary, dictionary_length) }?;
+        let bytes = encode_input(input, encoding)?;
+        zstd_compress(&bytes, level, dictionary)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Decompress a Zstandard byte array to a string
+///
+/// `dictionary` must be the dictionary used for compression, or empty when
+/// none was used. With `lenient` set, bytes that are invalid for the encoding
+/// fall back to Latin-1 as in `decompress_string_lenient`. `max_output_size`
+/// and `max_ratio` bound the output as in `decompress_string_with_format`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `bytes` is a valid pointer to at least `length` bytes, or null if length is 0
+/// - `encoding` is a valid null-terminated C string or null
+/// - `dictionary` is a valid pointer to at least `dictionary_length` bytes, or null if length is 0
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zstd_decompress_string(
+    bytes: *const u8,
+    length: usize,
+    encoding: *const c_char,
+    dictionary: *const u8,
+    dictionary_length: usize,
+    lenient: bool,
+    max_output_size: u64,
+    max_ratio: u32,
+) -> *mut c_char {
+    let limits = DecompressionLimits::new(max_output_size, max_ratio);
+    let result = read_optional_str(encoding, "Encoding").and_then(|encoding| {
+        let encoding = encoding.ok_or_else(|| "Encoding pointer is null".to_string())?;
+        let data = unsafe { slice_from_raw(bytes, length, "Byte array") }?;
+        let dictionary = unsafe { read_dictionary(dictionary, dictionary_length) }?;
+        let decompressed = zstd_decompress(data, dictionary, limits)?;
+        decoded_to_string(&decompressed, encoding, lenient)
+    });
+
+    finish_string(result, "decompressed result")
+}
+
+/// Train a Zstandard dictionary from sample payloads
+///
+/// The samples are passed back to back in `samples`, with the length of each
+/// one in `sample_lengths`. Training needs a reasonable number of
+/// representative samples (hundreds of small records); a typical
+/// `max_dictionary_size` is 16-112 KiB.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `samples` is a valid pointer to at least `samples_length` bytes, or null if length is 0
+/// - `sample_lengths` is a valid pointer to `sample_count` usize values, or null if count is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zstd_train_dictionary(
+    samples: *const u8,
+    samples_length: usize,
+    sample_lengths: *const usize,
+    sample_count: usize,
+    max_dictionary_size: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = unsafe { slice_from_raw(samples, samples_length, "Samples") }.and_then(|data| {
+        let sizes = if sample_count == 0 {
+            &[][..]
+        } else if sample_lengths.is_null() {
+            return Err("Sample lengths pointer is null".to_string());
+        } else {
+            unsafe { std::slice::from_raw_parts(sample_lengths, sample_count) }
+        };
+        train_dictionary(data, sizes, max_dictionary_size)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::zstandard::tests::telemetry_samples;
+    use std::ffi::{CStr, CString};
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Vec<u8> {
+        assert!(!ptr.is_null());
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        bytes
+    }
+
+    #[test]
+    fn test_zstd_string_round_trip_with_dictionary() {
+        let (samples, sizes) = telemetry_samples(400);
+        let mut dict_len = 0usize;
+        let dictionary = take_bytes(
+            unsafe {
+                zstd_train_dictionary(
+                    samples.as_ptr(),
+                    samples.len(),
+                    sizes.as_ptr(),
+                    sizes.len(),
+                    4096,
+                    &mut dict_len,
+                )
+            },
+            dict_len,
+        );
+
+        let text = r#"{"host":"web-01","level":"warn","service":"checkout"}"#;
+        let input = CString::new(text).unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let level = CString::new("19").unwrap();
+        let mut len = 0usize;
+        let compressed = take_bytes(
+            unsafe {
+                zstd_compress_string(
+                    input.as_ptr(),
+                    encoding.as_ptr(),
+                    level.as_ptr(),
+                    dictionary.as_ptr(),
+                    dictionary.len(),
+                    &mut len,
+                )
+            },
+            len,
+        );
+
+        let result = unsafe {
+            zstd_decompress_string(
+                compressed.as_ptr(),
+                compressed.len(),
+                encoding.as_ptr(),
+                dictionary.as_ptr(),
+                dictionary.len(),
+                false,
+                0,
+                0,
+            )
+        };
+        assert!(!result.is_null());
+        let decoded = unsafe { CStr::from_ptr(result).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(result) };
+        assert_eq!(decoded, text);
+    }
+
+    #[test]
+    fn test_zstd_compress_string_invalid_level() {
+        let input = CString::new("x").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let level = CString::new("0").unwrap();
+        let mut len = 3usize;
+
+        let result = unsafe {
+            zstd_compress_string(
+                input.as_ptr(),
+                encoding.as_ptr(),
+                level.as_ptr(),
+                std::ptr::null(),
+                0,
+                &mut len,
+            )
+        };
+
+        assert!(result.is_null());
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn test_zstd_train_dictionary_null_lengths() {
+        let samples = b"abc";
+        let result = unsafe {
+            zstd_train_dictionary(
+                samples.as_ptr(),
+                samples.len(),
+                std::ptr::null(),
+                1,
+                1024,
+                std::ptr::null_mut(),
+            )
+        };
+        assert!(result.is_null());
+    }
+}
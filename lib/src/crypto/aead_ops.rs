@@ -0,0 +1,210 @@
+//! Raw-key authenticated encryption operations
+
+use std::os::raw::c_char;
+
+use super::cipher::{self, Cipher};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_bytes;
+
+/// Encrypt bytes with an AEAD cipher and a caller-supplied key and nonce
+///
+/// Returns the ciphertext with the 16-byte authentication tag appended. The
+/// key must be 32 bytes and the nonce 12 bytes; a nonce must never be reused
+/// with the same key. `aad` is authenticated but not encrypted and may be
+/// empty.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `key`, `nonce`, `aad` and `plaintext` are valid pointers to at least the
+///   given number of bytes, or null if the length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - AES-256-GCM
+/// - CHACHA20-POLY1305
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aead_encrypt(
+    algorithm: *const c_char,
+    key: *const u8,
+    key_length: usize,
+    nonce: *const u8,
+    nonce_length: usize,
+    aad: *const u8,
+    aad_length: usize,
+    plaintext: *const u8,
+    plaintext_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let cipher = Cipher::parse(algorithm)?;
+        let key = unsafe { slice_from_raw(key, key_length, "Key") }?;
+        let nonce = unsafe { slice_from_raw(nonce, nonce_length, "Nonce") }?;
+        let aad = unsafe { slice_from_raw(aad, aad_length, "AAD") }?;
+        let plaintext = unsafe { slice_from_raw(plaintext, plaintext_length, "Plaintext") }?;
+        cipher::encrypt(cipher, key, nonce, aad, plaintext)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Decrypt and authenticate bytes produced by `aead_encrypt`
+///
+/// `ciphertext` must include the trailing 16-byte tag. Returns null with an
+/// error set when the key, nonce, AAD or data do not authenticate.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `key`, `nonce`, `aad` and `ciphertext` are valid pointers to at least the
+///   given number of bytes, or null if the length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aead_decrypt(
+    algorithm: *const c_char,
+    key: *const u8,
+    key_length: usize,
+    nonce: *const u8,
+    nonce_length: usize,
+    aad: *const u8,
+    aad_length: usize,
+    ciphertext: *const u8,
+    ciphertext_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let cipher = Cipher::parse(algorithm)?;
+        let key = unsafe { slice_from_raw(key, key_length, "Key") }?;
+        let nonce = unsafe { slice_from_raw(nonce, nonce_length, "Nonce") }?;
+        let aad = unsafe { slice_from_raw(aad, aad_length, "AAD") }?;
+        let ciphertext = unsafe { slice_from_raw(ciphertext, ciphertext_length, "Ciphertext") }?;
+        cipher::decrypt(cipher, key, nonce, aad, ciphertext)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_bytes(ptr: *mut u8, len: usize) -> Option<Vec<u8>> {
+        if ptr.is_null() {
+            return None;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, len).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        Some(bytes)
+    }
+
+    unsafe fn run(
+        f: unsafe extern "C" fn(
+            *const c_char,
+            *const u8,
+            usize,
+            *const u8,
+            usize,
+            *const u8,
+            usize,
+            *const u8,
+            usize,
+            *mut usize,
+        ) -> *mut u8,
+        algorithm: &str,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        data: &[u8],
+    ) -> Option<Vec<u8>> {
+        let algorithm = CString::new(algorithm).unwrap();
+        let mut len = 0usize;
+        let ptr = unsafe {
+            f(
+                algorithm.as_ptr(),
+                key.as_ptr(),
+                key.len(),
+                nonce.as_ptr(),
+                nonce.len(),
+                aad.as_ptr(),
+                aad.len(),
+                data.as_ptr(),
+                data.len(),
+                &mut len,
+            )
+        };
+        take_bytes(ptr, len)
+    }
+
+    #[test]
+    fn test_aead_round_trip() {
+        let key = [0x42u8; 32];
+        let nonce = [0x24u8; 12];
+
+        for algorithm in ["AES-256-GCM", "CHACHA20-POLY1305"] {
+            let sealed =
+                unsafe { run(aead_encrypt, algorithm, &key, &nonce, b"id=7", b"payload") }.unwrap();
+            assert_eq!(sealed.len(), b"payload".len() + 16);
+
+            let opened =
+                unsafe { run(aead_decrypt, algorithm, &key, &nonce, b"id=7", &sealed) }.unwrap();
+            assert_eq!(opened, b"payload");
+        }
+    }
+
+    #[test]
+    fn test_aead_decrypt_tampered_returns_null() {
+        let key = [1u8; 32];
+        let nonce = [2u8; 12];
+        let mut sealed =
+            unsafe { run(aead_encrypt, "AES-256-GCM", &key, &nonce, &[], b"data") }.unwrap();
+        sealed[0] ^= 1;
+
+        assert!(unsafe { run(aead_decrypt, "AES-256-GCM", &key, &nonce, &[], &sealed) }.is_none());
+    }
+
+    #[test]
+    fn test_aead_encrypt_empty_plaintext() {
+        let sealed = unsafe {
+            run(
+                aead_encrypt,
+                "CHACHA20-POLY1305",
+                &[0; 32],
+                &[0; 12],
+                &[],
+                &[],
+            )
+        }
+        .unwrap();
+        assert_eq!(sealed.len(), 16);
+    }
+
+    #[test]
+    fn test_aead_null_algorithm_returns_null() {
+        let mut len = 5usize;
+        let result = unsafe {
+            aead_encrypt(
+                std::ptr::null(),
+                [0u8; 32].as_ptr(),
+                32,
+                [0u8; 12].as_ptr(),
+                12,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                &mut len,
+            )
+        };
+        assert!(result.is_null());
+        assert_eq!(len, 0);
+    }
+}
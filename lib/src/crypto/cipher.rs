@@ -0,0 +1,202 @@
+//! AEAD cipher implementations (AES-256-GCM, ChaCha20-Poly1305)
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305;
+
+/// Key length in bytes shared by both supported ciphers.
+pub(crate) const KEY_LENGTH: usize = 32;
+
+/// Nonce length in bytes shared by both supported ciphers.
+pub(crate) const NONCE_LENGTH: usize = 12;
+
+/// Authentication tag length in bytes appended to every ciphertext.
+pub(crate) const TAG_LENGTH: usize = 16;
+
+/// Supported authenticated encryption algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cipher {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl Cipher {
+    /// Parses a cipher name, ignoring case, `-` and `_`.
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_uppercase();
+        match normalized.as_str() {
+            "AES256GCM" => Ok(Cipher::Aes256Gcm),
+            "CHACHA20POLY1305" => Ok(Cipher::ChaCha20Poly1305),
+            _ => Err(format!(
+                "Unsupported cipher: {}. Supported: AES-256-GCM, CHACHA20-POLY1305",
+                name
+            )),
+        }
+    }
+
+    /// Identifier byte stored in password envelopes.
+    pub(crate) fn id(self) -> u8 {
+        match self {
+            Cipher::Aes256Gcm => 1,
+            Cipher::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    /// Inverse of [`Cipher::id`].
+    pub(crate) fn from_id(id: u8) -> Result<Self, String> {
+        match id {
+            1 => Ok(Cipher::Aes256Gcm),
+            2 => Ok(Cipher::ChaCha20Poly1305),
+            _ => Err(format!("Unknown cipher identifier: {}", id)),
+        }
+    }
+}
+
+fn check_lengths(key: &[u8], nonce: &[u8]) -> Result<(), String> {
+    if key.len() != KEY_LENGTH {
+        return Err(format!(
+            "Key must be {} bytes, got {}",
+            KEY_LENGTH,
+            key.len()
+        ));
+    }
+    if nonce.len() != NONCE_LENGTH {
+        return Err(format!(
+            "Nonce must be {} bytes, got {}",
+            NONCE_LENGTH,
+            nonce.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Encrypts `plaintext`, returning the ciphertext with the tag appended.
+pub(crate) fn encrypt(
+    cipher: Cipher,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    check_lengths(key, nonce)?;
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+
+    let result = match cipher {
+        Cipher::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce.into(), payload),
+        Cipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).encrypt(nonce.into(), payload)
+        }
+    };
+    result.map_err(|_| "Encryption failed".to_string())
+}
+
+/// Decrypts and authenticates `ciphertext` (ciphertext followed by the tag).
+pub(crate) fn decrypt(
+    cipher: Cipher,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, String> {
+    check_lengths(key, nonce)?;
+    if ciphertext.len() < TAG_LENGTH {
+        return Err(format!(
+            "Ciphertext must be at least {} bytes (authentication tag)",
+            TAG_LENGTH
+        ));
+    }
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+
+    let result = match cipher {
+        Cipher::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce.into(), payload),
+        Cipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).decrypt(nonce.into(), payload)
+        }
+    };
+    result.map_err(|_| "Authentication failed: wrong key, nonce, AAD or tampered data".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_aes_256_gcm_known_vector() {
+        // NIST GCM test case 15 without AAD, truncated to the first block
+        let key = unhex("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308");
+        let nonce = unhex("cafebabefacedbaddecaf888");
+        let plaintext = unhex("d9313225f88406e5a55909c5aff5269a");
+
+        let sealed = encrypt(Cipher::Aes256Gcm, &key, &nonce, &[], &plaintext).unwrap();
+
+        assert_eq!(hex(&sealed[..16]), "522dc1f099567d07f47f37a32a84427d");
+        assert_eq!(
+            decrypt(Cipher::Aes256Gcm, &key, &nonce, &[], &sealed).unwrap(),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_rfc8439_vector() {
+        let key = unhex("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f");
+        let nonce = unhex("070000004041424344454647");
+        let aad = unhex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+        let sealed = encrypt(Cipher::ChaCha20Poly1305, &key, &nonce, &aad, plaintext).unwrap();
+
+        assert_eq!(hex(&sealed[..8]), "d31a8d34648e60db");
+        assert_eq!(
+            hex(&sealed[sealed.len() - TAG_LENGTH..]),
+            "1ae10b594f09e26a7e902ecbd0600691"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_aad() {
+        let key = [7u8; KEY_LENGTH];
+        let nonce = [1u8; NONCE_LENGTH];
+        let sealed = encrypt(Cipher::Aes256Gcm, &key, &nonce, b"header", b"secret").unwrap();
+
+        assert!(decrypt(Cipher::Aes256Gcm, &key, &nonce, b"other", &sealed).is_err());
+    }
+
+    #[test]
+    fn test_invalid_lengths_rejected() {
+        assert!(encrypt(Cipher::Aes256Gcm, &[0; 16], &[0; 12], &[], b"x").is_err());
+        assert!(encrypt(Cipher::ChaCha20Poly1305, &[0; 32], &[0; 8], &[], b"x").is_err());
+        assert!(decrypt(Cipher::Aes256Gcm, &[0; 32], &[0; 12], &[], &[0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_cipher_parse_aliases() {
+        assert_eq!(Cipher::parse("aes-256-gcm").unwrap(), Cipher::Aes256Gcm);
+        assert_eq!(Cipher::parse("AES256GCM").unwrap(), Cipher::Aes256Gcm);
+        assert_eq!(
+            Cipher::parse("ChaCha20-Poly1305").unwrap(),
+            Cipher::ChaCha20Poly1305
+        );
+        assert!(Cipher::parse("AES-128-CBC").is_err());
+    }
+}
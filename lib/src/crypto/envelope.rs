@@ -0,0 +1,211 @@
+//! Password-based encryption envelopes
+//!
+//! An envelope is the Base64 encoding of:
+//!
+//! ```text
+//! version (1) | cipher id (1) | kdf id (1) | memory KiB (4, BE) |
+//! iterations (4, BE) | parallelism (4, BE) | salt (16) | nonce (12) |
+//! ciphertext || tag
+//! ```
+//!
+//! The key is derived with Argon2id and everything before the ciphertext is
+//! authenticated as associated data, so the parameters cannot be altered
+//! without decryption failing. The header is only authenticated after the key
+//! is derived, so its Argon2id costs are bounded before deriving.
+
+use crate::base64::{decode_base64, encode_base64};
+
+use super::cipher::{self, Cipher, KEY_LENGTH, NONCE_LENGTH, TAG_LENGTH};
+use crate::random::fill_random;
+
+const ENVELOPE_VERSION: u8 = 1;
+
+/// KDF identifier for Argon2id (version 0x13).
+const KDF_ARGON2ID: u8 = 1;
+
+const SALT_LENGTH: usize = 16;
+
+const HEADER_LENGTH: usize = 3 + 4 * 3 + SALT_LENGTH + NONCE_LENGTH;
+
+/// Argon2id cost parameters recorded in an envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// Rejects costs above the Argon2 maximums for untrusted parameters.
+    fn check(self) -> Result<(), String> {
+        for (name, value, max) in [
+            ("memory", self.memory_kib, crate::kdf::ARGON2_MAX_MEMORY_KIB),
+            (
+                "iterations",
+                self.iterations,
+                crate::kdf::ARGON2_MAX_ITERATIONS,
+            ),
+            (
+                "parallelism",
+                self.parallelism,
+                crate::kdf::ARGON2_MAX_PARALLELISM,
+            ),
+        ] {
+            if value > max {
+                return Err(format!(
+                    "Argon2id {} of {} exceeds the maximum of {}",
+                    name, value, max
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn derive_key(password: &[u8], salt: &[u8], params: KdfParams) -> Result<Vec<u8>, String> {
+    params.check()?;
+    crate::kdf::argon2id_derive(
+        password,
+        salt,
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        KEY_LENGTH,
+    )
+}
+
+/// Encrypts `plaintext` under a password and returns the Base64 envelope.
+pub(crate) fn seal(
+    cipher: Cipher,
+    password: &[u8],
+    plaintext: &[u8],
+    params: KdfParams,
+) -> Result<String, String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    fill_random(&mut salt)?;
+    fill_random(&mut nonce)?;
+
+    let key = derive_key(password, &salt, params)?;
+
+    let mut envelope = Vec::with_capacity(HEADER_LENGTH + plaintext.len() + TAG_LENGTH);
+    envelope.extend_from_slice(&[ENVELOPE_VERSION, cipher.id(), KDF_ARGON2ID]);
+    envelope.extend_from_slice(&params.memory_kib.to_be_bytes());
+    envelope.extend_from_slice(&params.iterations.to_be_bytes());
+    envelope.extend_from_slice(&params.parallelism.to_be_bytes());
+    envelope.extend_from_slice(&salt);
+    envelope.extend_from_slice(&nonce);
+
+    let ciphertext = cipher::encrypt(cipher, &key, &nonce, &envelope, plaintext)?;
+    envelope.extend_from_slice(&ciphertext);
+
+    Ok(encode_base64(&envelope))
+}
+
+/// Decrypts a Base64 envelope produced by [`seal`].
+pub(crate) fn open(password: &[u8], envelope: &str) -> Result<Vec<u8>, String> {
+    let bytes = decode_base64(envelope.trim())?;
+
+    if bytes.len() < HEADER_LENGTH + TAG_LENGTH {
+        return Err("Envelope is too short".to_string());
+    }
+    if bytes[0] != ENVELOPE_VERSION {
+        return Err(format!("Unsupported envelope version: {}", bytes[0]));
+    }
+    let cipher = Cipher::from_id(bytes[1])?;
+    if bytes[2] != KDF_ARGON2ID {
+        return Err(format!("Unknown KDF identifier: {}", bytes[2]));
+    }
+
+    let read_u32 = |offset: usize| {
+        u32::from_be_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+    let params = KdfParams {
+        memory_kib: read_u32(3),
+        iterations: read_u32(7),
+        parallelism: read_u32(11),
+    };
+
+    let (header, ciphertext) = bytes.split_at(HEADER_LENGTH);
+    let salt = &header[15..15 + SALT_LENGTH];
+    let nonce = &header[15 + SALT_LENGTH..];
+
+    let key = derive_key(password, salt, params)?;
+    cipher::decrypt(cipher, &key, nonce, header, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deliberately cheap parameters to keep tests fast
+    const TEST_PARAMS: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_round_trip_both_ciphers() {
+        for cipher in [Cipher::Aes256Gcm, Cipher::ChaCha20Poly1305] {
+            let envelope = seal(cipher, b"hunter2", b"api-token", TEST_PARAMS).unwrap();
+            assert_eq!(open(b"hunter2", &envelope).unwrap(), b"api-token");
+        }
+    }
+
+    #[test]
+    fn test_envelopes_are_randomized() {
+        let a = seal(Cipher::Aes256Gcm, b"pw", b"same", TEST_PARAMS).unwrap();
+        let b = seal(Cipher::Aes256Gcm, b"pw", b"same", TEST_PARAMS).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_wrong_password_fails() {
+        let envelope = seal(Cipher::Aes256Gcm, b"right", b"secret", TEST_PARAMS).unwrap();
+        assert!(open(b"wrong", &envelope).is_err());
+    }
+
+    #[test]
+    fn test_tampered_header_fails() {
+        let envelope = seal(Cipher::ChaCha20Poly1305, b"pw", b"secret", TEST_PARAMS).unwrap();
+        let mut bytes = decode_base64(&envelope).unwrap();
+        // Bump the iteration count; the header is authenticated
+        bytes[10] ^= 0x02;
+        assert!(open(b"pw", &encode_base64(&bytes)).is_err());
+    }
+
+    #[test]
+    fn test_excessive_costs_rejected_before_deriving() {
+        let envelope = seal(Cipher::Aes256Gcm, b"pw", b"secret", TEST_PARAMS).unwrap();
+        for offset in [3, 7, 11] {
+            let mut bytes = decode_base64(&envelope).unwrap();
+            bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            let err = open(b"pw", &encode_base64(&bytes)).unwrap_err();
+            assert!(err.contains("exceeds the maximum"), "{}", err);
+        }
+
+        let params = KdfParams {
+            memory_kib: crate::kdf::ARGON2_MAX_MEMORY_KIB + 1,
+            ..TEST_PARAMS
+        };
+        assert!(seal(Cipher::Aes256Gcm, b"pw", b"secret", params).is_err());
+    }
+
+    #[test]
+    fn test_malformed_envelopes_rejected() {
+        assert!(open(b"pw", "not base64!").is_err());
+        assert!(open(b"pw", &encode_base64(&[1u8; 10])).is_err());
+
+        let envelope = seal(Cipher::Aes256Gcm, b"pw", b"x", TEST_PARAMS).unwrap();
+        let mut bytes = decode_base64(&envelope).unwrap();
+        bytes[0] = 9;
+        let err = open(b"pw", &encode_base64(&bytes)).unwrap_err();
+        assert!(err.contains("version"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Password-based envelope encryption operations
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+use super::cipher::Cipher;
+use super::envelope::{self, KdfParams};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_bytes;
+
+/// Encrypt bytes under a password and return a Base64 envelope string
+///
+/// A random salt and nonce are generated for every call and the key is derived
+/// with Argon2id. The envelope records the cipher, Argon2id parameters, salt
+/// and nonce so `decrypt_with_password` needs only the password. The OWASP
+/// baseline for Argon2id is 19456 KiB, 2 iterations, parallelism 1. Costs
+/// above 1048576 KiB (1 GiB), 64 iterations or parallelism 64 are rejected, and
+/// `decrypt_with_password` refuses envelopes that claim them.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `plaintext` is a valid pointer to at least `plaintext_length` bytes, or null if length is 0
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - AES-256-GCM
+/// - CHACHA20-POLY1305
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn encrypt_with_password(
+    plaintext: *const u8,
+    plaintext_length: usize,
+    password: *const u8,
+    password_length: usize,
+    algorithm: *const c_char,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let cipher = Cipher::parse(algorithm)?;
+        let plaintext = unsafe { slice_from_raw(plaintext, plaintext_length, "Plaintext") }?;
+        let password = unsafe { slice_from_raw(password, password_length, "Password") }?;
+        let params = KdfParams {
+            memory_kib,
+            iterations,
+            parallelism,
+        };
+        envelope::seal(cipher, password, plaintext, params)
+    });
+
+    let sealed = match result {
+        Ok(s) => s,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    match CString::new(sealed) {
+        Ok(c_str) => {
+            crate::error::clear_error();
+            c_str.into_raw()
+        }
+        Err(_) => {
+            crate::error::set_error("Failed to create C string from envelope".to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Decrypt a Base64 envelope produced by `encrypt_with_password`
+///
+/// Returns null with an error set when the password is wrong or the envelope
+/// is malformed or has been modified.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `envelope` is a valid null-terminated C string or null
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn decrypt_with_password(
+    envelope: *const c_char,
+    password: *const u8,
+    password_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(envelope, "Envelope").and_then(|envelope| {
+        let password = unsafe { slice_from_raw(password, password_length, "Password") }?;
+        envelope::open(password, envelope)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn seal(plaintext: &[u8], password: &[u8], algorithm: &str) -> *mut c_char {
+        let algorithm = CString::new(algorithm).unwrap();
+        unsafe {
+            encrypt_with_password(
+                plaintext.as_ptr(),
+                plaintext.len(),
+                password.as_ptr(),
+                password.len(),
+                algorithm.as_ptr(),
+                64,
+                1,
+                1,
+            )
+        }
+    }
+
+    #[test]
+    fn test_password_envelope_round_trip() {
+        let ptr = seal(b"db-password", b"correct horse", "CHACHA20-POLY1305");
+        assert!(!ptr.is_null());
+
+        let mut len = 0usize;
+        let password = b"correct horse";
+        let opened =
+            unsafe { decrypt_with_password(ptr, password.as_ptr(), password.len(), &mut len) };
+        assert!(!opened.is_null());
+        assert_eq!(
+            unsafe { std::slice::from_raw_parts(opened, len) },
+            b"db-password"
+        );
+
+        unsafe {
+            crate::memory::free_bytes(opened);
+            crate::memory::free_string(ptr);
+        }
+    }
+
+    #[test]
+    fn test_password_envelope_wrong_password_returns_null() {
+        let ptr = seal(b"secret", b"right", "AES-256-GCM");
+        let mut len = 9usize;
+        let wrong = b"wrong";
+
+        let opened = unsafe { decrypt_with_password(ptr, wrong.as_ptr(), wrong.len(), &mut len) };
+
+        assert!(opened.is_null());
+        assert_eq!(len, 0);
+        unsafe { crate::memory::free_string(ptr) };
+    }
+
+    #[test]
+    fn test_encrypt_with_password_invalid_params_returns_null() {
+        let algorithm = CString::new("AES-256-GCM").unwrap();
+        let result = unsafe {
+            encrypt_with_password(
+                b"x".as_ptr(),
+                1,
+                b"pw".as_ptr(),
+                2,
+                algorithm.as_ptr(),
+                0,
+                0,
+                0,
+            )
+        };
+        assert!(result.is_null());
+    }
+
+    #[test]
+    fn test_envelope_is_base64() {
+        let ptr = seal(b"x", b"pw", "AES-256-GCM");
+        let text = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+
+        assert!(
+            text.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=')
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Authenticated symmetric encryption (AES-256-GCM, ChaCha20-Poly1305) and
+//! password-based envelopes
+
+mod aead_ops;
+mod cipher;
+mod envelope;
+mod envelope_ops;
+
+// Re-export public FFI functions
+pub use aead_ops::{aead_decrypt, aead_encrypt};
+pub use envelope_ops::{decrypt_with_password, encrypt_with_password};
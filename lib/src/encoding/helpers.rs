@@ -1,5 +1,8 @@
 //! Helper functions for encoding operations
 
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
 /// Sets output length to zero if pointer is non-null.
 ///
 /// Used in error paths to ensure consistent behavior.
@@ -11,3 +14,39 @@ pub(crate) fn set_output_length_zero(out_length: *mut usize) {
         }
     }
 }
+
+/// Builds a byte slice from a pointer and length supplied over FFI.
+///
+/// A null pointer is accepted only when `length` is zero, mirroring the
+/// empty-input handling of `compute_hmac_bytes`.
+///
+/// # Safety
+/// If non-null, `ptr` must point to at least `length` readable bytes that
+/// remain valid for the returned lifetime.
+#[inline]
+pub(crate) unsafe fn slice_from_raw<'a>(
+    ptr: *const u8,
+    length: usize,
+    name: &str,
+) -> Result<&'a [u8], String> {
+    if length == 0 {
+        return Ok(&[]);
+    }
+
+    if ptr.is_null() {
+        return Err(format!("{} pointer is null", name));
+    }
+
+    Ok(unsafe { std::slice::from_raw_parts(ptr, length) })
+}
+
+/// Reads a required C string argument, naming it in the error message.
+pub(crate) fn read_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{} pointer is null", name));
+    }
+
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| format!("Invalid UTF-8 in {} string", name.to_lowercase()))
+}
@@ -7,3 +7,6 @@ mod string_to_bytes;
 // Re-export public FFI functions
 pub use bytes_to_string::{bytes_to_string, bytes_to_string_lenient};
 pub use string_to_bytes::string_to_bytes;
+
+// Re-export helpers for use by other modules
+pub(crate) use helpers::{read_str, set_output_length_zero, slice_from_raw};
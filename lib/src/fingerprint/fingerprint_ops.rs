@@ -0,0 +1,218 @@
+//! SSH key and X.509 certificate fingerprint operations
+
+use std::os::raw::c_char;
+
+use super::{ssh, x509};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::{finish_string, into_c_string};
+
+/// Compute the fingerprint of an OpenSSH public key line
+///
+/// Accepts a `.pub` file line or an `authorized_keys` entry (leading options
+/// are skipped). MD5 produces the legacy `MD5:aa:bb:...` form and every other
+/// algorithm the `SHA256:<base64>` form printed by `ssh-keygen -l`.
+///
+/// When `out_key_type` or `out_comment` is non-null it receives a newly
+/// allocated C string that must be freed with `free_string`; `out_comment`
+/// receives null when the line has no comment.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `key_line` and `algorithm` are valid null-terminated C strings or null
+/// - `out_key_type` and `out_comment` are valid pointers for writes or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA256 (OpenSSH default)
+/// - MD5 (legacy colon-hex)
+/// - SHA1, SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ssh_key_fingerprint(
+    key_line: *const c_char,
+    algorithm: *const c_char,
+    out_key_type: *mut *mut c_char,
+    out_comment: *mut *mut c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(key_line, "Key line").and_then(|line| {
+        let algorithm = read_str(algorithm, "Algorithm")?;
+        let key = ssh::parse_public_key(line)?;
+        let fingerprint = ssh::fingerprint(&key.blob, algorithm)?;
+        Ok((fingerprint, key))
+    });
+
+    let (fingerprint, key) = match result {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let strings = [
+        into_c_string(&fingerprint, "fingerprint result"),
+        into_c_string(&key.key_type, "fingerprint result"),
+        match key.comment {
+            Some(comment) => into_c_string(&comment, "fingerprint result"),
+            None => Ok(std::ptr::null_mut()),
+        },
+    ];
+
+    if let Some(Err(e)) = strings.iter().find(|r| r.is_err()) {
+        crate::error::set_error(e.clone());
+        for ptr in strings.iter().flatten() {
+            unsafe { crate::memory::free_string(*ptr) };
+        }
+        return std::ptr::null_mut();
+    }
+
+    let [fingerprint, key_type, comment] = strings.map(|r| r.unwrap_or_default());
+
+    // SAFETY: Output pointers are only written when the caller supplied them
+    unsafe {
+        if out_key_type.is_null() {
+            crate::memory::free_string(key_type);
+        } else {
+            *out_key_type = key_type;
+        }
+        if out_comment.is_null() {
+            crate::memory::free_string(comment);
+        } else {
+            *out_comment = comment;
+        }
+    }
+
+    fingerprint
+}
+
+/// Compute the fingerprint of an X.509 certificate
+///
+/// `certificate` is either PEM text (the first `CERTIFICATE` block is used) or
+/// raw DER. The result is uppercase colon-separated hex, matching
+/// `openssl x509 -fingerprint`. When `public_key_only` is true the digest
+/// covers the certificate's DER `SubjectPublicKeyInfo` instead, which stays
+/// the same when a certificate is renewed with the same key.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `certificate` is a valid pointer to at least `certificate_length` bytes or null
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA256 (recommended)
+/// - SHA1 (common in older tooling)
+/// - MD5, SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn x509_fingerprint(
+    certificate: *const u8,
+    certificate_length: usize,
+    algorithm: *const c_char,
+    public_key_only: bool,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let input = unsafe { slice_from_raw(certificate, certificate_length, "Certificate") }?;
+        let der = x509::certificate_der(input)?;
+        if public_key_only {
+            x509::fingerprint(x509::subject_public_key_info(&der)?, algorithm)
+        } else {
+            x509::fingerprint(&der, algorithm)
+        }
+    });
+
+    finish_string(result, "fingerprint result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    const ED25519_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAtyq/QgXyMbHUhrfx5gUPUKoDDpY5l2AABSFwX+kJ3M alice@example";
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    #[test]
+    fn test_ssh_key_fingerprint_outputs() {
+        let line = CString::new(ED25519_KEY).unwrap();
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut key_type = std::ptr::null_mut();
+        let mut comment = std::ptr::null_mut();
+
+        let fingerprint = take_string(unsafe {
+            ssh_key_fingerprint(
+                line.as_ptr(),
+                algorithm.as_ptr(),
+                &mut key_type,
+                &mut comment,
+            )
+        });
+
+        assert_eq!(
+            fingerprint.as_deref(),
+            Some("SHA256:cdIJMQZJXH1WpLzJTdWQZWrCDV+/RtE7YqAliW3pouY")
+        );
+        assert_eq!(take_string(key_type).as_deref(), Some("ssh-ed25519"));
+        assert_eq!(take_string(comment).as_deref(), Some("alice@example"));
+    }
+
+    #[test]
+    fn test_ssh_key_fingerprint_null_outputs_allowed() {
+        let line = CString::new(ED25519_KEY).unwrap();
+        let algorithm = CString::new("md5").unwrap();
+
+        let fingerprint = take_string(unsafe {
+            ssh_key_fingerprint(
+                line.as_ptr(),
+                algorithm.as_ptr(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        });
+
+        assert_eq!(
+            fingerprint.as_deref(),
+            Some("MD5:ae:79:0e:6e:e7:97:22:65:6a:1c:ae:6a:6d:5d:c1:6a")
+        );
+    }
+
+    #[test]
+    fn test_ssh_key_fingerprint_invalid_line_returns_null() {
+        let line = CString::new("ssh-ed25519 not-base64!").unwrap();
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let result = unsafe {
+            ssh_key_fingerprint(
+                line.as_ptr(),
+                algorithm.as_ptr(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        assert!(result.is_null());
+    }
+
+    #[test]
+    fn test_x509_fingerprint_garbage_returns_null() {
+        let input = b"garbage";
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let result =
+            unsafe { x509_fingerprint(input.as_ptr(), input.len(), algorithm.as_ptr(), false) };
+
+        assert!(result.is_null());
+    }
+}
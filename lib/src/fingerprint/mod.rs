@@ -0,0 +1,8 @@
+//! SSH public key and X.509 certificate fingerprint functions
+
+mod fingerprint_ops;
+mod ssh;
+mod x509;
+
+// Re-export public FFI functions
+pub use fingerprint_ops::{ssh_key_fingerprint, x509_fingerprint};
@@ -0,0 +1,155 @@
+//! OpenSSH public key line parsing and fingerprints
+//!
+//! Accepts `id_*.pub` and `authorized_keys` lines, including leading options
+//! such as `from="10.0.0.0/8",no-pty`.
+
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+};
+
+use crate::hash::compute_hash_raw;
+
+/// A public key parsed from an OpenSSH key line.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SshPublicKey {
+    pub key_type: String,
+    /// The decoded wire-format key blob that fingerprints are computed over
+    pub blob: Vec<u8>,
+    pub comment: Option<String>,
+}
+
+/// Splits a line on whitespace outside double quotes, returning byte ranges.
+fn tokenize(line: &str) -> Vec<(usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (i, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if let Some(s) = start.take() {
+                    tokens.push((s, i));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+
+    if let Some(s) = start {
+        tokens.push((s, line.len()));
+    }
+    tokens
+}
+
+/// Reads the key type string that starts every SSH public key blob.
+fn blob_key_type(blob: &[u8]) -> Option<&str> {
+    let length = u32::from_be_bytes(blob.get(..4)?.try_into().ok()?) as usize;
+    std::str::from_utf8(blob.get(4..4 + length)?).ok()
+}
+
+/// Parses an OpenSSH public key line.
+///
+/// The key is the first `<type> <base64>` token pair whose blob declares the
+/// same type, so leading `authorized_keys` options are skipped. Anything after
+/// the blob is the comment.
+pub(crate) fn parse_public_key(line: &str) -> Result<SshPublicKey, String> {
+    let line = line.trim();
+    let tokens = tokenize(line);
+
+    for pair in tokens.windows(2) {
+        let key_type = &line[pair[0].0..pair[0].1];
+        let encoded = &line[pair[1].0..pair[1].1];
+
+        let Ok(blob) = STANDARD
+            .decode(encoded)
+            .or_else(|_| STANDARD_NO_PAD.decode(encoded))
+        else {
+            continue;
+        };
+
+        if blob_key_type(&blob) == Some(key_type) {
+            let comment = line[pair[1].1..].trim();
+            return Ok(SshPublicKey {
+                key_type: key_type.to_string(),
+                blob,
+                comment: (!comment.is_empty()).then(|| comment.to_string()),
+            });
+        }
+    }
+
+    Err("No OpenSSH public key ('<type> <base64>') found in line".to_string())
+}
+
+/// Formats a fingerprint the way `ssh-keygen -l -E <algorithm>` does.
+///
+/// MD5 uses the legacy colon-separated lowercase hex (`MD5:aa:bb:...`); every
+/// other algorithm uses unpadded Base64 (`SHA256:...`).
+pub(crate) fn fingerprint(blob: &[u8], algorithm: &str) -> Result<String, String> {
+    let name = algorithm.to_uppercase();
+    let digest = compute_hash_raw(blob, &name)?;
+
+    if name == "MD5" {
+        let hex: Vec<String> = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(format!("MD5:{}", hex.join(":")))
+    } else {
+        Ok(format!("{}:{}", name, STANDARD_NO_PAD.encode(digest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAtyq/QgXyMbHUhrfx5gUPUKoDDpY5l2AABSFwX+kJ3M alice@example";
+
+    #[test]
+    fn test_fingerprint_matches_ssh_keygen() {
+        let key = parse_public_key(ED25519_KEY).unwrap();
+
+        assert_eq!(
+            fingerprint(&key.blob, "sha256").unwrap(),
+            "SHA256:cdIJMQZJXH1WpLzJTdWQZWrCDV+/RtE7YqAliW3pouY"
+        );
+        assert_eq!(
+            fingerprint(&key.blob, "MD5").unwrap(),
+            "MD5:ae:79:0e:6e:e7:97:22:65:6a:1c:ae:6a:6d:5d:c1:6a"
+        );
+    }
+
+    #[test]
+    fn test_parse_authorized_keys_options() {
+        let line = format!(
+            "from=\"10.0.0.1\",command=\"echo \\\"a b\\\"\",no-pty {}",
+            ED25519_KEY
+        );
+        let key = parse_public_key(&line).unwrap();
+
+        assert_eq!(key.key_type, "ssh-ed25519");
+        assert_eq!(key.comment.as_deref(), Some("alice@example"));
+    }
+
+    #[test]
+    fn test_parse_key_without_comment() {
+        let line = ED25519_KEY.rsplit_once(' ').unwrap().0;
+        assert_eq!(parse_public_key(line).unwrap().comment, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_lines() {
+        assert!(parse_public_key("").is_err());
+        assert!(parse_public_key("# comment").is_err());
+        // Blob declares ssh-ed25519, so the mismatched type is rejected
+        let mismatched = ED25519_KEY.replace("ssh-ed25519", "ssh-rsa");
+        assert!(parse_public_key(&mismatched).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! X.509 certificate decoding (PEM or DER) and fingerprints
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+use crate::hash::compute_hash_raw;
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_CONTEXT_0: u8 = 0xA0;
+
+/// Returns the DER bytes of a certificate supplied as PEM text or raw DER.
+///
+/// For PEM input the first `CERTIFICATE` block is used.
+pub(crate) fn certificate_der(input: &[u8]) -> Result<Vec<u8>, String> {
+    if input.first() == Some(&DER_SEQUENCE) {
+        return Ok(input.to_vec());
+    }
+
+    let text =
+        std::str::from_utf8(input).map_err(|_| "Certificate is neither DER nor PEM".to_string())?;
+    let start = text
+        .find(PEM_BEGIN)
+        .ok_or_else(|| "Certificate is neither DER nor PEM".to_string())?
+        + PEM_BEGIN.len();
+    let end = text[start..]
+        .find(PEM_END)
+        .ok_or_else(|| "PEM certificate is missing its END line".to_string())?
+        + start;
+
+    let body: String = text[start..end]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| format!("Invalid Base64 in PEM certificate: {}", e))
+}
+
+/// Reads one DER element, returning its tag, the whole element and its contents.
+fn read_element(input: &[u8]) -> Result<(u8, &[u8], &[u8]), String> {
+    let malformed = || "Malformed DER certificate".to_string();
+    let tag = *input.first().ok_or_else(malformed)?;
+    let first = *input.get(1).ok_or_else(malformed)?;
+
+    let (length, header) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 {
+            return Err(malformed());
+        }
+        let bytes = input.get(2..2 + count).ok_or_else(malformed)?;
+        let length = bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (length, 2 + count)
+    };
+
+    let end = header.checked_add(length).ok_or_else(malformed)?;
+    let element = input.get(..end).ok_or_else(malformed)?;
+    Ok((tag, element, &element[header..]))
+}
+
+/// Extracts the DER `SubjectPublicKeyInfo` from a certificate.
+pub(crate) fn subject_public_key_info(der: &[u8]) -> Result<&[u8], String> {
+    let expect_sequence = |tag: u8| {
+        if tag == DER_SEQUENCE {
+            Ok(())
+        } else {
+            Err("Malformed DER certificate".to_string())
+        }
+    };
+
+    let (tag, _, certificate) = read_element(der)?;
+    expect_sequence(tag)?;
+    let (tag, _, mut tbs) = read_element(certificate)?;
+    expect_sequence(tag)?;
+
+    // Skip the optional [0] version, then serial, signature, issuer, validity, subject
+    if tbs.first() == Some(&DER_CONTEXT_0) {
+        tbs = &tbs[read_element(tbs)?.1.len()..];
+    }
+    for _ in 0..5 {
+        tbs = &tbs[read_element(tbs)?.1.len()..];
+    }
+
+    let (tag, spki, _) = read_element(tbs)?;
+    expect_sequence(tag)?;
+    Ok(spki)
+}
+
+/// Formats a digest as uppercase colon-separated hex, as `openssl x509 -fingerprint` does.
+pub(crate) fn fingerprint(data: &[u8], algorithm: &str) -> Result<String, String> {
+    let digest = compute_hash_raw(data, algorithm)?;
+    Ok(digest
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed P-256 certificate generated with `openssl req -x509`
+    const CERT_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBjDCCATOgAwIBAgIUHIGKSh1Z0eA1SVyVAFmb8f1ioHMwCgYIKoZIzj0EAwIw
+HDEaMBgGA1UEAwwRY29udmVydC1jb3JlLXRlc3QwHhcNMjYxMDE4MTcwNzA5WhcN
+MzYxMDE1MTcwNzA5WjAcMRowGAYDVQQDDBFjb252ZXJ0LWNvcmUtdGVzdDBZMBMG
+ByqGSM49AgEGCCqGSM49AwEHA0IABEFssgGzkUuv0znZSpFwZ96LaAEcvM6ubLhJ
+9YE7ITybQfJLswMw8pqEEEzkagt1CsEETQc0hK5bGFuKtgKX/tyjUzBRMB0GA1Ud
+DgQWBBRvhNrbWOdZ/3Ql8v1DEr1dalvAUDAfBgNVHSMEGDAWgBRvhNrbWOdZ/3Ql
+8v1DEr1dalvAUDAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQCIEBR
+E2ewWg+koxB/WWcPX7c8sirtd19Em18FGuGk/7zoAiBgLRSXjneRWS6Ut3e6Tl5q
+AnWkgyp3wEFZd4fwhG7KIg==
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_certificate_fingerprint_matches_openssl() {
+        let der = certificate_der(CERT_PEM.as_bytes()).unwrap();
+
+        assert_eq!(
+            fingerprint(&der, "SHA256").unwrap(),
+            "6D:F8:9B:62:9B:96:97:8C:AB:F8:AD:1A:87:45:EB:7F:F7:F5:58:F4:9C:A9:10:E9:AC:7F:B6:CD:D8:15:3E:AD"
+        );
+        assert_eq!(
+            fingerprint(&der, "SHA1").unwrap(),
+            "7A:6B:F9:1B:BB:13:85:39:7C:73:04:69:70:0C:60:D1:48:99:A8:69"
+        );
+        assert_eq!(certificate_der(&der).unwrap(), der, "DER passes through");
+    }
+
+    #[test]
+    fn test_subject_public_key_info_fingerprint() {
+        let der = certificate_der(CERT_PEM.as_bytes()).unwrap();
+        let spki = subject_public_key_info(&der).unwrap();
+
+        assert_eq!(spki.len(), 91, "P-256 SubjectPublicKeyInfo is 91 bytes");
+        assert!(
+            fingerprint(spki, "SHA256")
+                .unwrap()
+                .starts_with("2D:6F:5A:0F:4C:B4:E4:34")
+        );
+    }
+
+    #[test]
+    fn test_certificate_der_rejects_invalid_input() {
+        assert!(certificate_der(b"not a certificate").is_err());
+        assert!(certificate_der(PEM_BEGIN.as_bytes()).is_err());
+        assert!(subject_public_key_info(&[0x30, 0x05, 0x30]).is_err());
+    }
+}
@@ -3,42 +3,78 @@
 use hmac::{Hmac, Mac};
 use md5::Md5;
 use sha1::Sha1;
+use sha2::digest::DynDigest;
 use sha2::{Digest, Sha256, Sha384, Sha512};
 
+#[cfg(feature = "legacy-hashes")]
+use md4::Md4;
+#[cfg(feature = "legacy-hashes")]
+use ripemd::Ripemd160;
+#[cfg(feature = "legacy-hashes")]
+use sm3::Sm3;
+#[cfg(feature = "legacy-hashes")]
+use whirlpool::Whirlpool;
+
+#[cfg(not(feature = "legacy-hashes"))]
+const SUPPORTED_ALGORITHMS: &str = "MD5, SHA1, SHA256, SHA384, SHA512";
+
+#[cfg(feature = "legacy-hashes")]
+const SUPPORTED_ALGORITHMS: &str =
+    "MD5, SHA1, SHA256, SHA384, SHA512, MD4, RIPEMD160, SM3, WHIRLPOOL";
+
+fn unsupported_algorithm(algorithm: &str) -> String {
+    format!(
+        "Unsupported algorithm: {}. Supported: {}",
+        algorithm, SUPPORTED_ALGORITHMS
+    )
+}
+
 /// Computes hash for the given bytes using the specified algorithm.
 ///
 /// Returns uppercase hexadecimal string for .NET compatibility.
 pub(crate) fn compute_hash_bytes(bytes: &[u8], algorithm: &str) -> Result<String, String> {
+    let digest = compute_hash_raw(bytes, algorithm)?;
+    Ok(digest.iter().map(|b| format!("{:02X}", b)).collect())
+}
+
+/// Computes hash for the given bytes, returning the raw digest bytes.
+pub(crate) fn compute_hash_raw(bytes: &[u8], algorithm: &str) -> Result<Vec<u8>, String> {
     match algorithm.to_uppercase().as_str() {
-        "MD5" => {
-            let mut hasher = Md5::new();
-            hasher.update(bytes);
-            Ok(format!("{:X}", hasher.finalize()))
-        }
-        "SHA1" => {
-            let mut hasher = Sha1::new();
-            hasher.update(bytes);
-            Ok(format!("{:X}", hasher.finalize()))
-        }
-        "SHA256" => {
-            let mut hasher = Sha256::new();
-            hasher.update(bytes);
-            Ok(format!("{:X}", hasher.finalize()))
-        }
-        "SHA384" => {
-            let mut hasher = Sha384::new();
-            hasher.update(bytes);
-            Ok(format!("{:X}", hasher.finalize()))
-        }
-        "SHA512" => {
-            let mut hasher = Sha512::new();
-            hasher.update(bytes);
-            Ok(format!("{:X}", hasher.finalize()))
-        }
-        _ => Err(format!(
-            "Unsupported algorithm: {}. Supported: MD5, SHA1, SHA256, SHA384, SHA512",
-            algorithm
-        )),
+        "MD5" => Ok(Md5::digest(bytes).to_vec()),
+        "SHA1" => Ok(Sha1::digest(bytes).to_vec()),
+        "SHA256" => Ok(Sha256::digest(bytes).to_vec()),
+        "SHA384" => Ok(Sha384::digest(bytes).to_vec()),
+        "SHA512" => Ok(Sha512::digest(bytes).to_vec()),
+        #[cfg(feature = "legacy-hashes")]
+        "MD4" => Ok(Md4::digest(bytes).to_vec()),
+        #[cfg(feature = "legacy-hashes")]
+        "RIPEMD160" => Ok(Ripemd160::digest(bytes).to_vec()),
+        #[cfg(feature = "legacy-hashes")]
+        "SM3" => Ok(Sm3::digest(bytes).to_vec()),
+        #[cfg(feature = "legacy-hashes")]
+        "WHIRLPOOL" => Ok(Whirlpool::digest(bytes).to_vec()),
+        _ => Err(unsupported_algorithm(algorithm)),
+    }
+}
+
+/// Creates an incremental hasher for the specified algorithm, for hashing
+/// data (such as files) that is not held in memory all at once.
+pub(crate) fn new_hasher(algorithm: &str) -> Result<Box<dyn DynDigest + Send>, String> {
+    match algorithm.to_uppercase().as_str() {
+        "MD5" => Ok(Box::new(Md5::new())),
+        "SHA1" => Ok(Box::new(Sha1::new())),
+        "SHA256" => Ok(Box::new(Sha256::new())),
+        "SHA384" => Ok(Box::new(Sha384::new())),
+        "SHA512" => Ok(Box::new(Sha512::new())),
+        #[cfg(feature = "legacy-hashes")]
+        "MD4" => Ok(Box::new(Md4::new())),
+        #[cfg(feature = "legacy-hashes")]
+        "RIPEMD160" => Ok(Box::new(Ripemd160::new())),
+        #[cfg(feature = "legacy-hashes")]
+        "SM3" => Ok(Box::new(Sm3::new())),
+        #[cfg(feature = "legacy-hashes")]
+        "WHIRLPOOL" => Ok(Box::new(Whirlpool::new())),
+        _ => Err(unsupported_algorithm(algorithm)),
     }
 }
 
@@ -50,65 +86,215 @@ pub(crate) fn compute_hmac_internal(
     key: &[u8],
     input: &[u8],
 ) -> Result<String, String> {
+    let mac = compute_hmac_raw(algorithm, key, input)?;
+    Ok(mac.iter().map(|b| format!("{:02X}", b)).collect())
+}
+
+/// Computes HMAC using the specified algorithm, returning the raw MAC bytes.
+pub(crate) fn compute_hmac_raw(
+    algorithm: &str,
+    key: &[u8],
+    input: &[u8],
+) -> Result<Vec<u8>, String> {
     match algorithm.to_uppercase().as_str() {
         "MD5" => compute_hmac_md5(key, input),
         "SHA1" => compute_hmac_sha1(key, input),
         "SHA256" => compute_hmac_sha256(key, input),
         "SHA384" => compute_hmac_sha384(key, input),
         "SHA512" => compute_hmac_sha512(key, input),
-        _ => Err(format!(
-            "Unsupported algorithm: {}. Supported: MD5, SHA1, SHA256, SHA384, SHA512",
-            algorithm
-        )),
+        #[cfg(feature = "legacy-hashes")]
+        "MD4" => compute_hmac_legacy::<Hmac<Md4>>(key, input, "MD4"),
+        #[cfg(feature = "legacy-hashes")]
+        "RIPEMD160" => compute_hmac_legacy::<Hmac<Ripemd160>>(key, input, "RIPEMD160"),
+        #[cfg(feature = "legacy-hashes")]
+        "SM3" => compute_hmac_legacy::<Hmac<Sm3>>(key, input, "SM3"),
+        #[cfg(feature = "legacy-hashes")]
+        "WHIRLPOOL" => compute_hmac_legacy::<Hmac<Whirlpool>>(key, input, "WHIRLPOOL"),
+        _ => Err(unsupported_algorithm(algorithm)),
     }
 }
 
 /// Compute HMAC-MD5
 #[inline]
-fn compute_hmac_md5(key: &[u8], input: &[u8]) -> Result<String, String> {
+fn compute_hmac_md5(key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
     type HmacMd5 = Hmac<Md5>;
     let mut mac = HmacMd5::new_from_slice(key)
         .map_err(|_| "Failed to create HMAC-MD5 instance".to_string())?;
     mac.update(input);
-    Ok(format!("{:X}", mac.finalize().into_bytes()))
+    Ok(mac.finalize().into_bytes().to_vec())
 }
 
 /// Compute HMAC-SHA1
 #[inline]
-fn compute_hmac_sha1(key: &[u8], input: &[u8]) -> Result<String, String> {
+fn compute_hmac_sha1(key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
     type HmacSha1 = Hmac<Sha1>;
     let mut mac = HmacSha1::new_from_slice(key)
         .map_err(|_| "Failed to create HMAC-SHA1 instance".to_string())?;
     mac.update(input);
-    Ok(format!("{:X}", mac.finalize().into_bytes()))
+    Ok(mac.finalize().into_bytes().to_vec())
 }
 
 /// Compute HMAC-SHA256
 #[inline]
-fn compute_hmac_sha256(key: &[u8], input: &[u8]) -> Result<String, String> {
+fn compute_hmac_sha256(key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
     type HmacSha256 = Hmac<Sha256>;
     let mut mac = HmacSha256::new_from_slice(key)
         .map_err(|_| "Failed to create HMAC-SHA256 instance".to_string())?;
     mac.update(input);
-    Ok(format!("{:X}", mac.finalize().into_bytes()))
+    Ok(mac.finalize().into_bytes().to_vec())
 }
 
 /// Compute HMAC-SHA384
 #[inline]
-fn compute_hmac_sha384(key: &[u8], input: &[u8]) -> Result<String, String> {
+fn compute_hmac_sha384(key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
     type HmacSha384 = Hmac<Sha384>;
     let mut mac = HmacSha384::new_from_slice(key)
         .map_err(|_| "Failed to create HMAC-SHA384 instance".to_string())?;
     mac.update(input);
-    Ok(format!("{:X}", mac.finalize().into_bytes()))
+    Ok(mac.finalize().into_bytes().to_vec())
 }
 
 /// Compute HMAC-SHA512
 #[inline]
-fn compute_hmac_sha512(key: &[u8], input: &[u8]) -> Result<String, String> {
+fn compute_hmac_sha512(key: &[u8], input: &[u8]) -> Result<Vec<u8>, String> {
     type HmacSha512 = Hmac<Sha512>;
     let mut mac = HmacSha512::new_from_slice(key)
         .map_err(|_| "Failed to create HMAC-SHA512 instance".to_string())?;
     mac.update(input);
-    Ok(format!("{:X}", mac.finalize().into_bytes()))
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// Compute an HMAC with one of the optional legacy digests
+#[cfg(feature = "legacy-hashes")]
+fn compute_hmac_legacy<M: Mac + hmac::digest::KeyInit>(
+    key: &[u8],
+    input: &[u8],
+    name: &str,
+) -> Result<Vec<u8>, String> {
+    let mut mac = <M as Mac>::new_from_slice(key)
+        .map_err(|_| format!("Failed to create HMAC-{} instance", name))?;
+    mac.update(input);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "legacy-hashes")]
+    const RFC2202_DATA: &[u8] = b"what do ya want for nothing?";
+
+    #[cfg(not(feature = "legacy-hashes"))]
+    #[test]
+    fn test_legacy_algorithms_need_feature() {
+        for algorithm in ["MD4", "RIPEMD160", "SM3", "WHIRLPOOL"] {
+            let err = compute_hash_raw(b"abc", algorithm).unwrap_err();
+            assert!(err.starts_with("Unsupported algorithm"), "{}", err);
+            assert!(err.ends_with("Supported: MD5, SHA1, SHA256, SHA384, SHA512"));
+            assert!(new_hasher(algorithm).is_err());
+            assert!(compute_hmac_raw(algorithm, b"key", b"abc").is_err());
+        }
+    }
+
+    #[cfg(feature = "legacy-hashes")]
+    #[test]
+    fn test_legacy_hash_vectors() {
+        let vectors: [(&str, &[u8], &str); 10] = [
+            // RFC 1320 Appendix A.5
+            ("MD4", b"", "31D6CFE0D16AE931B73C59D7E0C089C0"),
+            ("MD4", b"abc", "A448017AAF21D8525FC10AE87AA6729D"),
+            ("MD4", b"message digest", "D9130A8164549FE818874806E1C7014B"),
+            // RIPEMD-160 reference vectors
+            ("ripemd160", b"", "9C1185A5C5E9FC54612808977EE8F548B2258D31"),
+            (
+                "ripemd160",
+                b"abc",
+                "8EB208F7E05D987A9B044A8E98C6B087F15A0BFC",
+            ),
+            (
+                "RIPEMD160",
+                b"message digest",
+                "5D0689EF49D2FAE572B881B123A85FFA21595F36",
+            ),
+            // GB/T 32905-2016 Appendix A
+            (
+                "SM3",
+                b"abc",
+                "66C7F0F462EEEDD9D1F2D46BDC10E4E24167C4875CF2F7A2297DA02B8F4BA8E0",
+            ),
+            (
+                "SM3",
+                b"abcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcdabcd",
+                "DEBE9FF92275B8A138604889C18E5A4D6FDB70E5387E5765293DCBA39C0C5732",
+            ),
+            // ISO/IEC 10118-3 Whirlpool test vectors
+            (
+                "WHIRLPOOL",
+                b"",
+                "19FA61D75522A4669B44E39C1D2E1726C530232130D407F89AFEE0964997F7A73E83BE698B288FEBCF88E3E03C4F0757EA8964E59B63D93708B138CC42A66EB3",
+            ),
+            (
+                "whirlpool",
+                b"abc",
+                "4E2448A4C6F486BB16B6562C73B4020BF3043E3A731BCE721AE1B303D97E6D4C7181EEBDB6C57E277D0E34957114CBD6C797FC9D95D8B582D225292076D4EEF5",
+            ),
+        ];
+
+        for (algorithm, input, expected) in vectors {
+            assert_eq!(
+                compute_hash_bytes(input, algorithm).unwrap(),
+                expected,
+                "{}({:?})",
+                algorithm,
+                String::from_utf8_lossy(input)
+            );
+        }
+    }
+
+    #[cfg(feature = "legacy-hashes")]
+    #[test]
+    fn test_legacy_hmac_vectors() {
+        let vectors = [
+            ("MD4", "BE192C588A8E914D8A59B474A828128F"),
+            ("RIPEMD160", "DDA6C0213A485A9E24F4742064A7F033B43C4069"),
+            (
+                "SM3",
+                "2E87F1D16862E6D964B50A5200BF2B10B764FAA9680A296A2405F24BEC39F882",
+            ),
+            (
+                "WHIRLPOOL",
+                "3D595CCD1D4F4CFD045AF53BA7D5C8283FEE6DED6EAF1269071B6B4EA64800056B5077C6A942CFA1221BD4E5AED791276E5DD46A407D2B8007163D3E7CD1DE66",
+            ),
+        ];
+
+        for (algorithm, expected) in vectors {
+            assert_eq!(
+                compute_hmac_internal(algorithm, b"Jefe", RFC2202_DATA).unwrap(),
+                expected,
+                "HMAC-{}",
+                algorithm
+            );
+        }
+    }
+
+    #[cfg(feature = "legacy-hashes")]
+    #[test]
+    fn test_legacy_streaming_hasher() {
+        let mut hasher = new_hasher("whirlpool").unwrap();
+        hasher.update(b"a");
+        hasher.update(b"bc");
+        let mut digest = vec![0u8; hasher.output_size()];
+        hasher.finalize_into_reset(&mut digest).unwrap();
+        assert_eq!(digest, compute_hash_raw(b"abc", "WHIRLPOOL").unwrap());
+        assert_eq!(
+            digest,
+            [
+                0x4e, 0x24, 0x48, 0xa4, 0xc6, 0xf4, 0x86, 0xbb, 0x16, 0xb6, 0x56, 0x2c, 0x73, 0xb4,
+                0x02, 0x0b, 0xf3, 0x04, 0x3e, 0x3a, 0x73, 0x1b, 0xce, 0x72, 0x1a, 0xe1, 0xb3, 0x03,
+                0xd9, 0x7e, 0x6d, 0x4c, 0x71, 0x81, 0xee, 0xbd, 0xb6, 0xc5, 0x7e, 0x27, 0x7d, 0x0e,
+                0x34, 0x95, 0x71, 0x14, 0xcb, 0xd6, 0xc7, 0x97, 0xfc, 0x9d, 0x95, 0xd8, 0xb5, 0x82,
+                0xd2, 0x25, 0x29, 0x20, 0x76, 0xd4, 0xee, 0xf5,
+            ]
+        );
+    }
 }
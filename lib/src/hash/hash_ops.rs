@@ -21,6 +21,7 @@ use super::algorithms::compute_hash_bytes;
 /// - SHA256
 /// - SHA384
 /// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn compute_hash(
     input: *const c_char,
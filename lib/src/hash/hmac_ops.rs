@@ -26,6 +26,7 @@ use super::algorithms::compute_hmac_internal;
 /// - SHA256 (recommended)
 /// - SHA384
 /// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn compute_hmac_with_encoding(
     input: *const c_char,
@@ -129,6 +130,7 @@ pub unsafe extern "C" fn compute_hmac_with_encoding(
 /// - SHA256 (recommended)
 /// - SHA384
 /// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn compute_hmac_bytes(
     input_bytes: *const u8,
@@ -0,0 +1,289 @@
+//! Digest string formats: Subresource Integrity, multihash/multibase and RFC 6920 `ni` URIs
+
+use base64::{
+    Engine as _,
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+};
+use subtle::ConstantTimeEq;
+
+use super::algorithms::compute_hash_raw;
+use crate::otp::encode_base32;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Maps a hash algorithm to its SRI prefix and strength rank (higher is stronger).
+fn sri_algorithm(algorithm: &str) -> Option<(&'static str, &'static str, u8)> {
+    match algorithm.to_uppercase().replace('-', "").as_str() {
+        "SHA256" => Some(("sha256", "SHA256", 1)),
+        "SHA384" => Some(("sha384", "SHA384", 2)),
+        "SHA512" => Some(("sha512", "SHA512", 3)),
+        _ => None,
+    }
+}
+
+/// Computes a Subresource Integrity string such as `sha384-<base64>`.
+pub(crate) fn sri_string(bytes: &[u8], algorithm: &str) -> Result<String, String> {
+    let (prefix, hash, _) = sri_algorithm(algorithm).ok_or_else(|| {
+        format!(
+            "Unsupported SRI algorithm: {}. Supported: SHA256, SHA384, SHA512",
+            algorithm
+        )
+    })?;
+
+    let digest = compute_hash_raw(bytes, hash)?;
+    Ok(format!("{}-{}", prefix, STANDARD.encode(digest)))
+}
+
+/// Checks bytes against an SRI `integrity` value.
+///
+/// As in the SRI specification, the value may list several space-separated
+/// entries; only those using the strongest supported algorithm are considered
+/// and any one of them matching is sufficient. Entries with unknown algorithms
+/// and `?options` suffixes are ignored. Returns an error when no entry is usable.
+pub(crate) fn verify_sri(bytes: &[u8], integrity: &str) -> Result<bool, String> {
+    let entries: Vec<(&str, u8, Vec<u8>)> = integrity
+        .split_whitespace()
+        .filter_map(|entry| {
+            let entry = entry.split_once('?').map_or(entry, |(e, _)| e);
+            let (algorithm, value) = entry.split_once('-')?;
+            let (_, hash, rank) = sri_algorithm(algorithm)?;
+            let expected = STANDARD
+                .decode(value)
+                .or_else(|_| STANDARD_NO_PAD.decode(value))
+                .ok()?;
+            Some((hash, rank, expected))
+        })
+        .collect();
+
+    let strongest = entries
+        .iter()
+        .map(|(_, rank, _)| *rank)
+        .max()
+        .ok_or_else(|| "Integrity value contains no supported SHA-2 entries".to_string())?;
+
+    let mut matched = subtle::Choice::from(0);
+    for (hash, _, expected) in entries.iter().filter(|(_, rank, _)| *rank == strongest) {
+        let digest = compute_hash_raw(bytes, hash)?;
+        if digest.len() == expected.len() {
+            matched |= digest.ct_eq(expected);
+        }
+    }
+
+    Ok(matched.into())
+}
+
+/// Maps a hash algorithm to its multicodec code.
+fn multihash_code(algorithm: &str) -> Result<u64, String> {
+    match algorithm.to_uppercase().as_str() {
+        "MD5" => Ok(0xd5),
+        "SHA1" => Ok(0x11),
+        "SHA256" => Ok(0x12),
+        "SHA384" => Ok(0x20),
+        "SHA512" => Ok(0x13),
+        _ => Err(format!(
+            "Unsupported algorithm: {}. Supported: MD5, SHA1, SHA256, SHA384, SHA512",
+            algorithm
+        )),
+    }
+}
+
+/// Appends an unsigned LEB128 varint, as used by multiformats.
+fn push_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+fn encode_base58btc(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+
+    // Little-endian base-58 digits of the big-endian input
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&d| BASE58_ALPHABET[d as usize] as char),
+        )
+        .collect()
+}
+
+/// Encodes bytes with a multibase prefix.
+fn multibase_encode(bytes: &[u8], multibase: &str) -> Result<String, String> {
+    match multibase.to_lowercase().as_str() {
+        "base16" => Ok(format!(
+            "f{}",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        )),
+        "base32" => Ok(format!("b{}", encode_base32(bytes).to_lowercase())),
+        "base58btc" => Ok(format!("z{}", encode_base58btc(bytes))),
+        "base64" => Ok(format!("m{}", STANDARD_NO_PAD.encode(bytes))),
+        "base64url" => Ok(format!("u{}", URL_SAFE_NO_PAD.encode(bytes))),
+        _ => Err(format!(
+            "Unsupported multibase: {}. Supported: base16, base32, base58btc, base64, base64url",
+            multibase
+        )),
+    }
+}
+
+/// Computes a multihash (`<code><length><digest>`) and encodes it with multibase.
+pub(crate) fn multihash_string(
+    bytes: &[u8],
+    algorithm: &str,
+    multibase: &str,
+) -> Result<String, String> {
+    let code = multihash_code(algorithm)?;
+    let digest = compute_hash_raw(bytes, algorithm)?;
+
+    let mut multihash = Vec::with_capacity(digest.len() + 4);
+    push_varint(&mut multihash, code);
+    push_varint(&mut multihash, digest.len() as u64);
+    multihash.extend_from_slice(&digest);
+
+    multibase_encode(&multihash, multibase)
+}
+
+/// Maps an algorithm to its RFC 6920 name, underlying hash and truncated length.
+fn ni_algorithm(algorithm: &str) -> Result<(&'static str, &'static str, usize), String> {
+    match algorithm.to_lowercase().as_str() {
+        "sha256" | "sha-256" => Ok(("sha-256", "SHA256", 32)),
+        "sha-256-128" => Ok(("sha-256-128", "SHA256", 16)),
+        "sha-256-120" => Ok(("sha-256-120", "SHA256", 15)),
+        "sha-256-96" => Ok(("sha-256-96", "SHA256", 12)),
+        "sha-256-64" => Ok(("sha-256-64", "SHA256", 8)),
+        "sha-256-32" => Ok(("sha-256-32", "SHA256", 4)),
+        "sha384" | "sha-384" => Ok(("sha-384", "SHA384", 48)),
+        "sha512" | "sha-512" => Ok(("sha-512", "SHA512", 64)),
+        _ => Err(format!(
+            "Unsupported ni algorithm: {}. Supported: sha-256, sha-256-128, sha-256-120, sha-256-96, sha-256-64, sha-256-32, sha-384, sha-512",
+            algorithm
+        )),
+    }
+}
+
+/// Computes an RFC 6920 named-information URI such as `ni:///sha-256;<base64url>`.
+pub(crate) fn ni_uri(bytes: &[u8], algorithm: &str, authority: &str) -> Result<String, String> {
+    let (name, hash, length) = ni_algorithm(algorithm)?;
+
+    if authority.contains(['/', '?', '#']) {
+        return Err(format!("Invalid ni authority: {}", authority));
+    }
+
+    let digest = compute_hash_raw(bytes, hash)?;
+    Ok(format!(
+        "ni://{}/{};{}",
+        authority,
+        name,
+        URL_SAFE_NO_PAD.encode(&digest[..length])
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from the W3C Subresource Integrity specification
+    const SRI_EXAMPLE: &[u8] = b"alert('Hello, world.');";
+    const SRI_EXAMPLE_SHA384: &str =
+        "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO";
+
+    #[test]
+    fn test_sri_string_spec_example() {
+        assert_eq!(
+            sri_string(SRI_EXAMPLE, "SHA384").unwrap(),
+            SRI_EXAMPLE_SHA384
+        );
+        assert!(sri_string(SRI_EXAMPLE, "MD5").is_err());
+    }
+
+    #[test]
+    fn test_verify_sri_uses_strongest_algorithm() {
+        assert!(verify_sri(SRI_EXAMPLE, SRI_EXAMPLE_SHA384).unwrap());
+        assert!(verify_sri(SRI_EXAMPLE, &format!("{}?ct=text/js", SRI_EXAMPLE_SHA384)).unwrap());
+
+        // A correct sha256 entry is ignored when a (wrong) sha384 entry is present
+        let sha256 = sri_string(SRI_EXAMPLE, "sha256").unwrap();
+        let wrong = sri_string(b"other", "sha384").unwrap();
+        assert!(!verify_sri(SRI_EXAMPLE, &format!("{} {}", sha256, wrong)).unwrap());
+        assert!(
+            verify_sri(
+                SRI_EXAMPLE,
+                &format!("{} {} {}", sha256, wrong, SRI_EXAMPLE_SHA384)
+            )
+            .unwrap()
+        );
+
+        assert!(verify_sri(SRI_EXAMPLE, "md5-abc").is_err());
+    }
+
+    #[test]
+    fn test_multihash_string_sha256() {
+        let vectors = [
+            (
+                "base58btc",
+                "zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4",
+            ),
+            (
+                "base32",
+                "bciqlstjhxgju2pqiuuxffv62pwv7vree57rxuu4a52iir55m4lx432i",
+            ),
+            (
+                "base16",
+                "f1220b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+            ),
+        ];
+
+        for (multibase, expected) in vectors {
+            assert_eq!(
+                multihash_string(b"hello world", "SHA256", multibase).unwrap(),
+                expected
+            );
+        }
+        assert!(multihash_string(b"", "SHA256", "base36").is_err());
+    }
+
+    #[test]
+    fn test_base58btc_leading_zeros() {
+        assert_eq!(encode_base58btc(&[0, 0, 1]), "112");
+        assert_eq!(encode_base58btc(&[]), "");
+    }
+
+    #[test]
+    fn test_varint_multibyte_code() {
+        let mut output = Vec::new();
+        push_varint(&mut output, 0xd5);
+        assert_eq!(output, [0xd5, 0x01]);
+    }
+
+    #[test]
+    fn test_ni_uri_rfc6920_example() {
+        assert_eq!(
+            ni_uri(b"Hello World!", "sha-256", "").unwrap(),
+            "ni:///sha-256;f4OxZX_x_FO5LcGBSKHWXfwtSx-j1ncoSt3SABJtkGk"
+        );
+        assert_eq!(
+            ni_uri(b"Hello World!", "sha-256-128", "example.com").unwrap(),
+            "ni://example.com/sha-256-128;f4OxZX_x_FO5LcGBSKHWXQ"
+        );
+        assert!(ni_uri(b"", "MD5", "").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Subresource Integrity, multihash and `ni` URI digest string operations
+
+use std::os::raw::c_char;
+
+use super::integrity::{multihash_string, ni_uri, sri_string, verify_sri};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_string;
+
+/// Compute a Subresource Integrity string (e.g. `sha384-<base64>`) for raw bytes
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA256
+/// - SHA384 (recommended)
+/// - SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_sri(
+    input_bytes: *const u8,
+    input_length: usize,
+    algorithm: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let input = unsafe { slice_from_raw(input_bytes, input_length, "Input bytes") }?;
+        sri_string(input, algorithm)
+    });
+
+    finish_string(result, "digest")
+}
+
+/// Verify raw bytes against a Subresource Integrity value
+///
+/// `integrity` may contain several space-separated entries; only entries using
+/// the strongest listed algorithm are checked, and any one matching is enough.
+///
+/// Returns `true` when the bytes match. Returns `false` on mismatch, or on error
+/// (including an integrity value with no SHA-2 entries) with the reason
+/// available from `get_last_error`. A mismatch leaves no error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `integrity` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_sri_bytes(
+    input_bytes: *const u8,
+    input_length: usize,
+    integrity: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    let result = read_str(integrity, "Integrity").and_then(|integrity| {
+        let input = unsafe { slice_from_raw(input_bytes, input_length, "Input bytes") }?;
+        verify_sri(input, integrity)
+    });
+
+    match result {
+        Ok(matched) => matched,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+/// Compute a multibase-encoded multihash for raw bytes
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `algorithm` and `multibase` are valid null-terminated C strings or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+///
+/// # Supported Multibase Encodings
+/// - base16 (`f`), base32 (`b`), base58btc (`z`), base64 (`m`), base64url (`u`)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_multihash(
+    input_bytes: *const u8,
+    input_length: usize,
+    algorithm: *const c_char,
+    multibase: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let multibase = read_str(multibase, "Multibase")?;
+        let input = unsafe { slice_from_raw(input_bytes, input_length, "Input bytes") }?;
+        multihash_string(input, algorithm, multibase)
+    });
+
+    finish_string(result, "digest")
+}
+
+/// Compute an RFC 6920 named-information URI (e.g. `ni:///sha-256;<base64url>`)
+///
+/// `authority` may be null or empty to produce an `ni:///` URI without one.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `authority` is a valid null-terminated C string or null (optional)
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - sha-256 and the truncated sha-256-128, sha-256-120, sha-256-96, sha-256-64, sha-256-32
+/// - sha-384
+/// - sha-512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_ni_uri(
+    input_bytes: *const u8,
+    input_length: usize,
+    algorithm: *const c_char,
+    authority: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let authority = if authority.is_null() {
+            ""
+        } else {
+            read_str(authority, "Authority")?
+        };
+        let input = unsafe { slice_from_raw(input_bytes, input_length, "Input bytes") }?;
+        ni_uri(input, algorithm, authority)
+    });
+
+    finish_string(result, "digest")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_string(ptr: *mut c_char) -> String {
+        assert!(!ptr.is_null(), "Expected a non-null string");
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        s
+    }
+
+    #[test]
+    fn test_compute_sri_and_verify_round_trip() {
+        let input = b"console.log('hi');";
+        let algorithm = CString::new("sha512").unwrap();
+
+        let sri =
+            take_string(unsafe { compute_sri(input.as_ptr(), input.len(), algorithm.as_ptr()) });
+        assert!(sri.starts_with("sha512-"));
+
+        let integrity = CString::new(sri).unwrap();
+        assert!(unsafe { verify_sri_bytes(input.as_ptr(), input.len(), integrity.as_ptr()) });
+        assert!(!unsafe { verify_sri_bytes(b"tampered".as_ptr(), 8, integrity.as_ptr()) });
+        assert!(unsafe { crate::error::get_last_error() }.is_null());
+    }
+
+    #[test]
+    fn test_verify_sri_bytes_unusable_integrity_sets_error() {
+        let integrity = CString::new("md5-AAAA").unwrap();
+
+        assert!(!unsafe { verify_sri_bytes(std::ptr::null(), 0, integrity.as_ptr()) });
+        let error = unsafe { crate::error::get_last_error() };
+        assert!(
+            !error.is_null(),
+            "Unusable integrity value should set an error"
+        );
+        unsafe { crate::memory::free_string(error) };
+    }
+
+    #[test]
+    fn test_compute_multihash_empty_input() {
+        let algorithm = CString::new("SHA256").unwrap();
+        let multibase = CString::new("base16").unwrap();
+
+        let multihash = take_string(unsafe {
+            compute_multihash(std::ptr::null(), 0, algorithm.as_ptr(), multibase.as_ptr())
+        });
+
+        assert_eq!(
+            multihash,
+            "f1220e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_compute_ni_uri_null_authority() {
+        let input = b"Hello World!";
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let uri = take_string(unsafe {
+            compute_ni_uri(
+                input.as_ptr(),
+                input.len(),
+                algorithm.as_ptr(),
+                std::ptr::null(),
+            )
+        });
+
+        assert_eq!(
+            uri,
+            "ni:///sha-256;f4OxZX_x_FO5LcGBSKHWXfwtSx-j1ncoSt3SABJtkGk"
+        );
+    }
+
+    #[test]
+    fn test_compute_sri_null_algorithm_returns_null() {
+        let result = unsafe { compute_sri(b"x".as_ptr(), 1, std::ptr::null()) };
+        assert!(result.is_null());
+    }
+}
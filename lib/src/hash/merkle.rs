@@ -0,0 +1,311 @@
+//! RFC 6962 / RFC 9162 Merkle tree hashing and inclusion proofs
+
+use super::algorithms::compute_hash_raw;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Splits `data` into leaves, either by explicit lengths or into fixed-size chunks.
+///
+/// Explicit lengths must add up to the whole buffer. In chunk mode the final
+/// chunk may be shorter, and an empty buffer yields no leaves.
+pub(crate) fn split_leaves<'a>(
+    data: &'a [u8],
+    leaf_lengths: Option<&[usize]>,
+    chunk_size: usize,
+) -> Result<Vec<&'a [u8]>, String> {
+    match leaf_lengths {
+        Some(lengths) => {
+            let mut leaves = Vec::with_capacity(lengths.len());
+            let mut offset = 0usize;
+            for &length in lengths {
+                let end = offset
+                    .checked_add(length)
+                    .filter(|&end| end <= data.len())
+                    .ok_or_else(|| "Leaf lengths exceed the data length".to_string())?;
+                leaves.push(&data[offset..end]);
+                offset = end;
+            }
+            if offset != data.len() {
+                return Err("Leaf lengths do not cover the whole data buffer".to_string());
+            }
+            Ok(leaves)
+        }
+        None if chunk_size == 0 => Err("Chunk size must be greater than 0".to_string()),
+        None => Ok(data.chunks(chunk_size).collect()),
+    }
+}
+
+fn leaf_hash(algorithm: &str, leaf: &[u8]) -> Result<Vec<u8>, String> {
+    let mut input = Vec::with_capacity(leaf.len() + 1);
+    input.push(LEAF_PREFIX);
+    input.extend_from_slice(leaf);
+    compute_hash_raw(&input, algorithm)
+}
+
+fn node_hash(algorithm: &str, left: &[u8], right: &[u8]) -> Result<Vec<u8>, String> {
+    let mut input = Vec::with_capacity(left.len() + right.len() + 1);
+    input.push(NODE_PREFIX);
+    input.extend_from_slice(left);
+    input.extend_from_slice(right);
+    compute_hash_raw(&input, algorithm)
+}
+
+/// Largest power of two strictly less than `n` (for `n` >= 2).
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+fn subtree_root(algorithm: &str, hashes: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    match hashes.len() {
+        0 => compute_hash_raw(&[], algorithm),
+        1 => Ok(hashes[0].clone()),
+        n => {
+            let k = split_point(n);
+            let left = subtree_root(algorithm, &hashes[..k])?;
+            let right = subtree_root(algorithm, &hashes[k..])?;
+            node_hash(algorithm, &left, &right)
+        }
+    }
+}
+
+fn leaf_hashes(algorithm: &str, leaves: &[&[u8]]) -> Result<Vec<Vec<u8>>, String> {
+    leaves
+        .iter()
+        .map(|leaf| leaf_hash(algorithm, leaf))
+        .collect()
+}
+
+/// Computes the Merkle tree head (root hash) over the leaves.
+pub(crate) fn merkle_root(algorithm: &str, leaves: &[&[u8]]) -> Result<Vec<u8>, String> {
+    subtree_root(algorithm, &leaf_hashes(algorithm, leaves)?)
+}
+
+fn audit_path(
+    algorithm: &str,
+    index: usize,
+    hashes: &[Vec<u8>],
+    path: &mut Vec<Vec<u8>>,
+) -> Result<(), String> {
+    let n = hashes.len();
+    if n <= 1 {
+        return Ok(());
+    }
+
+    let k = split_point(n);
+    if index < k {
+        audit_path(algorithm, index, &hashes[..k], path)?;
+        path.push(subtree_root(algorithm, &hashes[k..])?);
+    } else {
+        audit_path(algorithm, index - k, &hashes[k..], path)?;
+        path.push(subtree_root(algorithm, &hashes[..k])?);
+    }
+    Ok(())
+}
+
+/// Computes the inclusion proof (audit path) for the leaf at `index`, leaf-side first.
+pub(crate) fn inclusion_proof(
+    algorithm: &str,
+    leaves: &[&[u8]],
+    index: usize,
+) -> Result<Vec<Vec<u8>>, String> {
+    if index >= leaves.len() {
+        return Err(format!(
+            "Leaf index {} is out of range for a tree of {} leaves",
+            index,
+            leaves.len()
+        ));
+    }
+
+    let mut path = Vec::new();
+    audit_path(
+        algorithm,
+        index,
+        &leaf_hashes(algorithm, leaves)?,
+        &mut path,
+    )?;
+    Ok(path)
+}
+
+/// Verifies an inclusion proof using the RFC 9162 section 2.1.3.2 algorithm.
+///
+/// `proof` is the concatenation of the audit path hashes. Returns `Ok(false)`
+/// when the proof does not lead to `root`, and an error for malformed input.
+pub(crate) fn verify_inclusion(
+    algorithm: &str,
+    leaf: &[u8],
+    index: u64,
+    tree_size: u64,
+    proof: &[u8],
+    root: &[u8],
+) -> Result<bool, String> {
+    if index >= tree_size {
+        return Err(format!(
+            "Leaf index {} is out of range for a tree of {} leaves",
+            index, tree_size
+        ));
+    }
+
+    let hash_size = compute_hash_raw(&[], algorithm)?.len();
+    if !proof.len().is_multiple_of(hash_size) {
+        return Err(format!(
+            "Proof length must be a multiple of the {}-byte hash size",
+            hash_size
+        ));
+    }
+
+    let mut f_n = index;
+    let mut s_n = tree_size - 1;
+    let mut r = leaf_hash(algorithm, leaf)?;
+
+    for p in proof.chunks(hash_size) {
+        if s_n == 0 {
+            return Ok(false);
+        }
+
+        if f_n & 1 == 1 || f_n == s_n {
+            r = node_hash(algorithm, p, &r)?;
+            while f_n & 1 == 0 && f_n != 0 {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            r = node_hash(algorithm, &r, p)?;
+        }
+
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    Ok(s_n == 0 && r == root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Leaves from the certificate-transparency reference test data
+    const LEAVES: [&[u8]; 8] = [
+        b"",
+        b"\x00",
+        b"\x10",
+        b"\x20\x21",
+        b"\x30\x31",
+        b"\x40\x41\x42\x43",
+        b"\x50\x51\x52\x53\x54\x55\x56\x57",
+        b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+    ];
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_merkle_root_reference_vectors() {
+        let vectors = [
+            (
+                0,
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                1,
+                "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+            ),
+            (
+                5,
+                "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+            ),
+            (
+                8,
+                "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+            ),
+        ];
+
+        for (size, expected) in vectors {
+            assert_eq!(
+                hex(&merkle_root("SHA256", &LEAVES[..size]).unwrap()),
+                expected,
+                "Root of {} leaves",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_reference_vector() {
+        let proof = inclusion_proof("SHA256", &LEAVES[..5], 2).unwrap();
+        let proof: Vec<String> = proof.iter().map(|p| hex(p)).collect();
+
+        assert_eq!(
+            proof,
+            [
+                "07506a85fd9dd2f120eb694f86011e5bb4662e5c415a62917033d4a9624487e7",
+                "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+                "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_proof_verifies() {
+        for size in 1..=LEAVES.len() {
+            let leaves = &LEAVES[..size];
+            let root = merkle_root("SHA256", leaves).unwrap();
+            for index in 0..size {
+                let proof = inclusion_proof("SHA256", leaves, index).unwrap().concat();
+                assert!(
+                    verify_inclusion(
+                        "SHA256",
+                        leaves[index],
+                        index as u64,
+                        size as u64,
+                        &proof,
+                        &root
+                    )
+                    .unwrap(),
+                    "Leaf {} of {} should verify",
+                    index,
+                    size
+                );
+                assert!(
+                    !verify_inclusion(
+                        "SHA256",
+                        b"forged",
+                        index as u64,
+                        size as u64,
+                        &proof,
+                        &root
+                    )
+                    .unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_leaves_modes() {
+        let data = b"abcdefghij";
+        assert_eq!(
+            split_leaves(data, None, 4).unwrap(),
+            [&b"abcd"[..], b"efgh", b"ij"]
+        );
+        assert_eq!(
+            split_leaves(data, Some(&[3, 0, 7]), 0).unwrap(),
+            [&b"abc"[..], b"", b"defghij"]
+        );
+        assert!(split_leaves(data, Some(&[3, 3]), 0).is_err());
+        assert!(split_leaves(data, Some(&[30]), 0).is_err());
+        assert!(split_leaves(data, None, 0).is_err());
+        assert!(split_leaves(b"", None, 4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_inclusion_rejects_bad_input() {
+        assert!(verify_inclusion("SHA256", b"", 3, 3, &[], &[]).is_err());
+        assert!(verify_inclusion("SHA256", b"", 0, 2, &[0u8; 31], &[]).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Merkle tree root, inclusion proof and proof verification operations
+
+use std::os::raw::c_char;
+
+use super::merkle::{inclusion_proof, merkle_root, split_leaves, verify_inclusion};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_bytes;
+
+/// Reads the data buffer and optional leaf length array describing the leaves.
+unsafe fn read_leaf_inputs<'a>(
+    data: *const u8,
+    data_length: usize,
+    leaf_lengths: *const usize,
+    leaf_count: usize,
+) -> Result<(&'a [u8], Option<&'a [usize]>), String> {
+    let data = unsafe { slice_from_raw(data, data_length, "Data") }?;
+    let lengths = if leaf_lengths.is_null() {
+        None
+    } else {
+        Some(unsafe { std::slice::from_raw_parts(leaf_lengths, leaf_count) })
+    };
+    Ok((data, lengths))
+}
+
+/// Compute an RFC 6962 Merkle tree root over a list of leaves
+///
+/// The leaves are laid out back to back in `data`. When `leaf_lengths` is
+/// non-null it holds `leaf_count` lengths that must add up to `data_length`;
+/// when it is null, `data` is split into `chunk_size`-byte chunks (the last may
+/// be shorter). Leaves are hashed as `H(0x00 || leaf)` and nodes as
+/// `H(0x01 || left || right)`; an empty tree's root is the hash of no data.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `data` is a valid pointer to at least `data_length` bytes, or null if length is 0
+/// - `leaf_lengths` is a valid pointer to `leaf_count` lengths, or null for chunk mode
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to write the output length
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256 (standard), SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_merkle_root(
+    data: *const u8,
+    data_length: usize,
+    leaf_lengths: *const usize,
+    leaf_count: usize,
+    chunk_size: usize,
+    algorithm: *const c_char,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let (data, lengths) =
+            unsafe { read_leaf_inputs(data, data_length, leaf_lengths, leaf_count) }?;
+        let leaves = split_leaves(data, lengths, chunk_size)?;
+        merkle_root(algorithm, &leaves)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Compute the inclusion proof for one leaf of an RFC 6962 Merkle tree
+///
+/// Leaves are described exactly as for `compute_merkle_root`. The proof is
+/// returned as the audit path hashes concatenated, leaf-side first, ready to
+/// pass to `verify_merkle_proof`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `data` is a valid pointer to at least `data_length` bytes, or null if length is 0
+/// - `leaf_lengths` is a valid pointer to `leaf_count` lengths, or null for chunk mode
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to write the output length
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_merkle_proof(
+    data: *const u8,
+    data_length: usize,
+    leaf_lengths: *const usize,
+    leaf_count: usize,
+    chunk_size: usize,
+    leaf_index: usize,
+    algorithm: *const c_char,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let (data, lengths) =
+            unsafe { read_leaf_inputs(data, data_length, leaf_lengths, leaf_count) }?;
+        let leaves = split_leaves(data, lengths, chunk_size)?;
+        Ok(inclusion_proof(algorithm, &leaves, leaf_index)?.concat())
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Verify an RFC 6962 Merkle inclusion proof
+///
+/// Checks that `leaf` is the leaf at `leaf_index` of a tree with `tree_size`
+/// leaves and root `root`, using the concatenated audit path in `proof`.
+///
+/// Returns `true` when the proof is valid. Returns `false` when it is not, or on
+/// error (such as an out-of-range index or truncated proof) with the reason
+/// available from `get_last_error`. An invalid proof leaves no error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `leaf`, `proof` and `root` are valid pointers to at least their stated lengths, or null if the length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_merkle_proof(
+    leaf: *const u8,
+    leaf_length: usize,
+    leaf_index: u64,
+    tree_size: u64,
+    proof: *const u8,
+    proof_length: usize,
+    root: *const u8,
+    root_length: usize,
+    algorithm: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let leaf = unsafe { slice_from_raw(leaf, leaf_length, "Leaf") }?;
+        let proof = unsafe { slice_from_raw(proof, proof_length, "Proof") }?;
+        let root = unsafe { slice_from_raw(root, root_length, "Root") }?;
+        verify_inclusion(algorithm, leaf, leaf_index, tree_size, proof, root)
+    });
+
+    match result {
+        Ok(valid) => valid,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_bytes(ptr: *mut u8, length: usize) -> Vec<u8> {
+        assert!(!ptr.is_null(), "Expected non-null bytes");
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, length).to_vec() };
+        unsafe { crate::memory::free_bytes(ptr) };
+        bytes
+    }
+
+    #[test]
+    fn test_chunked_root_proof_and_verify() {
+        let data = b"abcdefghij";
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut root_length = 0;
+        let mut proof_length = 0;
+
+        let root = take_bytes(
+            unsafe {
+                compute_merkle_root(
+                    data.as_ptr(),
+                    data.len(),
+                    std::ptr::null(),
+                    0,
+                    4,
+                    algorithm.as_ptr(),
+                    &mut root_length,
+                )
+            },
+            root_length,
+        );
+        let proof = take_bytes(
+            unsafe {
+                compute_merkle_proof(
+                    data.as_ptr(),
+                    data.len(),
+                    std::ptr::null(),
+                    0,
+                    4,
+                    2,
+                    algorithm.as_ptr(),
+                    &mut proof_length,
+                )
+            },
+            proof_length,
+        );
+
+        let root_hex: String = root.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            root_hex,
+            "2a5b33d54d89d05737a7dd798d9862d55951564aafb5460691ad8a7a9ab6c678"
+        );
+        assert_eq!(proof.len(), 32);
+
+        let verify = |leaf: &[u8]| unsafe {
+            verify_merkle_proof(
+                leaf.as_ptr(),
+                leaf.len(),
+                2,
+                3,
+                proof.as_ptr(),
+                proof.len(),
+                root.as_ptr(),
+                root.len(),
+                algorithm.as_ptr(),
+            )
+        };
+        assert!(verify(b"ij"));
+        assert!(!verify(b"xx"));
+    }
+
+    #[test]
+    fn test_explicit_leaf_lengths_match_chunks() {
+        let data = b"abcdefghij";
+        let lengths = [4usize, 4, 2];
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut length = 0;
+
+        let root = take_bytes(
+            unsafe {
+                compute_merkle_root(
+                    data.as_ptr(),
+                    data.len(),
+                    lengths.as_ptr(),
+                    lengths.len(),
+                    0,
+                    algorithm.as_ptr(),
+                    &mut length,
+                )
+            },
+            length,
+        );
+
+        assert_eq!(root[0], 0x2a);
+    }
+
+    #[test]
+    fn test_compute_merkle_proof_out_of_range_returns_null() {
+        let data = b"abcd";
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut length = 99;
+
+        let result = unsafe {
+            compute_merkle_proof(
+                data.as_ptr(),
+                data.len(),
+                std::ptr::null(),
+                0,
+                2,
+                2,
+                algorithm.as_ptr(),
+                &mut length,
+            )
+        };
+
+        assert!(result.is_null());
+        assert_eq!(length, 0);
+    }
+}
@@ -1,9 +1,24 @@
-//! Cryptographic hash functions (MD5, SHA1, SHA256, SHA384, SHA512, HMAC)
+//! Cryptographic hash functions (MD5, SHA1, SHA256, SHA384, SHA512, HMAC), verification,
+//! digest string formats (SRI, multihash, ni URIs) and Merkle trees
 
 mod algorithms;
 mod hash_ops;
 mod hmac_ops;
+mod integrity;
+mod integrity_ops;
+mod merkle;
+mod merkle_ops;
+mod multi;
+mod multi_ops;
+mod verify_ops;
 
 // Re-export public FFI functions
 pub use hash_ops::compute_hash;
 pub use hmac_ops::{compute_hmac_bytes, compute_hmac_with_encoding};
+pub use integrity_ops::{compute_multihash, compute_ni_uri, compute_sri, verify_sri_bytes};
+pub use merkle_ops::{compute_merkle_proof, compute_merkle_root, verify_merkle_proof};
+pub use multi_ops::{compute_file_hashes, compute_hashes};
+pub use verify_ops::{verify_hash, verify_hmac};
+
+// Re-export hash and HMAC helpers for use by other modules
+pub(crate) use algorithms::{compute_hash_raw, compute_hmac_raw, new_hasher};
@@ -0,0 +1,242 @@
+//! Computing several digests over one pass of the input
+
+use sha2::digest::DynDigest;
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::thread::ScopedJoinHandle;
+
+use super::algorithms::new_hasher;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Chunks buffered per worker before the reader waits for hashing to catch up.
+const CHANNEL_DEPTH: usize = 4;
+
+/// Inputs shorter than this are hashed on the calling thread, where spawning
+/// a worker per algorithm would cost more than it saves.
+const PARALLEL_THRESHOLD: usize = 1024 * 1024;
+
+/// Parses a comma- or space-separated algorithm list into unique uppercase names.
+pub(crate) fn parse_algorithm_list(list: &str) -> Result<Vec<String>, String> {
+    let mut algorithms: Vec<String> = Vec::new();
+
+    for name in list
+        .split([',', ';', ' '])
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        let name = name.to_uppercase().replace('-', "");
+        new_hasher(&name)?;
+        if !algorithms.contains(&name) {
+            algorithms.push(name);
+        }
+    }
+
+    if algorithms.is_empty() {
+        return Err("At least one algorithm must be specified".to_string());
+    }
+    Ok(algorithms)
+}
+
+fn finish(mut hasher: Box<dyn DynDigest + Send>) -> String {
+    let mut digest = vec![0u8; hasher.output_size()];
+    // The buffer is sized from the hasher itself, so this cannot fail
+    let _ = hasher.finalize_into_reset(&mut digest);
+    digest.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Waits for a worker, turning a panic into an error instead of unwinding
+/// into the caller.
+fn join_worker(handle: ScopedJoinHandle<'_, String>) -> Result<String, String> {
+    handle
+        .join()
+        .map_err(|_| "Hash worker thread panicked".to_string())
+}
+
+/// Hashes a buffer with every algorithm in turn on the calling thread.
+fn hash_serial(bytes: &[u8], hashers: Vec<Box<dyn DynDigest + Send>>) -> Vec<String> {
+    hashers
+        .into_iter()
+        .map(|mut hasher| {
+            hasher.update(bytes);
+            finish(hasher)
+        })
+        .collect()
+}
+
+/// Hashes an in-memory buffer with every algorithm, one thread per algorithm.
+///
+/// Buffers below [`PARALLEL_THRESHOLD`] are hashed on the calling thread.
+/// Returns `(algorithm, uppercase hex digest)` pairs in the order requested.
+pub(crate) fn hash_bytes_multi(
+    bytes: &[u8],
+    algorithms: &[String],
+) -> Result<Vec<(String, String)>, String> {
+    let hashers = algorithms
+        .iter()
+        .map(|a| new_hasher(a))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if bytes.len() < PARALLEL_THRESHOLD {
+        let digests = hash_serial(bytes, hashers);
+        return Ok(algorithms.iter().cloned().zip(digests).collect());
+    }
+
+    let digests = std::thread::scope(|scope| {
+        let handles: Vec<_> = hashers
+            .into_iter()
+            .map(|mut hasher| {
+                scope.spawn(move || {
+                    hasher.update(bytes);
+                    finish(hasher)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(join_worker)
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(algorithms.iter().cloned().zip(digests).collect())
+}
+
+/// Hashes a stream with every algorithm while reading it only once.
+///
+/// The reader runs on the calling thread and hands each chunk to one worker
+/// per algorithm, so the digests are computed in parallel with the I/O.
+/// Streams that end before [`PARALLEL_THRESHOLD`] are hashed on the calling
+/// thread instead.
+pub(crate) fn hash_reader_multi<R: Read>(
+    mut reader: R,
+    algorithms: &[String],
+) -> Result<Vec<(String, String)>, String> {
+    let hashers = algorithms
+        .iter()
+        .map(|a| new_hasher(a))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut head = Vec::new();
+    (&mut reader)
+        .take(PARALLEL_THRESHOLD as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| format!("Failed to read input: {}", e))?;
+    if head.len() < PARALLEL_THRESHOLD {
+        let digests = hash_serial(&head, hashers);
+        return Ok(algorithms.iter().cloned().zip(digests).collect());
+    }
+    let head = Arc::new(head);
+
+    let digests = std::thread::scope(|scope| -> Result<Vec<String>, String> {
+        let mut senders = Vec::with_capacity(hashers.len());
+        let mut handles = Vec::with_capacity(hashers.len());
+
+        for mut hasher in hashers {
+            let (sender, receiver) = mpsc::sync_channel::<Arc<Vec<u8>>>(CHANNEL_DEPTH);
+            // The channel has room for this first chunk, so sending cannot block
+            let _ = sender.send(Arc::clone(&head));
+            senders.push(sender);
+            handles.push(scope.spawn(move || {
+                for chunk in receiver {
+                    hasher.update(&chunk);
+                }
+                finish(hasher)
+            }));
+        }
+
+        let read_result = loop {
+            let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+            match reader.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(read) => {
+                    buffer.truncate(read);
+                    let chunk = Arc::new(buffer);
+                    for sender in &senders {
+                        // Workers only stop once their sender is dropped
+                        let _ = sender.send(Arc::clone(&chunk));
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(format!("Failed to read input: {}", e)),
+            }
+        };
+
+        drop(senders);
+        let digests = handles
+            .into_iter()
+            .map(join_worker)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        read_result.map(|_| digests)
+    })?;
+
+    Ok(algorithms.iter().cloned().zip(digests).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::algorithms::compute_hash_bytes;
+
+    #[test]
+    fn test_parse_algorithm_list() {
+        assert_eq!(
+            parse_algorithm_list("md5, SHA-1;sha256 sha256").unwrap(),
+            ["MD5", "SHA1", "SHA256"]
+        );
+        assert!(parse_algorithm_list(" , ").is_err());
+        assert!(parse_algorithm_list("SHA256,CRC32").is_err());
+    }
+
+    #[test]
+    fn test_multi_hash_matches_single_hashes() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let algorithms = parse_algorithm_list("MD5,SHA1,SHA256,SHA384,SHA512").unwrap();
+
+        let from_bytes = hash_bytes_multi(&data, &algorithms).unwrap();
+        let from_reader = hash_reader_multi(&data[..], &algorithms).unwrap();
+
+        assert_eq!(from_bytes, from_reader);
+        for (algorithm, digest) in from_bytes {
+            assert_eq!(digest, compute_hash_bytes(&data, &algorithm).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_multi_hash_large_input_uses_workers() {
+        let data: Vec<u8> = (0..3 * PARALLEL_THRESHOLD as u32 + 17)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let algorithms = parse_algorithm_list("MD5,SHA256,SHA512").unwrap();
+
+        let from_bytes = hash_bytes_multi(&data, &algorithms).unwrap();
+        let from_reader = hash_reader_multi(&data[..], &algorithms).unwrap();
+
+        assert_eq!(from_bytes, from_reader);
+        for (algorithm, digest) in from_bytes {
+            assert_eq!(digest, compute_hash_bytes(&data, &algorithm).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_join_worker_reports_panic() {
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| -> String { panic!("worker failed") });
+            assert_eq!(
+                join_worker(handle).unwrap_err(),
+                "Hash worker thread panicked"
+            );
+        });
+    }
+
+    #[test]
+    fn test_multi_hash_empty_input() {
+        let algorithms = parse_algorithm_list("SHA256").unwrap();
+        let result = hash_reader_multi(std::io::empty(), &algorithms).unwrap();
+        assert_eq!(
+            result[0].1,
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Multi-algorithm hashing operations
+
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::BufReader;
+use std::os::raw::c_char;
+
+use super::multi::{hash_bytes_multi, hash_reader_multi, parse_algorithm_list};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_string;
+
+/// Renders digests as a JSON object mapping algorithm names to digests.
+fn digests_json(digests: Vec<(String, String)>) -> String {
+    let json: Map<String, Value> = digests
+        .into_iter()
+        .map(|(algorithm, digest)| (algorithm, Value::String(digest)))
+        .collect();
+    Value::Object(json).to_string()
+}
+
+/// Compute several hashes of raw bytes in one call
+///
+/// `algorithms` is a comma-separated list such as `MD5,SHA1,SHA256,SHA512`;
+/// duplicates are ignored. Inputs of 1 MiB or more are hashed with one thread
+/// per algorithm over the same buffer. The result is a JSON object mapping each algorithm name
+/// (uppercase) to its uppercase hexadecimal digest.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `algorithms` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_hashes(
+    input_bytes: *const u8,
+    input_length: usize,
+    algorithms: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithms, "Algorithms").and_then(|algorithms| {
+        let algorithms = parse_algorithm_list(algorithms)?;
+        let input = unsafe { slice_from_raw(input_bytes, input_length, "Input bytes") }?;
+        hash_bytes_multi(input, &algorithms)
+    });
+
+    finish_string(result.map(digests_json), "hash results")
+}
+
+/// Compute several hashes of a file while reading it only once
+///
+/// The file is streamed in chunks, each shared with one worker thread per
+/// algorithm, so large files are neither loaded into memory nor read more than
+/// once. Files under 1 MiB are hashed on the calling thread. The result has
+/// the same JSON shape as `compute_hashes`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `path` and `algorithms` are valid null-terminated C strings or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_file_hashes(
+    path: *const c_char,
+    algorithms: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(path, "Path").and_then(|path| {
+        let algorithms = parse_algorithm_list(read_str(algorithms, "Algorithms")?)?;
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        hash_reader_multi(BufReader::new(file), &algorithms)
+    });
+
+    finish_string(result.map(digests_json), "hash results")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_json(ptr: *mut c_char) -> Value {
+        assert!(!ptr.is_null(), "Expected a non-null string");
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        serde_json::from_str(&s).unwrap()
+    }
+
+    #[test]
+    fn test_compute_hashes_known_vectors() {
+        let input = b"abc";
+        let algorithms = CString::new("MD5,SHA1,SHA256").unwrap();
+
+        let json =
+            take_json(unsafe { compute_hashes(input.as_ptr(), input.len(), algorithms.as_ptr()) });
+
+        assert_eq!(json["MD5"], "900150983CD24FB0D6963F7D28E17F72");
+        assert_eq!(json["SHA1"], "A9993E364706816ABA3E25717850C26C9CD0D89D");
+        assert_eq!(
+            json["SHA256"],
+            "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+        );
+    }
+
+    #[test]
+    fn test_compute_file_hashes() {
+        let path = std::env::temp_dir().join(format!(
+            "convert_core_multi_hash_{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, b"abc").unwrap();
+        let path_c = CString::new(path.to_str().unwrap()).unwrap();
+        let algorithms = CString::new("sha-512, md5").unwrap();
+
+        let json = take_json(unsafe { compute_file_hashes(path_c.as_ptr(), algorithms.as_ptr()) });
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(json["MD5"], "900150983CD24FB0D6963F7D28E17F72");
+        assert!(json["SHA512"].as_str().unwrap().starts_with("DDAF35A1"));
+    }
+
+    #[test]
+    fn test_compute_file_hashes_missing_file_returns_null() {
+        let path = CString::new("/nonexistent/convert_core/file.bin").unwrap();
+        let algorithms = CString::new("SHA256").unwrap();
+
+        let result = unsafe { compute_file_hashes(path.as_ptr(), algorithms.as_ptr()) };
+
+        assert!(result.is_null(), "Missing file should return null");
+    }
+
+    #[test]
+    fn test_compute_hashes_unsupported_algorithm_returns_null() {
+        let algorithms = CString::new("SHA256,CRC32").unwrap();
+
+        let result = unsafe { compute_hashes(b"abc".as_ptr(), 3, algorithms.as_ptr()) };
+
+        assert!(result.is_null());
+    }
+}
@@ -0,0 +1,463 @@
+//! Constant-time hash and HMAC verification operations
+
+use base64::{Engine as _, engine::general_purpose};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use subtle::ConstantTimeEq;
+
+use super::algorithms::{compute_hash_raw, compute_hmac_raw};
+
+/// Decode an expected digest supplied as hexadecimal (any case) or Base64.
+///
+/// Hexadecimal is tried first when the length matches the digest size, so a
+/// digest whose hex form happens to be valid Base64 is still read as hex.
+fn decode_expected_digest(expected: &str, digest_length: usize) -> Result<Vec<u8>, String> {
+    let trimmed = expected.trim();
+
+    if trimmed.len() == digest_length * 2 && trimmed.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return decode_hex(trimmed);
+    }
+
+    general_purpose::STANDARD
+        .decode(trimmed)
+        .or_else(|_| general_purpose::URL_SAFE.decode(trimmed))
+        .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(trimmed))
+        .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(trimmed))
+        .map_err(|_| "Expected value is not valid hexadecimal or Base64".to_string())
+}
+
+/// Decode a hexadecimal string of even length into bytes.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("Hexadecimal string has odd length".to_string());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| "Invalid hexadecimal character".to_string())
+        })
+        .collect()
+}
+
+/// Compare a computed digest against an expected value in constant time.
+///
+/// Length differences are not secret (digest sizes are public), so only the
+/// byte comparison itself is performed in constant time.
+fn digest_matches(computed: &[u8], expected: &str) -> Result<bool, String> {
+    let expected_bytes = decode_expected_digest(expected, computed.len())?;
+
+    if computed.len() != expected_bytes.len() {
+        return Ok(false);
+    }
+
+    Ok(computed.ct_eq(&expected_bytes).into())
+}
+
+/// Verify an HMAC over raw bytes against an expected value
+///
+/// Computes the HMAC of `input_bytes` with `key` and compares it to `expected`
+/// in constant time. The expected value may be hexadecimal (any case) or Base64
+/// (standard or URL-safe, padded or unpadded).
+///
+/// Returns `true` when the MAC matches. Returns `false` on mismatch, or on error
+/// with the reason available from `get_last_error`. A mismatch leaves no error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input_bytes` is a valid pointer to a byte array of at least `input_length` bytes, or null if length is 0
+/// - `key` is a valid pointer to a byte array of at least `key_length` bytes or null
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `expected` is a valid null-terminated C string or null
+///
+/// # Supported Algorithms
+/// - MD5 (not recommended for security-critical applications)
+/// - SHA1 (not recommended for security-critical applications)
+/// - SHA256 (recommended)
+/// - SHA384
+/// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_hmac(
+    input_bytes: *const u8,
+    input_length: usize,
+    key: *const u8,
+    key_length: usize,
+    algorithm: *const c_char,
+    expected: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    if key.is_null() {
+        crate::error::set_error("Key pointer is null".to_string());
+        return false;
+    }
+
+    if algorithm.is_null() {
+        crate::error::set_error("Algorithm pointer is null".to_string());
+        return false;
+    }
+
+    if expected.is_null() {
+        crate::error::set_error("Expected value pointer is null".to_string());
+        return false;
+    }
+
+    let algorithm_str = match unsafe { CStr::from_ptr(algorithm).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in algorithm string".to_string());
+            return false;
+        }
+    };
+
+    let expected_str = match unsafe { CStr::from_ptr(expected).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in expected value string".to_string());
+            return false;
+        }
+    };
+
+    let input_slice = if input_length == 0 {
+        &[]
+    } else {
+        if input_bytes.is_null() {
+            crate::error::set_error("Input bytes pointer is null".to_string());
+            return false;
+        }
+        unsafe { std::slice::from_raw_parts(input_bytes, input_length) }
+    };
+
+    let key_slice = unsafe { std::slice::from_raw_parts(key, key_length) };
+
+    let mac = match compute_hmac_raw(algorithm_str, key_slice, input_slice) {
+        Ok(mac) => mac,
+        Err(e) => {
+            crate::error::set_error(e);
+            return false;
+        }
+    };
+
+    match digest_matches(&mac, expected_str) {
+        Ok(matches) => matches,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+/// Verify a cryptographic hash of a string against an expected value
+///
+/// Converts `input` to bytes using `encoding`, hashes it and compares the digest
+/// to `expected` in constant time. The expected value may be hexadecimal (any case)
+/// or Base64 (standard or URL-safe, padded or unpadded).
+///
+/// Returns `true` when the digest matches. Returns `false` on mismatch, or on error
+/// with the reason available from `get_last_error`. A mismatch leaves no error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `input` is a valid null-terminated C string or null
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `encoding` is a valid null-terminated C string or null
+/// - `expected` is a valid null-terminated C string or null
+///
+/// # Supported Algorithms
+/// - MD5
+/// - SHA1
+/// - SHA256
+/// - SHA384
+/// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_hash(
+    input: *const c_char,
+    algorithm: *const c_char,
+    encoding: *const c_char,
+    expected: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    if input.is_null() {
+        crate::error::set_error("Input pointer is null".to_string());
+        return false;
+    }
+
+    if algorithm.is_null() {
+        crate::error::set_error("Algorithm pointer is null".to_string());
+        return false;
+    }
+
+    if encoding.is_null() {
+        crate::error::set_error("Encoding pointer is null".to_string());
+        return false;
+    }
+
+    if expected.is_null() {
+        crate::error::set_error("Expected value pointer is null".to_string());
+        return false;
+    }
+
+    let input_str = match unsafe { CStr::from_ptr(input).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in input string".to_string());
+            return false;
+        }
+    };
+
+    let algorithm_str = match unsafe { CStr::from_ptr(algorithm).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in algorithm string".to_string());
+            return false;
+        }
+    };
+
+    let encoding_str = match unsafe { CStr::from_ptr(encoding).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in encoding string".to_string());
+            return false;
+        }
+    };
+
+    let expected_str = match unsafe { CStr::from_ptr(expected).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in expected value string".to_string());
+            return false;
+        }
+    };
+
+    let bytes = match crate::base64::convert_string_to_bytes(input_str, encoding_str) {
+        Ok(b) => b,
+        Err(e) => {
+            crate::error::set_error(e);
+            return false;
+        }
+    };
+
+    let digest = match compute_hash_raw(&bytes, algorithm_str) {
+        Ok(digest) => digest,
+        Err(e) => {
+            crate::error::set_error(e);
+            return false;
+        }
+    };
+
+    match digest_matches(&digest, expected_str) {
+        Ok(matches) => matches,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    const HMAC_SHA256_TEST_SECRET: &str =
+        "0329A06B62CD16B33EB6792BE8C60B158D89A2EE3A876FCE9A881EBB488C0914";
+
+    fn verify_hmac_str(input: &[u8], key: &[u8], algorithm: &str, expected: &str) -> bool {
+        let algorithm = CString::new(algorithm).unwrap();
+        let expected = CString::new(expected).unwrap();
+        unsafe {
+            verify_hmac(
+                input.as_ptr(),
+                input.len(),
+                key.as_ptr(),
+                key.len(),
+                algorithm.as_ptr(),
+                expected.as_ptr(),
+            )
+        }
+    }
+
+    fn last_error_is_set() -> bool {
+        let ptr = unsafe { crate::error::get_last_error() };
+        let is_set = !ptr.is_null();
+        unsafe { crate::memory::free_string(ptr) };
+        is_set
+    }
+
+    #[test]
+    fn test_verify_hmac_uppercase_hex_matches() {
+        assert!(verify_hmac_str(
+            b"test",
+            b"secret",
+            "SHA256",
+            HMAC_SHA256_TEST_SECRET
+        ));
+        assert!(!last_error_is_set(), "Match should not set an error");
+    }
+
+    #[test]
+    fn test_verify_hmac_lowercase_hex_matches() {
+        assert!(verify_hmac_str(
+            b"test",
+            b"secret",
+            "sha256",
+            &HMAC_SHA256_TEST_SECRET.to_lowercase()
+        ));
+    }
+
+    #[test]
+    fn test_verify_hmac_base64_matches() {
+        // Base64 of the HMAC-SHA256 bytes for 'test' keyed with 'secret'
+        assert!(verify_hmac_str(
+            b"test",
+            b"secret",
+            "SHA256",
+            "Aymga2LNFrM+tnkr6MYLFY2Jou46h2/Omogeu0iMCRQ="
+        ));
+        assert!(verify_hmac_str(
+            b"test",
+            b"secret",
+            "SHA256",
+            "Aymga2LNFrM-tnkr6MYLFY2Jou46h2_Omogeu0iMCRQ"
+        ));
+    }
+
+    #[test]
+    fn test_verify_hmac_mismatch_returns_false_without_error() {
+        let mut tampered = HMAC_SHA256_TEST_SECRET.to_string();
+        tampered.replace_range(0..1, "1");
+
+        assert!(!verify_hmac_str(b"test", b"secret", "SHA256", &tampered));
+        assert!(!last_error_is_set(), "Mismatch should not set an error");
+    }
+
+    #[test]
+    fn test_verify_hmac_truncated_expected_returns_false() {
+        assert!(!verify_hmac_str(
+            b"test",
+            b"secret",
+            "SHA256",
+            &HMAC_SHA256_TEST_SECRET[..32]
+        ));
+    }
+
+    #[test]
+    fn test_verify_hmac_invalid_expected_sets_error() {
+        assert!(!verify_hmac_str(
+            b"test",
+            b"secret",
+            "SHA256",
+            "not a digest!"
+        ));
+        assert!(
+            last_error_is_set(),
+            "Unparseable expected value should set an error"
+        );
+    }
+
+    #[test]
+    fn test_verify_hmac_unsupported_algorithm_sets_error() {
+        assert!(!verify_hmac_str(
+            b"test",
+            b"secret",
+            "UNSUPPORTED",
+            HMAC_SHA256_TEST_SECRET
+        ));
+        assert!(last_error_is_set());
+    }
+
+    #[test]
+    fn test_verify_hmac_null_expected_returns_false() {
+        let input = b"test";
+        let key = b"secret";
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let result = unsafe {
+            verify_hmac(
+                input.as_ptr(),
+                input.len(),
+                key.as_ptr(),
+                key.len(),
+                algorithm.as_ptr(),
+                std::ptr::null(),
+            )
+        };
+
+        assert!(!result, "Null expected value should return false");
+        assert!(last_error_is_set());
+    }
+
+    #[test]
+    fn test_verify_hash_known_vectors() {
+        let test_cases = vec![
+            ("MD5", "098f6bcd4621d373cade4e832627b4f6"),
+            ("SHA1", "A94A8FE5CCB19BA61C4C0873D391E987982FBBD3"),
+            ("SHA256", "n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg="),
+        ];
+
+        for (algorithm, expected) in test_cases {
+            let input = CString::new("test").unwrap();
+            let algo = CString::new(algorithm).unwrap();
+            let encoding = CString::new("UTF8").unwrap();
+            let expected = CString::new(expected).unwrap();
+
+            let result = unsafe {
+                verify_hash(
+                    input.as_ptr(),
+                    algo.as_ptr(),
+                    encoding.as_ptr(),
+                    expected.as_ptr(),
+                )
+            };
+
+            assert!(result, "{} digest of 'test' should verify", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_verify_hash_mismatch_returns_false() {
+        let input = CString::new("test2").unwrap();
+        let algorithm = CString::new("SHA256").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let expected =
+            CString::new("9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08")
+                .unwrap();
+
+        let result = unsafe {
+            verify_hash(
+                input.as_ptr(),
+                algorithm.as_ptr(),
+                encoding.as_ptr(),
+                expected.as_ptr(),
+            )
+        };
+
+        assert!(!result, "Digest of different input should not verify");
+        assert!(!last_error_is_set());
+    }
+
+    #[test]
+    fn test_verify_hash_null_input_returns_false() {
+        let algorithm = CString::new("SHA256").unwrap();
+        let encoding = CString::new("UTF8").unwrap();
+        let expected = CString::new("00").unwrap();
+
+        let result = unsafe {
+            verify_hash(
+                std::ptr::null(),
+                algorithm.as_ptr(),
+                encoding.as_ptr(),
+                expected.as_ptr(),
+            )
+        };
+
+        assert!(!result, "Null input should return false");
+        assert!(last_error_is_set());
+    }
+}
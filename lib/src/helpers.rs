@@ -0,0 +1,83 @@
+//! Argument and result helpers shared by the FFI exports
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+use crate::encoding::read_str;
+
+/// Reads an optional C string argument, treating null as absent.
+pub(crate) fn read_optional_str<'a>(
+    ptr: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        Ok(None)
+    } else {
+        read_str(ptr, name).map(Some)
+    }
+}
+
+/// Converts a string into a caller-owned C string pointer for an output argument.
+pub(crate) fn into_c_string(value: &str, context: &str) -> Result<*mut c_char, String> {
+    CString::new(value)
+        .map(CString::into_raw)
+        .map_err(|_| format!("Failed to create C string from {}", context))
+}
+
+/// Returns bytes to the caller, or records the error and returns null.
+pub(crate) fn finish_bytes(result: Result<Vec<u8>, String>, out_length: *mut usize) -> *mut u8 {
+    match result {
+        Ok(bytes) => {
+            if !out_length.is_null() {
+                unsafe {
+                    *out_length = bytes.len();
+                }
+            }
+            crate::error::clear_error();
+            crate::memory::allocate_byte_array(bytes)
+        }
+        Err(e) => {
+            crate::error::set_error(e);
+            crate::encoding::set_output_length_zero(out_length);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Reports success to the caller, or records the error and returns false.
+pub(crate) fn finish_bool(result: Result<(), String>) -> bool {
+    match result {
+        Ok(()) => {
+            crate::error::clear_error();
+            true
+        }
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+/// Returns a string result to the caller, or records the error and returns null.
+///
+/// `context` names the value in the error raised when it contains a NUL byte.
+pub(crate) fn finish_string(result: Result<String, String>, context: &str) -> *mut c_char {
+    let value = match result {
+        Ok(s) => s,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    match CString::new(value) {
+        Ok(c_str) => {
+            crate::error::clear_error();
+            c_str.into_raw()
+        }
+        Err(_) => {
+            crate::error::set_error(format!("Failed to create C string from {}", context));
+            std::ptr::null_mut()
+        }
+    }
+}
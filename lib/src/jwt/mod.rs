@@ -0,0 +1,7 @@
+//! JSON Web Token functions (HS256/HS384/HS512 compact JWS)
+
+mod algorithms;
+mod jwt_ops;
+
+// Re-export public FFI functions
+pub use jwt_ops::{jwt_decode, jwt_sign, jwt_validate_claims, jwt_verify};
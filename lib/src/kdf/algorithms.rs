@@ -0,0 +1,435 @@
+//! Core key derivation algorithm implementations
+
+use argon2::{Argon2, Params as Argon2Params, Version};
+use hkdf::SimpleHkdf;
+use md5::Md5;
+use password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use pbkdf2::{Pbkdf2, pbkdf2_hmac};
+use scrypt::{Params as ScryptParams, Scrypt};
+use sha1::Sha1;
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+#[cfg(feature = "legacy-hashes")]
+use md4::Md4;
+#[cfg(feature = "legacy-hashes")]
+use ripemd::Ripemd160;
+#[cfg(feature = "legacy-hashes")]
+use sm3::Sm3;
+#[cfg(feature = "legacy-hashes")]
+use whirlpool::Whirlpool;
+
+/// Maps a PBKDF2 hash name to its PHC algorithm identifier.
+fn pbkdf2_algorithm(algorithm: &str) -> Result<pbkdf2::Algorithm, String> {
+    match algorithm.to_uppercase().as_str() {
+        "SHA1" => Ok(pbkdf2::Algorithm::Pbkdf2Sha1),
+        "SHA256" => Ok(pbkdf2::Algorithm::Pbkdf2Sha256),
+        "SHA512" => Ok(pbkdf2::Algorithm::Pbkdf2Sha512),
+        _ => Err(unsupported_pbkdf2_algorithm(algorithm)),
+    }
+}
+
+fn unsupported_pbkdf2_algorithm(algorithm: &str) -> String {
+    format!(
+        "Unsupported PBKDF2 algorithm: {}. Supported: SHA1, SHA256, SHA512",
+        algorithm
+    )
+}
+
+/// Largest key, in bytes, that the PBKDF2, scrypt and Argon2id functions derive.
+const MAX_KEY_LENGTH: usize = 1024;
+
+/// Validates the requested output length for raw key derivation.
+fn validate_key_length(key_length: usize) -> Result<(), String> {
+    if key_length == 0 || key_length > MAX_KEY_LENGTH {
+        return Err(format!(
+            "Key length must be between 1 and {} bytes",
+            MAX_KEY_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// Highest PBKDF2 iteration count accepted from a PHC string.
+const PHC_MAX_PBKDF2_ITERATIONS: u64 = 10_000_000;
+
+/// Most working memory, in bytes, scrypt may use (1 GiB).
+const SCRYPT_MAX_MEMORY_BYTES: u64 = 1 << 30;
+
+/// Highest scrypt parallelism accepted.
+const SCRYPT_MAX_PARALLELISM: u64 = 64;
+
+/// Highest Argon2 memory cost, in KiB, accepted for derivation, hashing and
+/// untrusted parameters such as PHC strings and encryption envelopes (1 GiB).
+pub(crate) const ARGON2_MAX_MEMORY_KIB: u32 = 1024 * 1024;
+
+/// Highest Argon2 pass count accepted.
+pub(crate) const ARGON2_MAX_ITERATIONS: u32 = 64;
+
+/// Highest Argon2 lane count accepted.
+pub(crate) const ARGON2_MAX_PARALLELISM: u32 = 64;
+
+/// Fails when `value` is above `max`, naming the parameter in the error.
+fn check_at_most(name: &str, value: u64, max: u64) -> Result<(), String> {
+    if value > max {
+        return Err(format!(
+            "{} of {} exceeds the maximum of {}",
+            name, value, max
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects scrypt costs beyond the maximums above, so a request cannot
+/// demand more memory than can be allocated without aborting.
+fn check_scrypt_cost(log_n: u64, r: u64, p: u64) -> Result<(), String> {
+    check_at_most("scrypt parallelism", p, SCRYPT_MAX_PARALLELISM)?;
+    // scrypt needs 128 * r * 2^log_n bytes of working memory
+    let memory = match log_n {
+        0..64 => (128 * u128::from(r)) << log_n,
+        _ => u128::MAX,
+    };
+    if memory > u128::from(SCRYPT_MAX_MEMORY_BYTES) {
+        return Err(format!(
+            "scrypt parameters log_n={} and r={} need more than {} bytes of memory",
+            log_n, r, SCRYPT_MAX_MEMORY_BYTES
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects Argon2 costs beyond the maximums above.
+fn check_argon2_cost(memory_kib: u64, iterations: u64, parallelism: u64) -> Result<(), String> {
+    check_at_most(
+        "Argon2 memory cost (KiB)",
+        memory_kib,
+        ARGON2_MAX_MEMORY_KIB.into(),
+    )?;
+    check_at_most(
+        "Argon2 iterations",
+        iterations,
+        ARGON2_MAX_ITERATIONS.into(),
+    )?;
+    check_at_most(
+        "Argon2 parallelism",
+        parallelism,
+        ARGON2_MAX_PARALLELISM.into(),
+    )
+}
+
+/// Rejects PHC cost parameters beyond the maximums above, so a crafted hash
+/// cannot demand unbounded memory or time during verification.
+fn check_phc_params(hash: &PasswordHash) -> Result<(), String> {
+    let param = |name: &str, default: u64| {
+        hash.params.get(name).map_or(Ok(default), |value| {
+            value
+                .decimal()
+                .map(u64::from)
+                .map_err(|_| format!("PHC parameter {} must be a decimal number", name))
+        })
+    };
+
+    match hash.algorithm.as_str() {
+        "argon2id" | "argon2i" | "argon2d" => {
+            check_argon2_cost(param("m", 0)?, param("t", 0)?, param("p", 0)?)
+        }
+        "scrypt" => check_scrypt_cost(param("ln", 0)?, param("r", 8)?, param("p", 0)?),
+        "pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => {
+            check_at_most(
+                "PBKDF2 iterations",
+                param("i", 0)?,
+                PHC_MAX_PBKDF2_ITERATIONS,
+            )?;
+            check_at_most("PBKDF2 key length", param("l", 0)?, MAX_KEY_LENGTH as u64)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Minimum raw salt length accepted for PHC strings.
+const PHC_SALT_MIN_BYTES: usize = 4;
+
+/// Maximum raw salt length accepted for PHC strings (64 Base64 characters).
+const PHC_SALT_MAX_BYTES: usize = 48;
+
+/// Encodes raw salt bytes for use in a PHC string.
+fn phc_salt(salt: &[u8]) -> Result<SaltString, String> {
+    if !(PHC_SALT_MIN_BYTES..=PHC_SALT_MAX_BYTES).contains(&salt.len()) {
+        return Err(format!(
+            "Salt must be between {} and {} bytes for PHC strings",
+            PHC_SALT_MIN_BYTES, PHC_SALT_MAX_BYTES
+        ));
+    }
+    SaltString::encode_b64(salt).map_err(|e| format!("Invalid salt for PHC string: {}", e))
+}
+
+fn scrypt_params(log_n: u8, r: u32, p: u32, key_length: usize) -> Result<ScryptParams, String> {
+    check_scrypt_cost(log_n.into(), r.into(), p.into())?;
+    ScryptParams::new(log_n, r, p, key_length)
+        .map_err(|e| format!("Invalid scrypt parameters: {}", e))
+}
+
+fn argon2id(
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    key_length: usize,
+) -> Result<Argon2<'static>, String> {
+    check_argon2_cost(memory_kib.into(), iterations.into(), parallelism.into())?;
+    let params = Argon2Params::new(memory_kib, iterations, parallelism, Some(key_length))
+        .map_err(|e| format!("Invalid Argon2id parameters: {}", e))?;
+    Ok(Argon2::new(
+        argon2::Algorithm::Argon2id,
+        Version::V0x13,
+        params,
+    ))
+}
+
+/// Derives a key using PBKDF2-HMAC with the specified hash algorithm.
+pub(crate) fn pbkdf2_derive(
+    algorithm: &str,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    key_length: usize,
+) -> Result<Vec<u8>, String> {
+    validate_key_length(key_length)?;
+    if iterations == 0 {
+        return Err("Iterations must be greater than 0".to_string());
+    }
+
+    let mut key = vec![0u8; key_length];
+    match algorithm.to_uppercase().as_str() {
+        "SHA1" => pbkdf2_hmac::<Sha1>(password, salt, iterations, &mut key),
+        "SHA256" => pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut key),
+        "SHA512" => pbkdf2_hmac::<Sha512>(password, salt, iterations, &mut key),
+        _ => return Err(unsupported_pbkdf2_algorithm(algorithm)),
+    }
+    Ok(key)
+}
+
+/// Derives a key using scrypt with cost parameters N = 2^`log_n`, `r` and `p`.
+pub(crate) fn scrypt_derive(
+    password: &[u8],
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+    key_length: usize,
+) -> Result<Vec<u8>, String> {
+    validate_key_length(key_length)?;
+    let params = scrypt_params(log_n, r, p, key_length)?;
+
+    let mut key = vec![0u8; key_length];
+    scrypt::scrypt(password, salt, &params, &mut key)
+        .map_err(|e| format!("scrypt derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Derives a key using Argon2id (version 0x13).
+pub(crate) fn argon2id_derive(
+    password: &[u8],
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    key_length: usize,
+) -> Result<Vec<u8>, String> {
+    validate_key_length(key_length)?;
+    let hasher = argon2id(memory_kib, iterations, parallelism, key_length)?;
+
+    let mut key = vec![0u8; key_length];
+    hasher
+        .hash_password_into(password, salt, &mut key)
+        .map_err(|e| format!("Argon2id derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Hashes a password with PBKDF2-HMAC and returns a PHC-format string.
+pub(crate) fn pbkdf2_phc(
+    algorithm: &str,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    key_length: usize,
+) -> Result<String, String> {
+    validate_key_length(key_length)?;
+    let ident = pbkdf2_algorithm(algorithm)?.ident();
+    let salt = phc_salt(salt)?;
+    let params = pbkdf2::Params {
+        rounds: iterations,
+        output_length: key_length,
+    };
+
+    Pbkdf2
+        .hash_password_customized(password, Some(ident), None, params, &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("PBKDF2 password hashing failed: {}", e))
+}
+
+/// Hashes a password with scrypt and returns a PHC-format string.
+pub(crate) fn scrypt_phc(
+    password: &[u8],
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+    key_length: usize,
+) -> Result<String, String> {
+    validate_key_length(key_length)?;
+    let params = scrypt_params(log_n, r, p, key_length)?;
+    let salt = phc_salt(salt)?;
+
+    Scrypt
+        .hash_password_customized(password, None, None, params, &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("scrypt password hashing failed: {}", e))
+}
+
+/// Hashes a password with Argon2id and returns a PHC-format string.
+pub(crate) fn argon2id_phc(
+    password: &[u8],
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    key_length: usize,
+) -> Result<String, String> {
+    validate_key_length(key_length)?;
+    let hasher = argon2id(memory_kib, iterations, parallelism, key_length)?;
+    let salt = phc_salt(salt)?;
+
+    hasher
+        .hash_password(password, &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Argon2id password hashing failed: {}", e))
+}
+
+/// Verifies a password against a PHC-format string.
+///
+/// Returns `Ok(false)` when the password does not match, and `Err` when the
+/// PHC string is malformed or names an unsupported algorithm.
+pub(crate) fn verify_phc(password: &[u8], phc: &str) -> Result<bool, String> {
+    let hash = PasswordHash::new(phc).map_err(|e| format!("Invalid PHC string: {}", e))?;
+    check_phc_params(&hash)?;
+
+    let result = match hash.algorithm.as_str() {
+        "argon2id" | "argon2i" | "argon2d" => Argon2::default().verify_password(password, &hash),
+        "scrypt" => Scrypt.verify_password(password, &hash),
+        "pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => Pbkdf2.verify_password(password, &hash),
+        other => {
+            return Err(format!(
+                "Unsupported PHC algorithm: {}. Supported: argon2id, argon2i, argon2d, scrypt, pbkdf2, pbkdf2-sha256, pbkdf2-sha512",
+                other
+            ));
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(format!("Password verification failed: {}", e)),
+    }
+}
+
+/// Runs HKDF-Extract for a concrete hash, returning the pseudorandom key.
+fn hkdf_extract_with<H: Digest + BlockSizeUser + Clone>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let salt = if salt.is_empty() { None } else { Some(salt) };
+    let (prk, _) = SimpleHkdf::<H>::extract(salt, ikm);
+    prk.to_vec()
+}
+
+/// Runs HKDF-Expand for a concrete hash, validating the PRK and output lengths.
+fn hkdf_expand_with<H: Digest + BlockSizeUser + Clone>(
+    prk: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    let hash_length = <H as Digest>::output_size();
+    let max_length = 255 * hash_length;
+    if length == 0 || length > max_length {
+        return Err(format!(
+            "Output length must be between 1 and {} bytes for this algorithm",
+            max_length
+        ));
+    }
+
+    let hkdf = SimpleHkdf::<H>::from_prk(prk).map_err(|_| {
+        format!(
+            "Pseudorandom key must be at least {} bytes for this algorithm",
+            hash_length
+        )
+    })?;
+
+    let mut okm = vec![0u8; length];
+    hkdf.expand(info, &mut okm)
+        .map_err(|e| format!("HKDF expand failed: {}", e))?;
+    Ok(okm)
+}
+
+/// Performs HKDF-Extract (RFC 5869) using the specified hash algorithm.
+///
+/// An empty salt is treated as absent, which RFC 5869 defines as HashLen zero bytes.
+pub(crate) fn hkdf_extract_internal(
+    algorithm: &str,
+    salt: &[u8],
+    ikm: &[u8],
+) -> Result<Vec<u8>, String> {
+    match algorithm.to_uppercase().as_str() {
+        "MD5" => Ok(hkdf_extract_with::<Md5>(salt, ikm)),
+        "SHA1" => Ok(hkdf_extract_with::<Sha1>(salt, ikm)),
+        "SHA256" => Ok(hkdf_extract_with::<Sha256>(salt, ikm)),
+        "SHA384" => Ok(hkdf_extract_with::<Sha384>(salt, ikm)),
+        "SHA512" => Ok(hkdf_extract_with::<Sha512>(salt, ikm)),
+        #[cfg(feature = "legacy-hashes")]
+        "MD4" => Ok(hkdf_extract_with::<Md4>(salt, ikm)),
+        #[cfg(feature = "legacy-hashes")]
+        "RIPEMD160" => Ok(hkdf_extract_with::<Ripemd160>(salt, ikm)),
+        #[cfg(feature = "legacy-hashes")]
+        "SM3" => Ok(hkdf_extract_with::<Sm3>(salt, ikm)),
+        #[cfg(feature = "legacy-hashes")]
+        "WHIRLPOOL" => Ok(hkdf_extract_with::<Whirlpool>(salt, ikm)),
+        _ => Err(unsupported_hkdf_algorithm(algorithm)),
+    }
+}
+
+/// Performs HKDF-Expand (RFC 5869) using the specified hash algorithm.
+///
+/// `length` must be between 1 and 255 * HashLen bytes.
+pub(crate) fn hkdf_expand_internal(
+    algorithm: &str,
+    prk: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    match algorithm.to_uppercase().as_str() {
+        "MD5" => hkdf_expand_with::<Md5>(prk, info, length),
+        "SHA1" => hkdf_expand_with::<Sha1>(prk, info, length),
+        "SHA256" => hkdf_expand_with::<Sha256>(prk, info, length),
+        "SHA384" => hkdf_expand_with::<Sha384>(prk, info, length),
+        "SHA512" => hkdf_expand_with::<Sha512>(prk, info, length),
+        #[cfg(feature = "legacy-hashes")]
+        "MD4" => hkdf_expand_with::<Md4>(prk, info, length),
+        #[cfg(feature = "legacy-hashes")]
+        "RIPEMD160" => hkdf_expand_with::<Ripemd160>(prk, info, length),
+        #[cfg(feature = "legacy-hashes")]
+        "SM3" => hkdf_expand_with::<Sm3>(prk, info, length),
+        #[cfg(feature = "legacy-hashes")]
+        "WHIRLPOOL" => hkdf_expand_with::<Whirlpool>(prk, info, length),
+        _ => Err(unsupported_hkdf_algorithm(algorithm)),
+    }
+}
+
+#[cfg(not(feature = "legacy-hashes"))]
+const SUPPORTED_HKDF_ALGORITHMS: &str = "MD5, SHA1, SHA256, SHA384, SHA512";
+
+#[cfg(feature = "legacy-hashes")]
+const SUPPORTED_HKDF_ALGORITHMS: &str =
+    "MD5, SHA1, SHA256, SHA384, SHA512, MD4, RIPEMD160, SM3, WHIRLPOOL";
+
+fn unsupported_hkdf_algorithm(algorithm: &str) -> String {
+    format!(
+        "Unsupported HKDF algorithm: {}. Supported: {}",
+        algorithm, SUPPORTED_HKDF_ALGORITHMS
+    )
+}
@@ -0,0 +1,475 @@
+//! Raw key derivation operations (PBKDF2, scrypt, Argon2id)
+
+use std::os::raw::c_char;
+
+use super::algorithms::{argon2id_derive, pbkdf2_derive, scrypt_derive};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_bytes;
+
+/// Derive a key using PBKDF2-HMAC
+///
+/// `key_length` must be between 1 and 1024 bytes.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - SHA1 (for legacy interoperability only)
+/// - SHA256
+/// - SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn derive_pbkdf2(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    algorithm: *const c_char,
+    iterations: u32,
+    key_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let pw = unsafe { slice_from_raw(password, password_length, "Password") }?;
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        pbkdf2_derive(algorithm, pw, salt, iterations, key_length)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Derive a key using scrypt
+///
+/// The CPU/memory cost is N = 2^`log_n`; `r` is the block size and `p` the
+/// parallelisation factor. `key_length` must be between 1 and 1024 bytes.
+/// `p` may be at most 64, and the working memory of 128 * `r` * N bytes at
+/// most 1 GiB.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn derive_scrypt(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    key_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = unsafe { slice_from_raw(password, password_length, "Password") }.and_then(|pw| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        scrypt_derive(pw, salt, log_n, r, p, key_length)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Derive a key using Argon2id (version 0x13)
+///
+/// `memory_kib` is the memory cost in KiB, `iterations` the time cost and
+/// `parallelism` the number of lanes. The salt must be at least 8 bytes and
+/// `key_length` between 1 and 1024 bytes. `memory_kib` may be at most 1 GiB
+/// (1048576), and `iterations` and `parallelism` at most 64 each.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn derive_argon2id(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    key_length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let result = unsafe { slice_from_raw(password, password_length, "Password") }.and_then(|pw| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        argon2id_derive(pw, salt, memory_kib, iterations, parallelism, key_length)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_bytes(ptr: *mut u8, length: usize) -> Vec<u8> {
+        assert!(!ptr.is_null(), "Derivation should not return null");
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, length) }.to_vec();
+        unsafe { crate::memory::free_bytes(ptr) };
+        bytes
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_derive_pbkdf2_rfc6070_vector() {
+        // RFC 6070 test vector: P = "password", S = "salt", c = 2, dkLen = 20
+        let password = b"password";
+        let salt = b"salt";
+        let algorithm = CString::new("SHA1").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                algorithm.as_ptr(),
+                2,
+                20,
+                &mut out_length,
+            )
+        };
+
+        let key = take_bytes(ptr, out_length);
+        assert_eq!(to_hex(&key), "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957");
+    }
+
+    #[test]
+    fn test_derive_pbkdf2_sha256_vector() {
+        let password = b"password";
+        let salt = b"salt";
+        let algorithm = CString::new("sha256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                algorithm.as_ptr(),
+                1,
+                32,
+                &mut out_length,
+            )
+        };
+
+        let key = take_bytes(ptr, out_length);
+        assert_eq!(
+            to_hex(&key),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+    }
+
+    #[test]
+    fn test_derive_pbkdf2_zero_iterations_returns_null() {
+        let password = b"password";
+        let salt = b"salt";
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 99usize;
+
+        let ptr = unsafe {
+            derive_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                algorithm.as_ptr(),
+                0,
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Zero iterations should return null");
+        assert_eq!(out_length, 0);
+    }
+
+    #[test]
+    fn test_derive_pbkdf2_oversized_key_returns_null() {
+        let password = b"password";
+        let salt = b"salt";
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 99usize;
+
+        for key_length in [1025, 1 << 62] {
+            let ptr = unsafe {
+                derive_pbkdf2(
+                    password.as_ptr(),
+                    password.len(),
+                    salt.as_ptr(),
+                    salt.len(),
+                    algorithm.as_ptr(),
+                    1,
+                    key_length,
+                    &mut out_length,
+                )
+            };
+
+            assert!(ptr.is_null(), "Oversized key length should return null");
+            assert_eq!(out_length, 0);
+        }
+    }
+
+    #[test]
+    fn test_derive_pbkdf2_unsupported_algorithm_returns_null() {
+        let password = b"password";
+        let salt = b"salt";
+        let algorithm = CString::new("MD5").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                algorithm.as_ptr(),
+                1,
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Unsupported algorithm should return null");
+    }
+
+    #[test]
+    fn test_derive_scrypt_rfc7914_vector() {
+        // RFC 7914 test vector: P = "password", S = "NaCl", N = 1024, r = 8, p = 16
+        let password = b"password";
+        let salt = b"NaCl";
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_scrypt(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                10,
+                8,
+                16,
+                64,
+                &mut out_length,
+            )
+        };
+
+        let key = take_bytes(ptr, out_length);
+        assert_eq!(
+            to_hex(&key),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+             2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn test_derive_scrypt_invalid_params_returns_null() {
+        let password = b"password";
+        let salt = b"NaCl";
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_scrypt(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                10,
+                0,
+                1,
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Zero block size should return null");
+    }
+
+    #[test]
+    fn test_derive_argon2id_is_deterministic() {
+        let password = [0x01u8; 32];
+        let salt = [0x02u8; 16];
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_argon2id(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                32,
+                3,
+                4,
+                32,
+                &mut out_length,
+            )
+        };
+
+        let key = take_bytes(ptr, out_length);
+        assert_eq!(key.len(), 32);
+
+        let ptr_again = unsafe {
+            derive_argon2id(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                32,
+                3,
+                4,
+                32,
+                &mut out_length,
+            )
+        };
+        assert_eq!(
+            key,
+            take_bytes(ptr_again, out_length),
+            "Argon2id derivation should be deterministic"
+        );
+    }
+
+    #[test]
+    fn test_derive_argon2id_short_salt_returns_null() {
+        let password = b"password";
+        let salt = b"salt";
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_argon2id(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                32,
+                1,
+                1,
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(
+            ptr.is_null(),
+            "Salt shorter than 8 bytes should return null"
+        );
+    }
+
+    fn assert_error_set(ptr: *mut u8, out_length: usize, expected: &str) {
+        assert!(ptr.is_null());
+        assert_eq!(out_length, 0);
+        let error = unsafe { crate::error::get_last_error() };
+        assert!(!error.is_null());
+        let message = unsafe { std::ffi::CStr::from_ptr(error) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { crate::memory::free_string(error) };
+        assert!(message.contains(expected), "{}", message);
+    }
+
+    #[test]
+    fn test_derive_scrypt_excessive_cost_sets_error() {
+        let password = b"password";
+        let salt = b"NaCl";
+
+        for (log_n, r, p, expected) in [
+            (63, 8, 1, "bytes of memory"),
+            (21, 8, 1, "bytes of memory"),
+            (10, 8, 65, "scrypt parallelism"),
+        ] {
+            let mut out_length = 99usize;
+            let ptr = unsafe {
+                derive_scrypt(
+                    password.as_ptr(),
+                    password.len(),
+                    salt.as_ptr(),
+                    salt.len(),
+                    log_n,
+                    r,
+                    p,
+                    32,
+                    &mut out_length,
+                )
+            };
+            assert_error_set(ptr, out_length, expected);
+        }
+    }
+
+    #[test]
+    fn test_derive_argon2id_excessive_cost_sets_error() {
+        let password = b"password";
+        let salt = b"somesalt";
+
+        for (memory_kib, iterations, parallelism, expected) in [
+            (u32::MAX, 1, 1, "Argon2 memory cost"),
+            (64, 65, 1, "Argon2 iterations"),
+            (1024, 1, 65, "Argon2 parallelism"),
+        ] {
+            let mut out_length = 99usize;
+            let ptr = unsafe {
+                derive_argon2id(
+                    password.as_ptr(),
+                    password.len(),
+                    salt.as_ptr(),
+                    salt.len(),
+                    memory_kib,
+                    iterations,
+                    parallelism,
+                    32,
+                    &mut out_length,
+                )
+            };
+            assert_error_set(ptr, out_length, expected);
+        }
+    }
+
+    #[test]
+    fn test_derive_null_password_with_nonzero_length_returns_null() {
+        let salt = b"saltsalt";
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            derive_argon2id(
+                std::ptr::null(),
+                8,
+                salt.as_ptr(),
+                salt.len(),
+                32,
+                1,
+                1,
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(
+            ptr.is_null(),
+            "Null password with length should return null"
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! HKDF (RFC 5869) extract, expand and derive operations
+
+use std::os::raw::c_char;
+
+use super::algorithms::{hkdf_expand_internal, hkdf_extract_internal};
+use crate::encoding::{read_str, set_output_length_zero, slice_from_raw};
+use crate::helpers::finish_bytes;
+
+/// Perform HKDF-Extract, returning the pseudorandom key (PRK)
+///
+/// An empty salt is treated as absent, which RFC 5869 defines as HashLen zero bytes.
+/// The returned PRK is HashLen bytes long.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `ikm` is a valid pointer to at least `ikm_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - MD5 (not recommended for security-critical applications)
+/// - SHA1 (not recommended for security-critical applications)
+/// - SHA256 (recommended)
+/// - SHA384
+/// - SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hkdf_extract(
+    ikm: *const u8,
+    ikm_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    algorithm: *const c_char,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let algorithm_str = match read_str(algorithm, "Algorithm") {
+        Ok(s) => s,
+        Err(e) => {
+            crate::error::set_error(e);
+            set_output_length_zero(out_length);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let result = unsafe { slice_from_raw(ikm, ikm_length, "Input key material") }.and_then(|ikm| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        hkdf_extract_internal(algorithm_str, salt, ikm)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Perform HKDF-Expand, returning `length` bytes of output keying material
+///
+/// `prk` must be at least HashLen bytes and `length` must be between 1 and
+/// 255 * HashLen bytes.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `prk` is a valid pointer to at least `prk_length` bytes, or null if length is 0
+/// - `info` is a valid pointer to at least `info_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hkdf_expand(
+    prk: *const u8,
+    prk_length: usize,
+    info: *const u8,
+    info_length: usize,
+    algorithm: *const c_char,
+    length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let algorithm_str = match read_str(algorithm, "Algorithm") {
+        Ok(s) => s,
+        Err(e) => {
+            crate::error::set_error(e);
+            set_output_length_zero(out_length);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let result = unsafe { slice_from_raw(prk, prk_length, "PRK") }.and_then(|prk| {
+        let info = unsafe { slice_from_raw(info, info_length, "Info") }?;
+        hkdf_expand_internal(algorithm_str, prk, info, length)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+/// Perform HKDF-Extract followed by HKDF-Expand in a single call
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `ikm` is a valid pointer to at least `ikm_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `info` is a valid pointer to at least `info_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+/// - MD4, RIPEMD160, SM3, WHIRLPOOL (only with the `legacy-hashes` cargo feature)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hkdf_derive(
+    ikm: *const u8,
+    ikm_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    info: *const u8,
+    info_length: usize,
+    algorithm: *const c_char,
+    length: usize,
+    out_length: *mut usize,
+) -> *mut u8 {
+    crate::error::clear_error();
+
+    let algorithm_str = match read_str(algorithm, "Algorithm") {
+        Ok(s) => s,
+        Err(e) => {
+            crate::error::set_error(e);
+            set_output_length_zero(out_length);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let result = unsafe { slice_from_raw(ikm, ikm_length, "Input key material") }.and_then(|ikm| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        let info = unsafe { slice_from_raw(info, info_length, "Info") }?;
+        let prk = hkdf_extract_internal(algorithm_str, salt, ikm)?;
+        hkdf_expand_internal(algorithm_str, &prk, info, length)
+    });
+
+    finish_bytes(result, out_length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_bytes(ptr: *mut u8, length: usize) -> Vec<u8> {
+        assert!(!ptr.is_null(), "HKDF should not return null");
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, length) }.to_vec();
+        unsafe { crate::memory::free_bytes(ptr) };
+        bytes
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // RFC 5869 Appendix A.1 (Test Case 1)
+    const IKM: [u8; 22] = [0x0b; 22];
+    const SALT: [u8; 13] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    const INFO: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+    const PRK_HEX: &str = "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5";
+    const OKM_HEX: &str =
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+
+    #[test]
+    fn test_hkdf_extract_rfc5869_case1() {
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_extract(
+                IKM.as_ptr(),
+                IKM.len(),
+                SALT.as_ptr(),
+                SALT.len(),
+                algorithm.as_ptr(),
+                &mut out_length,
+            )
+        };
+
+        assert_eq!(to_hex(&take_bytes(ptr, out_length)), PRK_HEX);
+    }
+
+    #[test]
+    fn test_hkdf_expand_rfc5869_case1() {
+        let prk: Vec<u8> = (0..PRK_HEX.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&PRK_HEX[i..i + 2], 16).unwrap())
+            .collect();
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_expand(
+                prk.as_ptr(),
+                prk.len(),
+                INFO.as_ptr(),
+                INFO.len(),
+                algorithm.as_ptr(),
+                42,
+                &mut out_length,
+            )
+        };
+
+        assert_eq!(to_hex(&take_bytes(ptr, out_length)), OKM_HEX);
+    }
+
+    #[test]
+    fn test_hkdf_derive_rfc5869_case1() {
+        let algorithm = CString::new("sha256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_derive(
+                IKM.as_ptr(),
+                IKM.len(),
+                SALT.as_ptr(),
+                SALT.len(),
+                INFO.as_ptr(),
+                INFO.len(),
+                algorithm.as_ptr(),
+                42,
+                &mut out_length,
+            )
+        };
+
+        assert_eq!(to_hex(&take_bytes(ptr, out_length)), OKM_HEX);
+    }
+
+    #[test]
+    fn test_hkdf_derive_rfc5869_case7_sha1_no_salt() {
+        // RFC 5869 Appendix A.7: SHA-1, IKM = 0x0c * 22, salt not provided, info empty
+        let ikm = [0x0cu8; 22];
+        let algorithm = CString::new("SHA1").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_derive(
+                ikm.as_ptr(),
+                ikm.len(),
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                algorithm.as_ptr(),
+                42,
+                &mut out_length,
+            )
+        };
+
+        assert_eq!(
+            to_hex(&take_bytes(ptr, out_length)),
+            "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48"
+        );
+    }
+
+    #[test]
+    fn test_hkdf_derive_all_algorithms_max_length() {
+        let test_cases = vec![
+            ("MD5", 16),
+            ("SHA1", 20),
+            ("SHA256", 32),
+            ("SHA384", 48),
+            ("SHA512", 64),
+        ];
+
+        for (algo, hash_length) in test_cases {
+            let algorithm = CString::new(algo).unwrap();
+            let mut out_length = 0usize;
+
+            let ptr = unsafe {
+                hkdf_derive(
+                    IKM.as_ptr(),
+                    IKM.len(),
+                    SALT.as_ptr(),
+                    SALT.len(),
+                    INFO.as_ptr(),
+                    INFO.len(),
+                    algorithm.as_ptr(),
+                    255 * hash_length,
+                    &mut out_length,
+                )
+            };
+            assert_eq!(
+                take_bytes(ptr, out_length).len(),
+                255 * hash_length,
+                "HKDF-{} should allow 255 * HashLen bytes",
+                algo
+            );
+
+            let too_long = unsafe {
+                hkdf_derive(
+                    IKM.as_ptr(),
+                    IKM.len(),
+                    SALT.as_ptr(),
+                    SALT.len(),
+                    INFO.as_ptr(),
+                    INFO.len(),
+                    algorithm.as_ptr(),
+                    255 * hash_length + 1,
+                    &mut out_length,
+                )
+            };
+            assert!(
+                too_long.is_null(),
+                "HKDF-{} should reject more than 255 * HashLen bytes",
+                algo
+            );
+            assert_eq!(out_length, 0);
+        }
+    }
+
+    #[cfg(feature = "legacy-hashes")]
+    #[test]
+    fn test_hkdf_legacy_algorithms_follow_rfc5869() {
+        use crate::hash::compute_hmac_raw;
+
+        for (algo, hash_length) in [
+            ("MD4", 16),
+            ("RIPEMD160", 20),
+            ("SM3", 32),
+            ("WHIRLPOOL", 64),
+        ] {
+            let algorithm = CString::new(algo).unwrap();
+            let length = 2 * hash_length + 10;
+
+            // PRK = HMAC-Hash(salt, IKM)
+            let expected_prk = compute_hmac_raw(algo, &SALT, &IKM).unwrap();
+            // T(i) = HMAC-Hash(PRK, T(i-1) | info | i), OKM = first L bytes of T(1) | T(2) | ...
+            let mut expected_okm = Vec::new();
+            let mut block = Vec::new();
+            for counter in 1u8..=3 {
+                let mut message = block.clone();
+                message.extend_from_slice(&INFO);
+                message.push(counter);
+                block = compute_hmac_raw(algo, &expected_prk, &message).unwrap();
+                expected_okm.extend_from_slice(&block);
+            }
+            expected_okm.truncate(length);
+
+            let mut out_length = 0usize;
+            let prk = take_bytes(
+                unsafe {
+                    hkdf_extract(
+                        IKM.as_ptr(),
+                        IKM.len(),
+                        SALT.as_ptr(),
+                        SALT.len(),
+                        algorithm.as_ptr(),
+                        &mut out_length,
+                    )
+                },
+                out_length,
+            );
+            assert_eq!(prk, expected_prk, "HKDF-{} extract", algo);
+
+            let okm = take_bytes(
+                unsafe {
+                    hkdf_expand(
+                        prk.as_ptr(),
+                        prk.len(),
+                        INFO.as_ptr(),
+                        INFO.len(),
+                        algorithm.as_ptr(),
+                        length,
+                        &mut out_length,
+                    )
+                },
+                out_length,
+            );
+            assert_eq!(okm, expected_okm, "HKDF-{} expand", algo);
+
+            let derived = take_bytes(
+                unsafe {
+                    hkdf_derive(
+                        IKM.as_ptr(),
+                        IKM.len(),
+                        SALT.as_ptr(),
+                        SALT.len(),
+                        INFO.as_ptr(),
+                        INFO.len(),
+                        algorithm.as_ptr(),
+                        length,
+                        &mut out_length,
+                    )
+                },
+                out_length,
+            );
+            assert_eq!(derived, okm, "HKDF-{} derive", algo);
+        }
+    }
+
+    #[test]
+    fn test_hkdf_expand_short_prk_returns_null() {
+        let prk = [0u8; 16];
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_expand(
+                prk.as_ptr(),
+                prk.len(),
+                std::ptr::null(),
+                0,
+                algorithm.as_ptr(),
+                32,
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "PRK shorter than HashLen should return null");
+    }
+
+    #[test]
+    fn test_hkdf_derive_zero_length_returns_null() {
+        let algorithm = CString::new("SHA256").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_derive(
+                IKM.as_ptr(),
+                IKM.len(),
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                algorithm.as_ptr(),
+                0,
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Zero output length should return null");
+    }
+
+    #[test]
+    fn test_hkdf_extract_unsupported_algorithm_returns_null() {
+        let algorithm = CString::new("UNSUPPORTED").unwrap();
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_extract(
+                IKM.as_ptr(),
+                IKM.len(),
+                std::ptr::null(),
+                0,
+                algorithm.as_ptr(),
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Unsupported algorithm should return null");
+    }
+
+    #[test]
+    fn test_hkdf_extract_null_algorithm_returns_null() {
+        let mut out_length = 0usize;
+
+        let ptr = unsafe {
+            hkdf_extract(
+                IKM.as_ptr(),
+                IKM.len(),
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                &mut out_length,
+            )
+        };
+
+        assert!(ptr.is_null(), "Null algorithm should return null");
+    }
+}
@@ -0,0 +1,18 @@
+//! Key derivation functions (PBKDF2, scrypt, Argon2id, HKDF)
+
+mod algorithms;
+mod derive_ops;
+mod hkdf_ops;
+mod password_ops;
+
+// Re-export public FFI functions
+pub use derive_ops::{derive_argon2id, derive_pbkdf2, derive_scrypt};
+pub use hkdf_ops::{hkdf_derive, hkdf_expand, hkdf_extract};
+pub use password_ops::{
+    hash_password_argon2id, hash_password_pbkdf2, hash_password_scrypt, verify_password,
+};
+
+// Re-export key derivation helpers for use by other modules
+pub(crate) use algorithms::{
+    ARGON2_MAX_ITERATIONS, ARGON2_MAX_MEMORY_KIB, ARGON2_MAX_PARALLELISM, argon2id_derive,
+};
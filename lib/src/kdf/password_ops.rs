@@ -0,0 +1,338 @@
+//! PHC-format password hashing and verification operations
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use super::algorithms::{argon2id_phc, pbkdf2_phc, scrypt_phc, verify_phc};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_string;
+
+/// Hash a password with PBKDF2-HMAC and return a PHC-format string
+///
+/// The salt is supplied by the caller and must be between 4 and 48 bytes so it
+/// can be embedded in the PHC string.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA1 (PHC identifier `pbkdf2`)
+/// - SHA256 (PHC identifier `pbkdf2-sha256`)
+/// - SHA512 (PHC identifier `pbkdf2-sha512`)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hash_password_pbkdf2(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    algorithm: *const c_char,
+    iterations: u32,
+    key_length: usize,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let pw = unsafe { slice_from_raw(password, password_length, "Password") }?;
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        pbkdf2_phc(algorithm, pw, salt, iterations, key_length)
+    });
+
+    finish_string(result, "PHC result")
+}
+
+/// Hash a password with scrypt and return a PHC-format string
+///
+/// The cost parameters are limited as in `derive_scrypt`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hash_password_scrypt(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    key_length: usize,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = unsafe { slice_from_raw(password, password_length, "Password") }.and_then(|pw| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        scrypt_phc(pw, salt, log_n, r, p, key_length)
+    });
+
+    finish_string(result, "PHC result")
+}
+
+/// Hash a password with Argon2id and return a PHC-format string
+///
+/// The cost parameters are limited as in `derive_argon2id`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `salt` is a valid pointer to at least `salt_length` bytes, or null if length is 0
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hash_password_argon2id(
+    password: *const u8,
+    password_length: usize,
+    salt: *const u8,
+    salt_length: usize,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    key_length: usize,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = unsafe { slice_from_raw(password, password_length, "Password") }.and_then(|pw| {
+        let salt = unsafe { slice_from_raw(salt, salt_length, "Salt") }?;
+        argon2id_phc(pw, salt, memory_kib, iterations, parallelism, key_length)
+    });
+
+    finish_string(result, "PHC result")
+}
+
+/// Verify a password against a PHC-format hash string
+///
+/// Accepts Argon2 (`argon2id`, `argon2i`, `argon2d`), `scrypt` and PBKDF2
+/// (`pbkdf2`, `pbkdf2-sha256`, `pbkdf2-sha512`) hashes. Cost parameters are
+/// read from the PHC string and rejected with an error above these maximums:
+/// 1 GiB of memory for Argon2 and scrypt, 64 Argon2 passes, parallelism 64,
+/// 10,000,000 PBKDF2 iterations and a 1024-byte PBKDF2 output.
+///
+/// Returns `true` when the password matches. Returns `false` on mismatch, or on
+/// error with the reason available from `get_last_error`. A mismatch leaves no
+/// error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `password` is a valid pointer to at least `password_length` bytes, or null if length is 0
+/// - `phc` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_password(
+    password: *const u8,
+    password_length: usize,
+    phc: *const c_char,
+) -> bool {
+    crate::error::clear_error();
+
+    if phc.is_null() {
+        crate::error::set_error("PHC string pointer is null".to_string());
+        return false;
+    }
+
+    let phc_str = match unsafe { CStr::from_ptr(phc).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in PHC string".to_string());
+            return false;
+        }
+    };
+
+    let result = unsafe { slice_from_raw(password, password_length, "Password") }
+        .and_then(|pw| verify_phc(pw, phc_str));
+
+    match result {
+        Ok(matches) => matches,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn take_string(ptr: *mut c_char) -> String {
+        assert!(!ptr.is_null(), "PHC hashing should not return null");
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        s
+    }
+
+    fn verify(password: &[u8], phc: &str) -> bool {
+        let phc = CString::new(phc).unwrap();
+        unsafe { verify_password(password.as_ptr(), password.len(), phc.as_ptr()) }
+    }
+
+    fn last_error_is_set() -> bool {
+        let ptr = unsafe { crate::error::get_last_error() };
+        let is_set = !ptr.is_null();
+        unsafe { crate::memory::free_string(ptr) };
+        is_set
+    }
+
+    #[test]
+    fn test_hash_password_pbkdf2_round_trip() {
+        let password = b"correct horse";
+        let salt = b"0123456789abcdef";
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let phc = take_string(unsafe {
+            hash_password_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                algorithm.as_ptr(),
+                1000,
+                32,
+            )
+        });
+
+        assert!(
+            phc.starts_with("$pbkdf2-sha256$i=1000,l=32$"),
+            "Unexpected PHC string: {}",
+            phc
+        );
+        assert!(verify(password, &phc));
+        assert!(!verify(b"wrong horse", &phc));
+        assert!(!last_error_is_set(), "Mismatch should not set an error");
+    }
+
+    #[test]
+    fn test_hash_password_scrypt_round_trip() {
+        let password = b"correct horse";
+        let salt = b"0123456789abcdef";
+
+        let phc = take_string(unsafe {
+            hash_password_scrypt(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                10,
+                8,
+                1,
+                32,
+            )
+        });
+
+        assert!(
+            phc.starts_with("$scrypt$ln=10,r=8,p=1$"),
+            "Unexpected PHC string: {}",
+            phc
+        );
+        assert!(verify(password, &phc));
+        assert!(!verify(b"wrong horse", &phc));
+    }
+
+    #[test]
+    fn test_hash_password_argon2id_round_trip() {
+        let password = b"correct horse";
+        let salt = b"0123456789abcdef";
+
+        let phc = take_string(unsafe {
+            hash_password_argon2id(
+                password.as_ptr(),
+                password.len(),
+                salt.as_ptr(),
+                salt.len(),
+                64,
+                1,
+                1,
+                32,
+            )
+        });
+
+        assert!(
+            phc.starts_with("$argon2id$v=19$m=64,t=1,p=1$"),
+            "Unexpected PHC string: {}",
+            phc
+        );
+        assert!(verify(password, &phc));
+        assert!(!verify(b"wrong horse", &phc));
+    }
+
+    #[test]
+    fn test_verify_password_known_pbkdf2_sha256_phc() {
+        // PBKDF2-HMAC-SHA256 of "password" with salt "saltsalt" and 1000 iterations
+        assert!(verify(
+            b"password",
+            "$pbkdf2-sha256$i=1000,l=32$c2FsdHNhbHQ$E196ZhRPzw+wA84EjzHwJO1cv/MFJdO6C/sxmUeTYqY"
+        ));
+    }
+
+    #[test]
+    fn test_verify_password_malformed_phc_sets_error() {
+        assert!(!verify(b"password", "not a phc string"));
+        assert!(
+            last_error_is_set(),
+            "Malformed PHC string should set an error"
+        );
+    }
+
+    #[test]
+    fn test_verify_password_excessive_cost_sets_error() {
+        for phc in [
+            "$argon2id$v=19$m=4294967295,t=1,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA",
+            "$argon2id$v=19$m=64,t=4000000000,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA",
+            "$scrypt$ln=40,r=8,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA",
+            "$scrypt$ln=20,r=16,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA",
+            "$pbkdf2-sha256$i=4000000000,l=32$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA",
+        ] {
+            assert!(!verify(b"password", phc), "{}", phc);
+            assert!(
+                last_error_is_set(),
+                "Excessive cost should set an error: {}",
+                phc
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_password_unsupported_algorithm_sets_error() {
+        assert!(!verify(b"password", "$bcrypt$v=2$c2FsdHNhbHQ$aGFzaA"));
+        assert!(last_error_is_set());
+    }
+
+    #[test]
+    fn test_hash_password_pbkdf2_empty_salt_returns_null() {
+        let password = b"password";
+        let algorithm = CString::new("SHA256").unwrap();
+
+        let result = unsafe {
+            hash_password_pbkdf2(
+                password.as_ptr(),
+                password.len(),
+                std::ptr::null(),
+                0,
+                algorithm.as_ptr(),
+                1000,
+                32,
+            )
+        };
+
+        assert!(result.is_null(), "Empty salt should return null");
+    }
+
+    #[test]
+    fn test_verify_password_null_phc_returns_false() {
+        let password = b"password";
+
+        let result =
+            unsafe { verify_password(password.as_ptr(), password.len(), std::ptr::null()) };
+
+        assert!(!result, "Null PHC string should return false");
+        assert!(last_error_is_set());
+    }
+}
@@ -2,15 +2,26 @@
 //!
 //! High-performance conversion functions for the PowerShell Convert module.
 //! This library provides C ABI exports for Base64 encoding/decoding, cryptographic
-//! hashing, compression, URL encoding, and time/temperature conversions.
+//! hashing, checksum manifests, SSH/X.509 fingerprints, key derivation,
+//! authenticated encryption, secure random generation, one-time passwords, JWTs,
+//! AWS SigV4 signing, compression, URL encoding, and time/temperature conversions.
 
 // Module declarations
 mod base64;
 mod compression;
+mod crypto;
 mod encoding;
 mod error;
+mod fingerprint;
 mod hash;
+mod helpers;
+mod jwt;
+mod kdf;
+mod manifest;
 mod memory;
+mod otp;
+mod random;
+mod sigv4;
 mod temperature;
 mod time;
 mod url;
@@ -18,10 +29,18 @@ mod url;
 // Re-export public functions from modules
 pub use base64::*;
 pub use compression::*;
+pub use crypto::*;
 pub use encoding::*;
 pub use error::*;
+pub use fingerprint::*;
 pub use hash::*;
+pub use jwt::*;
+pub use kdf::*;
+pub use manifest::*;
 pub use memory::*;
+pub use otp::*;
+pub use random::*;
+pub use sigv4::*;
 pub use temperature::*;
 pub use time::*;
 pub use url::*;
@@ -0,0 +1,216 @@
+//! Hashing, verifying and listing files for checksum manifests
+
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::{Component, Path, PathBuf};
+
+use super::parse::{ManifestEntry, ManifestFormat, algorithm_info, escape_path};
+use crate::hash::new_hasher;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Hashes a file incrementally, returning the digest as lowercase hex.
+pub(crate) fn hash_file(path: &Path, algorithm: &str) -> std::io::Result<String> {
+    let mut hasher = new_hasher(algorithm).map_err(std::io::Error::other)?;
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let mut digest = vec![0u8; hasher.output_size()];
+    hasher
+        .finalize_into_reset(&mut digest)
+        .map_err(|_| std::io::Error::other("Digest buffer has the wrong size"))?;
+
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Joins a manifest path onto the base directory, refusing paths that escape it.
+fn resolve_entry_path(base: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err("Path is absolute or leaves the base directory".to_string());
+    }
+    Ok(base.join(relative))
+}
+
+/// Checks every manifest entry against files under `base`, returning a JSON report.
+///
+/// Each entry gets a `status` of `pass`, `fail`, `missing` or `error`; the
+/// report also carries per-status totals.
+pub(crate) fn verify_entries(entries: &[ManifestEntry], base: &Path) -> Value {
+    let mut counts = [0usize; 4];
+    let results: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            let outcome = resolve_entry_path(base, &entry.path).and_then(|path| {
+                match hash_file(&path, entry.algorithm) {
+                    Ok(actual) => Ok(Some(actual)),
+                    Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                    Err(e) => Err(e.to_string()),
+                }
+            });
+
+            let (index, status, actual, error) = match outcome {
+                Ok(Some(actual)) if actual == entry.digest => (0, "pass", Some(actual), None),
+                Ok(Some(actual)) => (1, "fail", Some(actual), None),
+                Ok(None) => (2, "missing", None, None),
+                Err(e) => (3, "error", None, Some(e)),
+            };
+            counts[index] += 1;
+
+            json!({
+                "path": entry.path,
+                "algorithm": entry.algorithm,
+                "expected": entry.digest,
+                "actual": actual,
+                "status": status,
+                "error": error,
+            })
+        })
+        .collect();
+
+    json!({
+        "passed": counts[0],
+        "failed": counts[1],
+        "missing": counts[2],
+        "errors": counts[3],
+        "entries": results,
+    })
+}
+
+/// Hashes each path under `base` and writes a manifest in the requested format.
+///
+/// Paths are written exactly as given. Any unreadable file is an error.
+pub(crate) fn generate_manifest(
+    paths: &[&str],
+    base: &Path,
+    algorithm: &str,
+    format: ManifestFormat,
+) -> Result<String, String> {
+    let (algorithm, _) = algorithm_info(algorithm)?;
+    let mut output = String::new();
+
+    for path in paths {
+        let digest = hash_file(&base.join(path), algorithm)
+            .map_err(|e| format!("Failed to hash {}: {}", path, e))?;
+
+        match format {
+            ManifestFormat::Gnu => {
+                let (escaped, name) = escape_path(path);
+                if escaped {
+                    output.push('\\');
+                }
+                output.push_str(&format!("{}  {}\n", digest, name));
+            }
+            ManifestFormat::Bsd => {
+                if path.contains(['\n', '\r']) {
+                    return Err(format!(
+                        "BSD manifests cannot list paths with line breaks: {:?}",
+                        path
+                    ));
+                }
+                output.push_str(&format!("{} ({}) = {}\n", algorithm, path, digest));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::parse::parse_manifest;
+
+    /// A scratch directory removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "convert_core_manifest_{}_{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(path.join("sub")).unwrap();
+            std::fs::write(path.join("hello.txt"), b"hello world").unwrap();
+            std::fs::write(path.join("sub").join("empty.bin"), b"").unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_generate_then_verify_round_trip() {
+        let dir = TempDir::new("round_trip");
+        let manifest = generate_manifest(
+            &["hello.txt", "sub/empty.bin"],
+            &dir.0,
+            "sha256",
+            ManifestFormat::Gnu,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  hello.txt\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  sub/empty.bin\n"
+        );
+
+        let report = verify_entries(&parse_manifest(&manifest, None).unwrap(), &dir.0);
+        assert_eq!(report["passed"], 2);
+        assert_eq!(report["failed"], 0);
+    }
+
+    #[test]
+    fn test_verify_reports_fail_missing_and_error() {
+        let dir = TempDir::new("statuses");
+        let manifest = "MD5 (hello.txt) = 00000000000000000000000000000000\n\
+                        MD5 (gone.txt) = d41d8cd98f00b204e9800998ecf8427e\n\
+                        MD5 (../escape.txt) = d41d8cd98f00b204e9800998ecf8427e\n";
+
+        let report = verify_entries(&parse_manifest(manifest, None).unwrap(), &dir.0);
+        let statuses: Vec<&str> = report["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["status"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(statuses, ["fail", "missing", "error"]);
+        assert_eq!(
+            report["entries"][0]["actual"],
+            "5eb63bbbe01eeed093cb22bb8f5acdc3"
+        );
+    }
+
+    #[test]
+    fn test_generate_bsd_and_missing_file() {
+        let dir = TempDir::new("bsd");
+        let manifest =
+            generate_manifest(&["sub/empty.bin"], &dir.0, "MD5", ManifestFormat::Bsd).unwrap();
+        assert_eq!(
+            manifest,
+            "MD5 (sub/empty.bin) = d41d8cd98f00b204e9800998ecf8427e\n"
+        );
+
+        assert!(generate_manifest(&["nope"], &dir.0, "MD5", ManifestFormat::Gnu).is_err());
+    }
+}
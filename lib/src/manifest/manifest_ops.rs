@@ -0,0 +1,203 @@
+//! Checksum manifest parsing, verification and generation operations
+
+use serde_json::{Value, json};
+use std::os::raw::c_char;
+use std::path::Path;
+
+use super::files::{generate_manifest, verify_entries};
+use super::parse::{ManifestFormat, parse_manifest};
+use crate::encoding::read_str;
+use crate::helpers::{finish_string, read_optional_str};
+
+/// Parse checksum manifest text into a JSON array of entries
+///
+/// Accepts GNU `sha256sum` lines (`<hex>  <path>`, `<hex> *<path>`, with `\`
+/// escaped names) and BSD lines (`SHA256 (<path>) = <hex>`), mixed freely.
+/// Blank lines and `#` comments are ignored. For GNU lines `algorithm` is used
+/// when given; when null it is inferred from the digest length.
+///
+/// Each entry has `path`, `algorithm`, `digest` (lowercase hex), `binary` and
+/// `format` (`gnu` or `bsd`) fields.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `content` is a valid null-terminated C string or null
+/// - `algorithm` is a valid null-terminated C string or null (optional)
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_checksum_manifest(
+    content: *const c_char,
+    algorithm: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(content, "Content").and_then(|content| {
+        let algorithm = read_optional_str(algorithm, "Algorithm")?;
+        let entries: Vec<Value> = parse_manifest(content, algorithm)?
+            .into_iter()
+            .map(|entry| {
+                json!({
+                    "path": entry.path,
+                    "algorithm": entry.algorithm,
+                    "digest": entry.digest,
+                    "binary": entry.binary,
+                    "format": entry.format.name(),
+                })
+            })
+            .collect();
+        Ok(Value::Array(entries).to_string())
+    });
+
+    finish_string(result, "manifest result")
+}
+
+/// Verify the files listed in a checksum manifest, returning a JSON report
+///
+/// Paths are resolved relative to `base_directory`; absolute paths and paths
+/// containing `..` are reported as errors rather than read. The report has
+/// `passed`, `failed`, `missing` and `errors` totals plus an `entries` array
+/// with `path`, `algorithm`, `expected`, `actual`, `status` (`pass`, `fail`,
+/// `missing` or `error`) and `error` for every listed file.
+///
+/// A malformed manifest returns null; per-file problems are reported in the
+/// JSON instead.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `content` and `base_directory` are valid null-terminated C strings or null
+/// - `algorithm` is a valid null-terminated C string or null (optional)
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn verify_checksum_manifest(
+    content: *const c_char,
+    base_directory: *const c_char,
+    algorithm: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(content, "Content").and_then(|content| {
+        let base = read_str(base_directory, "Base directory")?;
+        let algorithm = read_optional_str(algorithm, "Algorithm")?;
+        let entries = parse_manifest(content, algorithm)?;
+        Ok(verify_entries(&entries, Path::new(base)).to_string())
+    });
+
+    finish_string(result, "manifest result")
+}
+
+/// Generate a checksum manifest for a set of files
+///
+/// `paths` lists one path per line, relative to `base_directory`, and each is
+/// written to the manifest exactly as given. `format` is `gnu` (the
+/// `sha256sum` layout, the default when null) or `bsd`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `paths`, `base_directory` and `algorithm` are valid null-terminated C strings or null
+/// - `format` is a valid null-terminated C string or null (optional)
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - MD5, SHA1, SHA256, SHA384, SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn generate_checksum_manifest(
+    paths: *const c_char,
+    base_directory: *const c_char,
+    algorithm: *const c_char,
+    format: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(paths, "Paths").and_then(|paths| {
+        let base = read_str(base_directory, "Base directory")?;
+        let algorithm = read_str(algorithm, "Algorithm")?;
+        let format = match read_optional_str(format, "Format")? {
+            Some(format) => ManifestFormat::parse(format)?,
+            None => ManifestFormat::Gnu,
+        };
+
+        let paths: Vec<&str> = paths
+            .lines()
+            .map(|p| p.trim_end_matches('\r'))
+            .filter(|p| !p.is_empty())
+            .collect();
+        generate_manifest(&paths, Path::new(base), algorithm, format)
+    });
+
+    finish_string(result, "manifest result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_string(ptr: *mut c_char) -> String {
+        assert!(!ptr.is_null(), "Expected a non-null string");
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        s
+    }
+
+    #[test]
+    fn test_parse_checksum_manifest_json() {
+        let content = CString::new("d41d8cd98f00b204e9800998ecf8427e *empty.bin").unwrap();
+
+        let json: Value = serde_json::from_str(&take_string(unsafe {
+            parse_checksum_manifest(content.as_ptr(), std::ptr::null())
+        }))
+        .unwrap();
+
+        assert_eq!(json[0]["path"], "empty.bin");
+        assert_eq!(json[0]["algorithm"], "MD5");
+        assert_eq!(json[0]["binary"], true);
+        assert_eq!(json[0]["format"], "gnu");
+    }
+
+    #[test]
+    fn test_verify_checksum_manifest_missing_directory() {
+        let content = CString::new("d41d8cd98f00b204e9800998ecf8427e  empty.bin").unwrap();
+        let base = CString::new("/nonexistent/convert_core").unwrap();
+
+        let json: Value = serde_json::from_str(&take_string(unsafe {
+            verify_checksum_manifest(content.as_ptr(), base.as_ptr(), std::ptr::null())
+        }))
+        .unwrap();
+
+        assert_eq!(json["missing"], 1);
+        assert_eq!(json["entries"][0]["status"], "missing");
+    }
+
+    #[test]
+    fn test_verify_checksum_manifest_malformed_returns_null() {
+        let content = CString::new("garbage").unwrap();
+        let base = CString::new(".").unwrap();
+
+        let result =
+            unsafe { verify_checksum_manifest(content.as_ptr(), base.as_ptr(), std::ptr::null()) };
+
+        assert!(result.is_null(), "Malformed manifest should return null");
+    }
+
+    #[test]
+    fn test_generate_checksum_manifest_invalid_format_returns_null() {
+        let paths = CString::new("Cargo.toml").unwrap();
+        let base = CString::new(".").unwrap();
+        let algorithm = CString::new("SHA256").unwrap();
+        let format = CString::new("xml").unwrap();
+
+        let result = unsafe {
+            generate_checksum_manifest(
+                paths.as_ptr(),
+                base.as_ptr(),
+                algorithm.as_ptr(),
+                format.as_ptr(),
+            )
+        };
+
+        assert!(result.is_null(), "Unsupported format should return null");
+    }
+}
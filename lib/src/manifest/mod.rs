@@ -0,0 +1,10 @@
+//! Checksum manifest functions (sha256sum / SHA256SUMS and BSD-style digests)
+
+mod files;
+mod manifest_ops;
+mod parse;
+
+// Re-export public FFI functions
+pub use manifest_ops::{
+    generate_checksum_manifest, parse_checksum_manifest, verify_checksum_manifest,
+};
@@ -0,0 +1,300 @@
+//! Parsing of GNU coreutils (`sha256sum`) and BSD (`shasum --tag`) manifest lines
+
+/// The line syntax a manifest entry was written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ManifestFormat {
+    /// `<hex>  <path>` or `<hex> *<path>` as written by `sha256sum`
+    Gnu,
+    /// `SHA256 (<path>) = <hex>` as written by BSD `sha256` and `shasum --tag`
+    Bsd,
+}
+
+impl ManifestFormat {
+    pub(crate) fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "gnu" => Ok(Self::Gnu),
+            "bsd" => Ok(Self::Bsd),
+            _ => Err(format!(
+                "Unsupported manifest format: {}. Supported: gnu, bsd",
+                format
+            )),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Gnu => "gnu",
+            Self::Bsd => "bsd",
+        }
+    }
+}
+
+/// A single file listed in a checksum manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ManifestEntry {
+    pub path: String,
+    pub algorithm: &'static str,
+    /// Expected digest as lowercase hex
+    pub digest: String,
+    /// Whether the GNU binary-mode marker (`*`) was present
+    pub binary: bool,
+    pub format: ManifestFormat,
+}
+
+/// Normalises an algorithm name and returns its digest length in hex characters.
+pub(crate) fn algorithm_info(algorithm: &str) -> Result<(&'static str, usize), String> {
+    match algorithm.to_uppercase().replace('-', "").as_str() {
+        "MD5" => Ok(("MD5", 32)),
+        "SHA1" => Ok(("SHA1", 40)),
+        "SHA256" => Ok(("SHA256", 64)),
+        "SHA384" => Ok(("SHA384", 96)),
+        "SHA512" => Ok(("SHA512", 128)),
+        _ => Err(format!(
+            "Unsupported algorithm: {}. Supported: MD5, SHA1, SHA256, SHA384, SHA512",
+            algorithm
+        )),
+    }
+}
+
+/// Infers the algorithm of a bare hex digest from its length.
+fn algorithm_for_length(length: usize) -> Option<&'static str> {
+    match length {
+        32 => Some("MD5"),
+        40 => Some("SHA1"),
+        64 => Some("SHA256"),
+        96 => Some("SHA384"),
+        128 => Some("SHA512"),
+        _ => None,
+    }
+}
+
+fn check_hex(digest: &str) -> Result<String, String> {
+    if digest.is_empty() || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hexadecimal digest: {}", digest));
+    }
+    Ok(digest.to_lowercase())
+}
+
+/// Reverses GNU filename escaping (`\\` and `\n`), used when a line starts with `\`.
+fn unescape_path(path: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => output.push('\\'),
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            _ => return Err(format!("Invalid escape sequence in path: {}", path)),
+        }
+    }
+    Ok(output)
+}
+
+/// Applies GNU filename escaping, returning whether the line needs a leading `\`.
+pub(crate) fn escape_path(path: &str) -> (bool, String) {
+    if !path.contains(['\\', '\n', '\r']) {
+        return (false, path.to_string());
+    }
+
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    (true, escaped)
+}
+
+fn parse_bsd_line(line: &str) -> Option<Result<(String, &'static str, String), String>> {
+    let (algorithm, rest) = line.split_once(" (")?;
+
+    // A GNU line whose file name contains " (" has a hex digest and spaces before it
+    if algorithm.is_empty()
+        || !algorithm
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        || check_hex(algorithm).is_ok()
+    {
+        return None;
+    }
+
+    let (path, digest) = rest.rsplit_once(") = ")?;
+    Some(
+        algorithm_info(algorithm).and_then(|(algorithm, _)| {
+            Ok((path.to_string(), algorithm, check_hex(digest.trim())?))
+        }),
+    )
+}
+
+fn parse_gnu_line(line: &str) -> Result<(String, String, bool), String> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let (digest, rest) = line
+        .split_once(' ')
+        .ok_or_else(|| "Expected '<digest>  <path>' or 'ALG (<path>) = <digest>'".to_string())?;
+
+    let (binary, path) = match rest.chars().next() {
+        Some('*') => (true, &rest[1..]),
+        Some(' ') => (false, &rest[1..]),
+        _ => (false, rest),
+    };
+
+    if path.is_empty() {
+        return Err("Missing file path".to_string());
+    }
+
+    let path = if escaped {
+        unescape_path(path)?
+    } else {
+        path.to_string()
+    };
+
+    Ok((check_hex(digest)?, path, binary))
+}
+
+/// Parses manifest text in GNU or BSD format (both may be mixed).
+///
+/// Blank lines and `#` comments are skipped. For GNU lines the algorithm is
+/// `algorithm` when given, otherwise inferred from the digest length. Any
+/// malformed line is an error naming its line number.
+pub(crate) fn parse_manifest(
+    content: &str,
+    algorithm: Option<&str>,
+) -> Result<Vec<ManifestEntry>, String> {
+    let expected = algorithm.map(algorithm_info).transpose()?;
+    let mut entries = Vec::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line = raw_line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let entry = match parse_bsd_line(line) {
+            Some(parsed) => parsed.map(|(path, algorithm, digest)| ManifestEntry {
+                path,
+                algorithm,
+                digest,
+                binary: false,
+                format: ManifestFormat::Bsd,
+            }),
+            None => parse_gnu_line(line).and_then(|(digest, path, binary)| {
+                let algorithm = match expected {
+                    Some((name, _)) => name,
+                    None => algorithm_for_length(digest.len()).ok_or_else(|| {
+                        format!(
+                            "Cannot infer algorithm from {}-character digest",
+                            digest.len()
+                        )
+                    })?,
+                };
+                Ok(ManifestEntry {
+                    path,
+                    algorithm,
+                    digest,
+                    binary,
+                    format: ManifestFormat::Gnu,
+                })
+            }),
+        }
+        .and_then(|entry| {
+            let (_, hex_length) = algorithm_info(entry.algorithm)?;
+            if entry.digest.len() != hex_length {
+                return Err(format!(
+                    "{} digest must be {} hex characters",
+                    entry.algorithm, hex_length
+                ));
+            }
+            if let Some((name, _)) = expected
+                && name != entry.algorithm
+            {
+                return Err(format!(
+                    "Expected {} entry, found {}",
+                    name, entry.algorithm
+                ));
+            }
+            Ok(entry)
+        })
+        .map_err(|e| format!("Line {}: {}", index + 1, e))?;
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const EMPTY_MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+    #[test]
+    fn test_parse_gnu_lines() {
+        let content = format!(
+            "# release\n{}  text.txt\n{} *bin/app.exe\r\n\n",
+            EMPTY_SHA256,
+            EMPTY_SHA256.to_uppercase()
+        );
+        let entries = parse_manifest(&content, None).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "text.txt");
+        assert!(!entries[0].binary);
+        assert_eq!(entries[1].path, "bin/app.exe");
+        assert!(entries[1].binary);
+        assert_eq!(entries[1].digest, EMPTY_SHA256);
+        assert_eq!(entries[1].algorithm, "SHA256");
+    }
+
+    #[test]
+    fn test_parse_bsd_lines() {
+        let content = format!(
+            "SHA256 (file (1).txt) = {}\nMD5 (other) = {}",
+            EMPTY_SHA256, EMPTY_MD5
+        );
+        let entries = parse_manifest(&content, None).unwrap();
+
+        assert_eq!(entries[0].path, "file (1).txt");
+        assert_eq!(entries[0].format, ManifestFormat::Bsd);
+        assert_eq!(entries[1].algorithm, "MD5");
+    }
+
+    #[test]
+    fn test_parse_escaped_gnu_path() {
+        let content = format!("\\{}  dir\\\\new\\nline", EMPTY_MD5);
+        let entries = parse_manifest(&content, Some("md5")).unwrap();
+        assert_eq!(entries[0].path, "dir\\new\nline");
+        assert_eq!(
+            escape_path(&entries[0].path),
+            (true, "dir\\\\new\\nline".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_gnu_path_resembling_bsd() {
+        let content = format!("{}  notes (draft) = old.txt", EMPTY_MD5);
+        let entries = parse_manifest(&content, None).unwrap();
+        assert_eq!(entries[0].path, "notes (draft) = old.txt");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        let error =
+            parse_manifest(&format!("{}  ok\nnot a checksum", EMPTY_MD5), None).unwrap_err();
+        assert!(error.starts_with("Line 2:"), "{}", error);
+
+        assert!(
+            parse_manifest("abc  file", None).is_err(),
+            "Unknown digest length"
+        );
+        assert!(parse_manifest(&format!("{}  file", EMPTY_MD5), Some("SHA256")).is_err());
+        assert!(parse_manifest(&format!("MD5 (f) = {}", EMPTY_MD5), Some("SHA1")).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Core HOTP (RFC 4226) and TOTP (RFC 6238) implementations
+
+use subtle::ConstantTimeEq;
+
+use super::base32;
+use crate::hash::compute_hmac_raw;
+
+/// Smallest number of digits allowed by RFC 4226.
+pub(crate) const MIN_DIGITS: u32 = 6;
+
+/// Largest number of digits representable by the 31-bit truncated value.
+pub(crate) const MAX_DIGITS: u32 = 10;
+
+/// Largest drift window, in time steps either side, accepted when validating.
+/// Each extra step accepts two more codes, weakening the check.
+pub(crate) const MAX_WINDOW: u32 = 10;
+
+/// Normalises an OTP algorithm name, rejecting hashes not defined for TOTP.
+pub(crate) fn normalize_algorithm(algorithm: &str) -> Result<&'static str, String> {
+    match algorithm.to_uppercase().as_str() {
+        "SHA1" => Ok("SHA1"),
+        "SHA256" => Ok("SHA256"),
+        "SHA512" => Ok("SHA512"),
+        _ => Err(format!(
+            "Unsupported OTP algorithm: {}. Supported: SHA1, SHA256, SHA512",
+            algorithm
+        )),
+    }
+}
+
+/// Returns the shared secret as raw bytes, decoding it from Base32 text if requested.
+pub(crate) fn resolve_secret(secret: &[u8], is_base32: bool) -> Result<Vec<u8>, String> {
+    let key = if is_base32 {
+        let text = std::str::from_utf8(secret)
+            .map_err(|_| "Invalid UTF-8 in Base32 secret".to_string())?;
+        base32::decode(text)?
+    } else {
+        secret.to_vec()
+    };
+
+    if key.is_empty() {
+        return Err("Secret must not be empty".to_string());
+    }
+    Ok(key)
+}
+
+fn validate_digits(digits: u32) -> Result<(), String> {
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&digits) {
+        return Err(format!(
+            "Digits must be between {} and {}",
+            MIN_DIGITS, MAX_DIGITS
+        ));
+    }
+    Ok(())
+}
+
+fn totp_counter(timestamp: u64, period: u32) -> Result<u64, String> {
+    if period == 0 {
+        return Err("Period must be greater than 0".to_string());
+    }
+    Ok(timestamp / period as u64)
+}
+
+/// Computes an HOTP value for the given counter (RFC 4226 section 5.3).
+pub(crate) fn hotp(
+    algorithm: &str,
+    secret: &[u8],
+    counter: u64,
+    digits: u32,
+) -> Result<String, String> {
+    validate_digits(digits)?;
+    let algorithm = normalize_algorithm(algorithm)?;
+    let mac = compute_hmac_raw(algorithm, secret, &counter.to_be_bytes())?;
+
+    let offset = (mac[mac.len() - 1] & 0x0F) as usize;
+    let binary = u32::from_be_bytes([
+        mac[offset] & 0x7F,
+        mac[offset + 1],
+        mac[offset + 2],
+        mac[offset + 3],
+    ]);
+
+    let code = binary as u64 % 10u64.pow(digits);
+    Ok(format!("{:0width$}", code, width = digits as usize))
+}
+
+/// Computes a TOTP value for the given Unix time in seconds (RFC 6238).
+pub(crate) fn totp(
+    algorithm: &str,
+    secret: &[u8],
+    timestamp: u64,
+    period: u32,
+    digits: u32,
+) -> Result<String, String> {
+    let counter = totp_counter(timestamp, period)?;
+    hotp(algorithm, secret, counter, digits)
+}
+
+/// Checks a TOTP code against every time step within `window` steps of `timestamp`.
+///
+/// Each candidate is compared in constant time and all candidates are always
+/// evaluated, so timing does not reveal which step matched.
+pub(crate) fn validate_totp(
+    code: &str,
+    algorithm: &str,
+    secret: &[u8],
+    timestamp: u64,
+    period: u32,
+    digits: u32,
+    window: u32,
+) -> Result<bool, String> {
+    if window > MAX_WINDOW {
+        return Err(format!("Window must be at most {} time steps", MAX_WINDOW));
+    }
+    let counter = totp_counter(timestamp, period)?;
+    let first = counter.saturating_sub(window as u64);
+    let last = counter.saturating_add(window as u64);
+    let code = code.trim();
+
+    let mut matched = subtle::Choice::from(0);
+    for step in first..=last {
+        let candidate = hotp(algorithm, secret, step, digits)?;
+        matched |= candidate.as_bytes().ct_eq(code.as_bytes());
+    }
+
+    Ok(matched.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4226 Appendix D secret: ASCII "12345678901234567890"
+    const RFC4226_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_hotp_rfc4226_vectors() {
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(
+                hotp("SHA1", RFC4226_SECRET, counter as u64, 6).unwrap(),
+                *code,
+                "HOTP counter {} should match RFC 4226",
+                counter
+            );
+        }
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        let sha256_secret = b"12345678901234567890123456789012";
+        let sha512_secret = b"1234567890123456789012345678901234567890123456789012345678901234";
+
+        let vectors: [(u64, &str, &str, &str); 3] = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+
+        for (time, sha1, sha256, sha512) in vectors {
+            assert_eq!(totp("SHA1", RFC4226_SECRET, time, 30, 8).unwrap(), sha1);
+            assert_eq!(totp("SHA256", sha256_secret, time, 30, 8).unwrap(), sha256);
+            assert_eq!(totp("SHA512", sha512_secret, time, 30, 8).unwrap(), sha512);
+        }
+    }
+
+    #[test]
+    fn test_validate_totp_drift_window() {
+        // Code for time step 1 (t = 59) is accepted one step later only with a window
+        assert!(validate_totp("94287082", "SHA1", RFC4226_SECRET, 59, 30, 8, 0).unwrap());
+        assert!(!validate_totp("94287082", "SHA1", RFC4226_SECRET, 89, 30, 8, 0).unwrap());
+        assert!(validate_totp("94287082", "SHA1", RFC4226_SECRET, 89, 30, 8, 1).unwrap());
+    }
+
+    #[test]
+    fn test_validate_totp_rejects_wide_window() {
+        assert!(validate_totp("94287082", "SHA1", RFC4226_SECRET, 59, 30, 8, 10).unwrap());
+        assert!(validate_totp("94287082", "SHA1", RFC4226_SECRET, 59, 30, 8, 11).is_err());
+        assert!(validate_totp("94287082", "SHA1", RFC4226_SECRET, 59, 30, 8, u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_hotp_rejects_invalid_parameters() {
+        assert!(hotp("SHA1", RFC4226_SECRET, 0, 5).is_err());
+        assert!(hotp("SHA1", RFC4226_SECRET, 0, 11).is_err());
+        assert!(hotp("MD5", RFC4226_SECRET, 0, 6).is_err());
+        assert!(totp("SHA1", RFC4226_SECRET, 59, 0, 6).is_err());
+    }
+
+    #[test]
+    fn test_resolve_secret_base32() {
+        assert_eq!(
+            resolve_secret(b"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", true).unwrap(),
+            RFC4226_SECRET
+        );
+        assert!(resolve_secret(b"", false).is_err());
+    }
+}
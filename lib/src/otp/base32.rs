@@ -0,0 +1,92 @@
+//! RFC 4648 Base32 encoding and decoding for OTP secrets
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode bytes as unpadded, uppercase Base32 (the form used by `otpauth://` URIs).
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[((buffer >> bits) & 0x1F) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+
+    output
+}
+
+/// Decode Base32 text into bytes.
+///
+/// Decoding is case-insensitive, ignores spaces and hyphens (common in secrets
+/// shown to users) and accepts input with or without `=` padding.
+pub(crate) fn decode(input: &str) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for ch in input.trim_end_matches('=').chars() {
+        if ch == ' ' || ch == '-' {
+            continue;
+        }
+
+        let value = match ch.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => return Err(format!("Invalid Base32 character: '{}'", ch)),
+        };
+
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ];
+
+        for (plain, encoded) in vectors {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_base32_decode_lenient_formatting() {
+        assert_eq!(decode("mzxw 6ytb-oi======").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_base32_decode_invalid_character() {
+        assert!(
+            decode("MZXW1").is_err(),
+            "'1' is not in the Base32 alphabet"
+        );
+    }
+}
@@ -0,0 +1,14 @@
+//! One-time password functions (HOTP, TOTP and otpauth:// URIs)
+
+mod algorithms;
+mod base32;
+mod otp_ops;
+mod uri;
+mod uri_ops;
+
+// Re-export public FFI functions
+pub use otp_ops::{generate_hotp, generate_totp, validate_totp_code};
+pub use uri_ops::{generate_otpauth_uri, parse_otpauth_uri};
+
+// Re-export Base32 encoder for use by other modules
+pub(crate) use base32::encode as encode_base32;
@@ -0,0 +1,297 @@
+//! HOTP/TOTP generation and validation operations
+
+use std::os::raw::c_char;
+
+use super::algorithms::{hotp, resolve_secret, totp, validate_totp};
+use crate::encoding::{read_str, slice_from_raw};
+use crate::helpers::finish_string;
+use crate::time::resolve_unix_time;
+
+/// Generate an HOTP code (RFC 4226)
+///
+/// The secret is either raw key bytes or, when `secret_is_base32` is true, the
+/// ASCII Base32 text commonly shown to users (case-insensitive, padding optional).
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `secret` is a valid pointer to at least `secret_length` bytes or null
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA1 (default for most authenticator apps)
+/// - SHA256
+/// - SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn generate_hotp(
+    secret: *const u8,
+    secret_length: usize,
+    secret_is_base32: bool,
+    counter: u64,
+    digits: u32,
+    algorithm: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let secret = unsafe { slice_from_raw(secret, secret_length, "Secret") }?;
+        let key = resolve_secret(secret, secret_is_base32)?;
+        hotp(algorithm, &key, counter, digits)
+    });
+
+    finish_string(result, "OTP code")
+}
+
+/// Generate a TOTP code (RFC 6238)
+///
+/// `timestamp` is the Unix time in seconds; pass a negative value to use the
+/// current system time. `period` is the time step in seconds (usually 30).
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `secret` is a valid pointer to at least `secret_length` bytes or null
+/// - `algorithm` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Algorithms
+/// - SHA1 (default for most authenticator apps)
+/// - SHA256
+/// - SHA512
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn generate_totp(
+    secret: *const u8,
+    secret_length: usize,
+    secret_is_base32: bool,
+    timestamp: i64,
+    period: u32,
+    digits: u32,
+    algorithm: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_str(algorithm, "Algorithm").and_then(|algorithm| {
+        let secret = unsafe { slice_from_raw(secret, secret_length, "Secret") }?;
+        let key = resolve_secret(secret, secret_is_base32)?;
+        let time = resolve_unix_time(timestamp)?;
+        totp(algorithm, &key, time, period, digits)
+    });
+
+    finish_string(result, "OTP code")
+}
+
+/// Validate a TOTP code, allowing for clock drift
+///
+/// The code is accepted if it matches any time step within `window` steps before
+/// or after `timestamp` (a negative timestamp means the current system time).
+/// A `window` of 1 accepts the previous, current and next code; windows above
+/// 10 are rejected with an error.
+///
+/// Returns `true` when the code is valid. Returns `false` when it is not, or on
+/// error with the reason available from `get_last_error`. An invalid code leaves
+/// no error set.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `code` is a valid null-terminated C string or null
+/// - `secret` is a valid pointer to at least `secret_length` bytes or null
+/// - `algorithm` is a valid null-terminated C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn validate_totp_code(
+    code: *const c_char,
+    secret: *const u8,
+    secret_length: usize,
+    secret_is_base32: bool,
+    timestamp: i64,
+    period: u32,
+    digits: u32,
+    algorithm: *const c_char,
+    window: u32,
+) -> bool {
+    crate::error::clear_error();
+
+    let result = read_str(code, "Code").and_then(|code| {
+        let algorithm = read_str(algorithm, "Algorithm")?;
+        let secret = unsafe { slice_from_raw(secret, secret_length, "Secret") }?;
+        let key = resolve_secret(secret, secret_is_base32)?;
+        let time = resolve_unix_time(timestamp)?;
+        validate_totp(code, algorithm, &key, time, period, digits, window)
+    });
+
+    match result {
+        Ok(valid) => valid,
+        Err(e) => {
+            crate::error::set_error(e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    const RFC4226_SECRET: &[u8] = b"12345678901234567890";
+    const RFC4226_SECRET_BASE32: &[u8] = b"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn take_string(ptr: *mut c_char) -> String {
+        assert!(!ptr.is_null(), "OTP generation should not return null");
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        s
+    }
+
+    #[test]
+    fn test_generate_hotp_raw_secret() {
+        let algorithm = CString::new("SHA1").unwrap();
+
+        let code = take_string(unsafe {
+            generate_hotp(
+                RFC4226_SECRET.as_ptr(),
+                RFC4226_SECRET.len(),
+                false,
+                1,
+                6,
+                algorithm.as_ptr(),
+            )
+        });
+
+        assert_eq!(code, "287082");
+    }
+
+    #[test]
+    fn test_generate_totp_base32_secret() {
+        let algorithm = CString::new("sha1").unwrap();
+
+        let code = take_string(unsafe {
+            generate_totp(
+                RFC4226_SECRET_BASE32.as_ptr(),
+                RFC4226_SECRET_BASE32.len(),
+                true,
+                1111111109,
+                30,
+                8,
+                algorithm.as_ptr(),
+            )
+        });
+
+        assert_eq!(code, "07081804");
+    }
+
+    #[test]
+    fn test_generate_totp_current_time() {
+        let algorithm = CString::new("SHA1").unwrap();
+
+        let code = take_string(unsafe {
+            generate_totp(
+                RFC4226_SECRET.as_ptr(),
+                RFC4226_SECRET.len(),
+                false,
+                -1,
+                30,
+                6,
+                algorithm.as_ptr(),
+            )
+        });
+
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_generate_totp_invalid_base32_returns_null() {
+        let secret = b"NOT-BASE32!";
+        let algorithm = CString::new("SHA1").unwrap();
+
+        let result = unsafe {
+            generate_totp(
+                secret.as_ptr(),
+                secret.len(),
+                true,
+                59,
+                30,
+                6,
+                algorithm.as_ptr(),
+            )
+        };
+
+        assert!(result.is_null(), "Invalid Base32 secret should return null");
+    }
+
+    #[test]
+    fn test_generate_hotp_null_algorithm_returns_null() {
+        let result = unsafe {
+            generate_hotp(
+                RFC4226_SECRET.as_ptr(),
+                RFC4226_SECRET.len(),
+                false,
+                0,
+                6,
+                std::ptr::null(),
+            )
+        };
+
+        assert!(result.is_null(), "Null algorithm should return null");
+    }
+
+    #[test]
+    fn test_validate_totp_code_window() {
+        let code = CString::new("94287082").unwrap();
+        let algorithm = CString::new("SHA1").unwrap();
+
+        let validate = |timestamp: i64, window: u32| unsafe {
+            validate_totp_code(
+                code.as_ptr(),
+                RFC4226_SECRET.as_ptr(),
+                RFC4226_SECRET.len(),
+                false,
+                timestamp,
+                30,
+                8,
+                algorithm.as_ptr(),
+                window,
+            )
+        };
+
+        assert!(validate(59, 0), "Code should be valid in its own time step");
+        assert!(
+            !validate(119, 0),
+            "Code should expire without a drift window"
+        );
+        assert!(
+            !validate(119, 1),
+            "Code two steps old should be outside a window of 1"
+        );
+        assert!(
+            validate(119, 2),
+            "Code two steps old should be inside a window of 2"
+        );
+    }
+
+    #[test]
+    fn test_validate_totp_code_invalid_code_sets_no_error() {
+        let code = CString::new("00000000").unwrap();
+        let algorithm = CString::new("SHA1").unwrap();
+
+        let valid = unsafe {
+            validate_totp_code(
+                code.as_ptr(),
+                RFC4226_SECRET.as_ptr(),
+                RFC4226_SECRET.len(),
+                false,
+                59,
+                30,
+                8,
+                algorithm.as_ptr(),
+                1,
+            )
+        };
+
+        assert!(!valid);
+        let error = unsafe { crate::error::get_last_error() };
+        assert!(error.is_null(), "Wrong code should not set an error");
+    }
+}
@@ -0,0 +1,244 @@
+//! `otpauth://` key URI parsing and generation
+//!
+//! Follows the de facto Key URI Format used by authenticator apps:
+//! `otpauth://TYPE/LABEL?secret=BASE32&issuer=...&algorithm=...&digits=...&period=...`
+
+use percent_encoding::{AsciiSet, percent_decode_str, utf8_percent_encode};
+
+use super::algorithms::{MAX_DIGITS, MIN_DIGITS, normalize_algorithm};
+use super::base32;
+use crate::url::UNRESERVED;
+
+/// Characters left unencoded in the label: unreserved plus the issuer separator.
+const LABEL: &AsciiSet = &UNRESERVED.remove(b':');
+
+const DEFAULT_ALGORITHM: &str = "SHA1";
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u32 = 30;
+
+/// The components of an `otpauth://` URI.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OtpAuthUri {
+    /// Either `totp` or `hotp`
+    pub otp_type: String,
+    /// Decoded label, usually `Issuer:account`
+    pub label: String,
+    /// Issuer from the `issuer` parameter, or the label prefix when absent
+    pub issuer: Option<String>,
+    /// Raw secret bytes
+    pub secret: Vec<u8>,
+    pub algorithm: String,
+    pub digits: u32,
+    pub period: u32,
+    /// Initial counter (HOTP only; zero for TOTP)
+    pub counter: u64,
+}
+
+fn normalize_type(otp_type: &str) -> Result<String, String> {
+    match otp_type.to_lowercase().as_str() {
+        "totp" => Ok("totp".to_string()),
+        "hotp" => Ok("hotp".to_string()),
+        _ => Err(format!(
+            "Unsupported OTP type: {}. Supported: totp, hotp",
+            otp_type
+        )),
+    }
+}
+
+fn decode_component(value: &str, name: &str) -> Result<String, String> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .map(|s| s.into_owned())
+        .map_err(|_| format!("Invalid percent-encoding in {}", name))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("Invalid {} value: {}", name, value))
+}
+
+/// Parses an `otpauth://` URI, applying the standard defaults for omitted parameters.
+pub(crate) fn parse(uri: &str) -> Result<OtpAuthUri, String> {
+    let rest = uri
+        .trim()
+        .strip_prefix("otpauth://")
+        .ok_or_else(|| "URI must start with otpauth://".to_string())?;
+
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (otp_type, label) = path
+        .split_once('/')
+        .ok_or_else(|| "URI is missing the label".to_string())?;
+
+    let otp_type = normalize_type(otp_type)?;
+    let label = decode_component(label, "label")?;
+
+    let mut secret = None;
+    let mut issuer = None;
+    let mut algorithm = DEFAULT_ALGORITHM.to_string();
+    let mut digits = DEFAULT_DIGITS;
+    let mut period = DEFAULT_PERIOD;
+    let mut counter = None;
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode_component(&value.replace('+', " "), key)?;
+
+        match key.to_lowercase().as_str() {
+            "secret" => secret = Some(base32::decode(&value)?),
+            "issuer" => issuer = Some(value),
+            "algorithm" => algorithm = normalize_algorithm(&value)?.to_string(),
+            "digits" => digits = parse_number(&value, "digits")?,
+            "period" => period = parse_number(&value, "period")?,
+            "counter" => counter = Some(parse_number(&value, "counter")?),
+            // Unknown parameters (e.g. image) are ignored for forward compatibility
+            _ => {}
+        }
+    }
+
+    let secret = match secret {
+        Some(s) if !s.is_empty() => s,
+        _ => return Err("URI is missing the secret parameter".to_string()),
+    };
+
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&digits) {
+        return Err(format!(
+            "Digits must be between {} and {}",
+            MIN_DIGITS, MAX_DIGITS
+        ));
+    }
+
+    if period == 0 {
+        return Err("Period must be greater than 0".to_string());
+    }
+
+    let counter = match (otp_type.as_str(), counter) {
+        ("hotp", None) => return Err("HOTP URI is missing the counter parameter".to_string()),
+        (_, c) => c.unwrap_or(0),
+    };
+
+    let issuer = issuer.or_else(|| {
+        label
+            .split_once(':')
+            .map(|(prefix, _)| prefix.trim().to_string())
+    });
+
+    Ok(OtpAuthUri {
+        otp_type,
+        label,
+        issuer,
+        secret,
+        algorithm,
+        digits,
+        period,
+        counter,
+    })
+}
+
+/// Formats an `otpauth://` URI. The secret is written as unpadded Base32.
+pub(crate) fn format(uri: &OtpAuthUri) -> Result<String, String> {
+    let otp_type = normalize_type(&uri.otp_type)?;
+    let algorithm = normalize_algorithm(&uri.algorithm)?;
+
+    if uri.label.is_empty() {
+        return Err("Label must not be empty".to_string());
+    }
+
+    if uri.secret.is_empty() {
+        return Err("Secret must not be empty".to_string());
+    }
+
+    let mut output = format!(
+        "otpauth://{}/{}?secret={}",
+        otp_type,
+        utf8_percent_encode(&uri.label, LABEL),
+        base32::encode(&uri.secret)
+    );
+
+    if let Some(issuer) = uri.issuer.as_deref().filter(|i| !i.is_empty()) {
+        output.push_str("&issuer=");
+        output.push_str(&utf8_percent_encode(issuer, UNRESERVED).to_string());
+    }
+
+    output.push_str(&format!("&algorithm={}&digits={}", algorithm, uri.digits));
+
+    if otp_type == "hotp" {
+        output.push_str(&format!("&counter={}", uri.counter));
+    } else {
+        output.push_str(&format!("&period={}", uri.period));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_totp_uri() {
+        let parsed = parse(
+            "otpauth://totp/ACME%20Co:john.doe@email.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.otp_type, "totp");
+        assert_eq!(parsed.label, "ACME Co:john.doe@email.com");
+        assert_eq!(parsed.issuer.as_deref(), Some("ACME Co"));
+        assert_eq!(
+            base32::encode(&parsed.secret),
+            "HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ"
+        );
+        assert_eq!(parsed.algorithm, "SHA256");
+        assert_eq!(parsed.digits, 8);
+        assert_eq!(parsed.period, 60);
+    }
+
+    #[test]
+    fn test_parse_applies_defaults_and_label_issuer() {
+        let parsed = parse("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP").unwrap();
+
+        assert_eq!(parsed.issuer.as_deref(), Some("Example"));
+        assert_eq!(parsed.algorithm, "SHA1");
+        assert_eq!(parsed.digits, 6);
+        assert_eq!(parsed.period, 30);
+        assert_eq!(parsed.counter, 0);
+    }
+
+    #[test]
+    fn test_parse_hotp_requires_counter() {
+        assert!(parse("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP").is_err());
+        let parsed = parse("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP&counter=42").unwrap();
+        assert_eq!(parsed.counter, 42);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_uris() {
+        assert!(parse("https://example.com").is_err());
+        assert!(parse("otpauth://totp/alice").is_err(), "Missing secret");
+        assert!(parse("otpauth://sms/alice?secret=JBSWY3DPEHPK3PXP").is_err());
+        assert!(parse("otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&digits=4").is_err());
+        assert!(parse("otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&algorithm=MD5").is_err());
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let original = OtpAuthUri {
+            otp_type: "totp".to_string(),
+            label: "ACME Co:john.doe@email.com".to_string(),
+            issuer: Some("ACME Co".to_string()),
+            secret: b"12345678901234567890".to_vec(),
+            algorithm: "SHA1".to_string(),
+            digits: 6,
+            period: 30,
+            counter: 0,
+        };
+
+        let uri = format(&original).unwrap();
+        assert_eq!(
+            uri,
+            "otpauth://totp/ACME%20Co:john.doe%40email.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(parse(&uri).unwrap(), original);
+    }
+}
@@ -0,0 +1,353 @@
+//! `otpauth://` URI parsing and generation operations
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use super::algorithms::resolve_secret;
+use super::base32;
+use super::uri::{OtpAuthUri, format, parse};
+use crate::encoding::slice_from_raw;
+use crate::helpers::into_c_string;
+
+/// Parse an `otpauth://` URI into its components
+///
+/// On success every string output receives a newly allocated C string that must
+/// be freed with `free_string`. `out_issuer` receives null when the URI has no
+/// issuer. The secret is returned as unpadded uppercase Base32. Omitted
+/// parameters take the standard defaults (SHA1, 6 digits, 30 second period);
+/// `out_counter` is 0 for TOTP URIs.
+///
+/// Returns `true` on success, or `false` with the reason available from
+/// `get_last_error`.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `uri` is a valid null-terminated C string or null
+/// - Every output pointer is valid for writes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_otpauth_uri(
+    uri: *const c_char,
+    out_type: *mut *mut c_char,
+    out_label: *mut *mut c_char,
+    out_issuer: *mut *mut c_char,
+    out_secret: *mut *mut c_char,
+    out_algorithm: *mut *mut c_char,
+    out_digits: *mut u32,
+    out_period: *mut u32,
+    out_counter: *mut u64,
+) -> bool {
+    crate::error::clear_error();
+
+    if uri.is_null() {
+        crate::error::set_error("URI pointer is null".to_string());
+        return false;
+    }
+
+    if out_type.is_null()
+        || out_label.is_null()
+        || out_issuer.is_null()
+        || out_secret.is_null()
+        || out_algorithm.is_null()
+        || out_digits.is_null()
+        || out_period.is_null()
+        || out_counter.is_null()
+    {
+        crate::error::set_error("Output pointer is null".to_string());
+        return false;
+    }
+
+    let uri_str = match unsafe { CStr::from_ptr(uri).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            crate::error::set_error("Invalid UTF-8 in URI string".to_string());
+            return false;
+        }
+    };
+
+    let parsed = match parse(uri_str) {
+        Ok(p) => p,
+        Err(e) => {
+            crate::error::set_error(e);
+            return false;
+        }
+    };
+
+    let strings = [
+        into_c_string(&parsed.otp_type, "URI component"),
+        into_c_string(&parsed.label, "URI component"),
+        into_c_string(&base32::encode(&parsed.secret), "URI component"),
+        into_c_string(&parsed.algorithm, "URI component"),
+    ];
+    let issuer = match parsed.issuer.as_deref() {
+        Some(issuer) => into_c_string(issuer, "URI component"),
+        None => Ok(std::ptr::null_mut()),
+    };
+
+    if let Some(Err(e)) = strings
+        .iter()
+        .chain(std::iter::once(&issuer))
+        .find(|r| r.is_err())
+    {
+        crate::error::set_error(e.clone());
+        for ptr in strings.iter().chain(std::iter::once(&issuer)).flatten() {
+            unsafe { crate::memory::free_string(*ptr) };
+        }
+        return false;
+    }
+
+    let [otp_type, label, secret, algorithm] = strings.map(|r| r.unwrap_or_default());
+
+    // SAFETY: All output pointers have been validated as non-null
+    unsafe {
+        *out_type = otp_type;
+        *out_label = label;
+        *out_issuer = issuer.unwrap_or_default();
+        *out_secret = secret;
+        *out_algorithm = algorithm;
+        *out_digits = parsed.digits;
+        *out_period = parsed.period;
+        *out_counter = parsed.counter;
+    }
+
+    true
+}
+
+/// Generate an `otpauth://` URI
+///
+/// `otp_type` is `totp` or `hotp`. `issuer` may be null to omit the parameter.
+/// The secret is either raw key bytes or, when `secret_is_base32` is true, Base32
+/// text. `period` is written for TOTP URIs and `counter` for HOTP URIs.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `otp_type`, `label` and `algorithm` are valid null-terminated C strings or null
+/// - `issuer` is a valid null-terminated C string or null (optional)
+/// - `secret` is a valid pointer to at least `secret_length` bytes or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn generate_otpauth_uri(
+    otp_type: *const c_char,
+    label: *const c_char,
+    issuer: *const c_char,
+    secret: *const u8,
+    secret_length: usize,
+    secret_is_base32: bool,
+    algorithm: *const c_char,
+    digits: u32,
+    period: u32,
+    counter: u64,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    if otp_type.is_null() {
+        crate::error::set_error("OTP type pointer is null".to_string());
+        return std::ptr::null_mut();
+    }
+
+    if label.is_null() {
+        crate::error::set_error("Label pointer is null".to_string());
+        return std::ptr::null_mut();
+    }
+
+    if algorithm.is_null() {
+        crate::error::set_error("Algorithm pointer is null".to_string());
+        return std::ptr::null_mut();
+    }
+
+    let (otp_type_str, label_str, algorithm_str) = match (
+        unsafe { CStr::from_ptr(otp_type).to_str() },
+        unsafe { CStr::from_ptr(label).to_str() },
+        unsafe { CStr::from_ptr(algorithm).to_str() },
+    ) {
+        (Ok(t), Ok(l), Ok(a)) => (t, l, a),
+        _ => {
+            crate::error::set_error("Invalid UTF-8 in input string".to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let issuer_str = if issuer.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(issuer).to_str() } {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => {
+                crate::error::set_error("Invalid UTF-8 in issuer string".to_string());
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let result = unsafe { slice_from_raw(secret, secret_length, "Secret") }
+        .and_then(|secret| resolve_secret(secret, secret_is_base32))
+        .and_then(|secret| {
+            format(&OtpAuthUri {
+                otp_type: otp_type_str.to_string(),
+                label: label_str.to_string(),
+                issuer: issuer_str,
+                secret,
+                algorithm: algorithm_str.to_string(),
+                digits,
+                period,
+                counter,
+            })
+        });
+
+    let uri = match result {
+        Ok(uri) => uri,
+        Err(e) => {
+            crate::error::set_error(e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    match CString::new(uri) {
+        Ok(c_str) => {
+            crate::error::clear_error();
+            c_str.into_raw()
+        }
+        Err(_) => {
+            crate::error::set_error("Failed to create C string from URI".to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    #[test]
+    fn test_parse_otpauth_uri_outputs() {
+        let uri = CString::new(
+            "otpauth://totp/Example:alice@google.com?secret=JBSWY3DPEHPK3PXP&issuer=Example",
+        )
+        .unwrap();
+        let mut otp_type = std::ptr::null_mut();
+        let mut label = std::ptr::null_mut();
+        let mut issuer = std::ptr::null_mut();
+        let mut secret = std::ptr::null_mut();
+        let mut algorithm = std::ptr::null_mut();
+        let mut digits = 0u32;
+        let mut period = 0u32;
+        let mut counter = 99u64;
+
+        let ok = unsafe {
+            parse_otpauth_uri(
+                uri.as_ptr(),
+                &mut otp_type,
+                &mut label,
+                &mut issuer,
+                &mut secret,
+                &mut algorithm,
+                &mut digits,
+                &mut period,
+                &mut counter,
+            )
+        };
+
+        assert!(ok, "Valid URI should parse");
+        assert_eq!(take_string(otp_type).as_deref(), Some("totp"));
+        assert_eq!(
+            take_string(label).as_deref(),
+            Some("Example:alice@google.com")
+        );
+        assert_eq!(take_string(issuer).as_deref(), Some("Example"));
+        assert_eq!(take_string(secret).as_deref(), Some("JBSWY3DPEHPK3PXP"));
+        assert_eq!(take_string(algorithm).as_deref(), Some("SHA1"));
+        assert_eq!(digits, 6);
+        assert_eq!(period, 30);
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    fn test_parse_otpauth_uri_invalid_returns_false() {
+        let uri = CString::new("otpauth://totp/alice").unwrap();
+        let mut strings = [std::ptr::null_mut(); 5];
+        let mut digits = 0u32;
+        let mut period = 0u32;
+        let mut counter = 0u64;
+
+        let [t, l, i, s, a] = &mut strings;
+        let ok = unsafe {
+            parse_otpauth_uri(
+                uri.as_ptr(),
+                t,
+                l,
+                i,
+                s,
+                a,
+                &mut digits,
+                &mut period,
+                &mut counter,
+            )
+        };
+
+        assert!(!ok, "URI without a secret should fail");
+        assert!(strings.iter().all(|p| p.is_null()));
+    }
+
+    #[test]
+    fn test_generate_otpauth_uri_hotp() {
+        let otp_type = CString::new("HOTP").unwrap();
+        let label = CString::new("alice").unwrap();
+        let algorithm = CString::new("sha1").unwrap();
+        let secret = b"JBSWY3DPEHPK3PXP";
+
+        let uri = take_string(unsafe {
+            generate_otpauth_uri(
+                otp_type.as_ptr(),
+                label.as_ptr(),
+                std::ptr::null(),
+                secret.as_ptr(),
+                secret.len(),
+                true,
+                algorithm.as_ptr(),
+                6,
+                30,
+                7,
+            )
+        });
+
+        assert_eq!(
+            uri.as_deref(),
+            Some("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP&algorithm=SHA1&digits=6&counter=7")
+        );
+    }
+
+    #[test]
+    fn test_generate_otpauth_uri_invalid_type_returns_null() {
+        let otp_type = CString::new("sms").unwrap();
+        let label = CString::new("alice").unwrap();
+        let algorithm = CString::new("SHA1").unwrap();
+        let secret = b"secret";
+
+        let result = unsafe {
+            generate_otpauth_uri(
+                otp_type.as_ptr(),
+                label.as_ptr(),
+                std::ptr::null(),
+                secret.as_ptr(),
+                secret.len(),
+                false,
+                algorithm.as_ptr(),
+                6,
+                30,
+                0,
+            )
+        };
+
+        assert!(result.is_null(), "Unsupported OTP type should return null");
+    }
+}
@@ -0,0 +1,376 @@
+//! Random value generation from the operating system CSPRNG
+
+use std::collections::HashSet;
+
+/// RFC 4648 Base32 alphabet.
+const BASE32: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Bitcoin Base58 alphabet (no `0`, `O`, `I` or `l`).
+const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// RFC 4648 URL-safe Base64 alphabet.
+const BASE64URL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+
+/// Symbols used for passwords when the caller does not supply a set.
+pub(crate) const DEFAULT_SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.?/";
+
+/// Most distinct characters a custom alphabet may contain.
+const MAX_ALPHABET_LENGTH: usize = 4096;
+
+/// Largest number of bytes or characters a single call may generate (1 MiB).
+pub(crate) const MAX_LENGTH: usize = 1024 * 1024;
+
+/// Rejects requested lengths above [`MAX_LENGTH`] before anything is allocated.
+fn check_length(length: usize) -> Result<(), String> {
+    if length > MAX_LENGTH {
+        return Err(format!(
+            "Length {} exceeds the maximum of {}",
+            length, MAX_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// Fills `buffer` from the operating system CSPRNG.
+pub(crate) fn fill_random(buffer: &mut [u8]) -> Result<(), String> {
+    getrandom::getrandom(buffer).map_err(|e| format!("Failed to obtain random bytes: {}", e))
+}
+
+/// Returns `length` random bytes.
+pub(crate) fn random_bytes(length: usize) -> Result<Vec<u8>, String> {
+    check_length(length)?;
+    let mut bytes = vec![0u8; length];
+    fill_random(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Bytes fetched from the CSPRNG at a time when drawing many random values.
+const RANDOM_BLOCK_SIZE: usize = 1024;
+
+/// Random values drawn from the CSPRNG a block at a time, so generating long
+/// strings does not make one system call per character.
+struct RandomStream {
+    block: [u8; RANDOM_BLOCK_SIZE],
+    position: usize,
+}
+
+impl RandomStream {
+    fn new() -> Self {
+        Self {
+            block: [0u8; RANDOM_BLOCK_SIZE],
+            position: RANDOM_BLOCK_SIZE,
+        }
+    }
+
+    fn next_u64(&mut self) -> Result<u64, String> {
+        if self.position + 8 > RANDOM_BLOCK_SIZE {
+            fill_random(&mut self.block)?;
+            self.position = 0;
+        }
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&self.block[self.position..self.position + 8]);
+        self.position += 8;
+        Ok(u64::from_le_bytes(value))
+    }
+
+    /// Returns a uniformly distributed integer in `0..bound` using rejection sampling.
+    fn below(&mut self, bound: usize) -> Result<usize, String> {
+        let bound = bound as u64;
+        // Largest multiple of `bound` that fits, so every residue is equally likely
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64()?;
+            if value < zone {
+                return Ok((value % bound) as usize);
+            }
+        }
+    }
+}
+
+/// Resolves a named alphabet preset.
+pub(crate) fn named_alphabet(name: &str) -> Result<&'static str, String> {
+    match name.to_uppercase().as_str() {
+        "BASE32" => Ok(BASE32),
+        "BASE58" => Ok(BASE58),
+        "BASE64URL" => Ok(BASE64URL),
+        "HEX" => Ok("0123456789abcdef"),
+        "ALPHANUMERIC" => Ok("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+        "NUMERIC" => Ok(DIGITS),
+        _ => Err(format!(
+            "Unsupported alphabet: {}. Supported: BASE32, BASE58, BASE64URL, HEX, ALPHANUMERIC, NUMERIC",
+            name
+        )),
+    }
+}
+
+/// Splits an alphabet into characters, rejecting empty or oversized sets and
+/// duplicates (which would bias the output).
+fn alphabet_chars(alphabet: &str) -> Result<Vec<char>, String> {
+    let chars: Vec<char> = alphabet.chars().take(MAX_ALPHABET_LENGTH + 1).collect();
+    if chars.is_empty() {
+        return Err("Alphabet must not be empty".to_string());
+    }
+    if chars.len() > MAX_ALPHABET_LENGTH {
+        return Err(format!(
+            "Alphabet must have at most {} characters",
+            MAX_ALPHABET_LENGTH
+        ));
+    }
+    let mut seen = HashSet::with_capacity(chars.len());
+    if let Some(c) = chars.iter().find(|c| !seen.insert(**c)) {
+        return Err(format!("Alphabet contains duplicate character '{}'", c));
+    }
+    Ok(chars)
+}
+
+/// Returns `length` characters drawn uniformly from `alphabet`.
+pub(crate) fn random_string(length: usize, alphabet: &str) -> Result<String, String> {
+    check_length(length)?;
+    let chars = alphabet_chars(alphabet)?;
+    let mut random = RandomStream::new();
+    (0..length)
+        .map(|_| random.below(chars.len()).map(|i| chars[i]))
+        .collect()
+}
+
+/// Minimum number of characters required from each password class.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PasswordPolicy {
+    pub min_lowercase: usize,
+    pub min_uppercase: usize,
+    pub min_digits: usize,
+    pub min_symbols: usize,
+}
+
+/// Generates a password containing at least the required number of
+/// characters from each class. Classes with a minimum of zero are excluded;
+/// the remaining positions are drawn from the union of the included classes.
+pub(crate) fn random_password(
+    length: usize,
+    policy: PasswordPolicy,
+    symbols: &str,
+) -> Result<String, String> {
+    let classes = [
+        (policy.min_lowercase, LOWERCASE),
+        (policy.min_uppercase, UPPERCASE),
+        (policy.min_digits, DIGITS),
+        (policy.min_symbols, symbols),
+    ];
+
+    check_length(length)?;
+    let required = classes
+        .iter()
+        .try_fold(0usize, |sum, (minimum, _)| sum.checked_add(*minimum))
+        .ok_or_else(|| "Sum of class minimums overflows".to_string())?;
+    if required == 0 {
+        return Err("At least one character class must have a minimum greater than 0".to_string());
+    }
+    if required > length {
+        return Err(format!(
+            "Password length {} is shorter than the sum of class minimums ({})",
+            length, required
+        ));
+    }
+
+    let mut random = RandomStream::new();
+    let mut pool = String::new();
+    let mut password = Vec::with_capacity(length);
+    for (minimum, class) in classes.iter().filter(|(minimum, _)| *minimum > 0) {
+        let chars = alphabet_chars(class)?;
+        for _ in 0..*minimum {
+            password.push(chars[random.below(chars.len())?]);
+        }
+        pool.push_str(class);
+    }
+
+    let pool = alphabet_chars(&pool)
+        .map_err(|_| "Symbol set must not overlap letters or digits".to_string())?;
+    while password.len() < length {
+        password.push(pool[random.below(pool.len())?]);
+    }
+
+    // Fisher-Yates shuffle so the required characters are not at the front
+    for i in (1..password.len()).rev() {
+        let j = random.below(i + 1)?;
+        password.swap(i, j);
+    }
+
+    Ok(password.into_iter().collect())
+}
+
+/// Generates a random (version 4, RFC 9562) UUID in lowercase hyphenated form.
+pub(crate) fn random_uuid_v4() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    fill_random(&mut bytes)?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_bytes_length_and_variation() {
+        let a = random_bytes(32).unwrap();
+        let b = random_bytes(32).unwrap();
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+        assert!(random_bytes(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_random_below_stays_in_range() {
+        let mut random = RandomStream::new();
+        // Enough draws to refill the block several times
+        for bound in [1, 2, 3, 58, 1000] {
+            for _ in 0..200 {
+                assert!(random.below(bound).unwrap() < bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_string_uses_alphabet() {
+        let value = random_string(200, BASE58).unwrap();
+        assert_eq!(value.chars().count(), 200);
+        assert!(value.chars().all(|c| BASE58.contains(c)));
+    }
+
+    #[test]
+    fn test_random_string_unicode_alphabet() {
+        let value = random_string(10, "αβγ").unwrap();
+        assert_eq!(value.chars().count(), 10);
+        assert!(value.chars().all(|c| "αβγ".contains(c)));
+    }
+
+    #[test]
+    fn test_random_string_rejects_bad_alphabets() {
+        assert!(random_string(5, "").is_err());
+        assert!(random_string(5, "abca").is_err());
+    }
+
+    #[test]
+    fn test_alphabet_length_limits() {
+        let largest: String = (0..MAX_ALPHABET_LENGTH as u32)
+            .map(|i| char::from_u32(0x4e00 + i).unwrap())
+            .collect();
+        assert_eq!(random_string(64, &largest).unwrap().chars().count(), 64);
+
+        let duplicated = format!("{}\u{4e00}", &largest[..largest.len() - 3]);
+        let err = random_string(8, &duplicated).unwrap_err();
+        assert!(err.contains("duplicate"), "{}", err);
+
+        let oversized = format!("{}!", largest);
+        let err = random_string(8, &oversized).unwrap_err();
+        assert!(err.contains("at most"), "{}", err);
+    }
+
+    #[test]
+    fn test_named_alphabets() {
+        assert_eq!(named_alphabet("base32").unwrap(), BASE32);
+        assert_eq!(named_alphabet("Base64Url").unwrap().len(), 64);
+        assert!(named_alphabet("base36").is_err());
+    }
+
+    #[test]
+    fn test_random_password_meets_policy() {
+        let policy = PasswordPolicy {
+            min_lowercase: 2,
+            min_uppercase: 2,
+            min_digits: 3,
+            min_symbols: 1,
+        };
+        for _ in 0..20 {
+            let password = random_password(12, policy, DEFAULT_SYMBOLS).unwrap();
+            assert_eq!(password.len(), 12);
+            assert!(password.chars().filter(char::is_ascii_lowercase).count() >= 2);
+            assert!(password.chars().filter(char::is_ascii_uppercase).count() >= 2);
+            assert!(password.chars().filter(char::is_ascii_digit).count() >= 3);
+            assert!(password.chars().any(|c| DEFAULT_SYMBOLS.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_random_password_excludes_disabled_classes() {
+        let policy = PasswordPolicy {
+            min_digits: 1,
+            ..Default::default()
+        };
+        let password = random_password(16, policy, DEFAULT_SYMBOLS).unwrap();
+        assert!(password.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_random_password_invalid_policies() {
+        assert!(random_password(8, PasswordPolicy::default(), DEFAULT_SYMBOLS).is_err());
+
+        let policy = PasswordPolicy {
+            min_lowercase: 5,
+            min_digits: 5,
+            ..Default::default()
+        };
+        assert!(random_password(8, policy, DEFAULT_SYMBOLS).is_err());
+
+        let policy = PasswordPolicy {
+            min_lowercase: 1,
+            min_symbols: 1,
+            ..Default::default()
+        };
+        assert!(random_password(8, policy, "a!").is_err());
+    }
+
+    #[test]
+    fn test_oversized_lengths_rejected() {
+        assert!(random_bytes(MAX_LENGTH + 1).is_err());
+        assert!(random_string(usize::MAX, BASE58).is_err());
+
+        let policy = PasswordPolicy {
+            min_digits: 1,
+            ..Default::default()
+        };
+        assert!(random_password(usize::MAX, policy, DEFAULT_SYMBOLS).is_err());
+    }
+
+    #[test]
+    fn test_random_password_minimums_checked_before_generating() {
+        let policy = PasswordPolicy {
+            min_lowercase: usize::MAX,
+            min_uppercase: 1,
+            ..Default::default()
+        };
+        let err = random_password(16, policy, DEFAULT_SYMBOLS).unwrap_err();
+        assert!(err.contains("overflows"));
+
+        let policy = PasswordPolicy {
+            min_lowercase: usize::MAX / 2,
+            ..Default::default()
+        };
+        let err = random_password(16, policy, DEFAULT_SYMBOLS).unwrap_err();
+        assert!(err.contains("shorter than"));
+    }
+
+    #[test]
+    fn test_random_uuid_v4_format() {
+        let uuid = random_uuid_v4().unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_eq!(uuid.matches('-').count(), 4);
+        assert_ne!(uuid, random_uuid_v4().unwrap());
+    }
+}
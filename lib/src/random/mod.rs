@@ -0,0 +1,10 @@
+//! Cryptographically secure random generation (bytes, strings, passwords, UUIDs)
+
+mod generate;
+mod random_ops;
+
+// Re-export public FFI functions
+pub use random_ops::{random_bytes, random_password, random_string, random_uuid_v4};
+
+// Re-export the CSPRNG source for use by other modules
+pub(crate) use generate::fill_random;
@@ -0,0 +1,208 @@
+//! Secure random generation operations
+
+use std::os::raw::c_char;
+
+use super::generate::{self, DEFAULT_SYMBOLS, PasswordPolicy};
+use crate::helpers::{finish_string, read_optional_str};
+
+/// Generate cryptographically secure random bytes
+///
+/// `length` may be at most 1 MiB (1048576 bytes).
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `out_length` is a valid pointer to a usize or null (optional)
+/// - The returned pointer must be freed using `free_bytes`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_bytes(length: usize, out_length: *mut usize) -> *mut u8 {
+    crate::error::clear_error();
+
+    match generate::random_bytes(length) {
+        Ok(bytes) => {
+            if !out_length.is_null() {
+                unsafe {
+                    *out_length = bytes.len();
+                }
+            }
+            crate::memory::allocate_byte_array(bytes)
+        }
+        Err(e) => {
+            crate::error::set_error(e);
+            crate::encoding::set_output_length_zero(out_length);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Generate a random string of `length` characters over an alphabet
+///
+/// Exactly one of `alphabet_name` (a preset) or `custom_alphabet` (the literal
+/// characters to draw from, at most 4096 and without duplicates) must be
+/// non-null. Every character is chosen uniformly using rejection sampling.
+/// `length` may be at most 1048576 characters.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `alphabet_name` and `custom_alphabet` are valid null-terminated C strings or null
+/// - The returned pointer must be freed using `free_string`
+///
+/// # Supported Alphabets
+/// - BASE32 (RFC 4648, `A-Z2-7`)
+/// - BASE58 (Bitcoin)
+/// - BASE64URL (RFC 4648 URL-safe)
+/// - HEX (lowercase)
+/// - ALPHANUMERIC
+/// - NUMERIC
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_string(
+    length: usize,
+    alphabet_name: *const c_char,
+    custom_alphabet: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_optional_str(alphabet_name, "Alphabet name").and_then(|name| {
+        let custom = read_optional_str(custom_alphabet, "Custom alphabet")?;
+        let alphabet = match (name, custom) {
+            (Some(name), None) => generate::named_alphabet(name)?,
+            (None, Some(custom)) => custom,
+            _ => {
+                return Err(
+                    "Exactly one of alphabet name or custom alphabet must be provided".to_string(),
+                );
+            }
+        };
+        generate::random_string(length, alphabet)
+    });
+
+    finish_string(result, "random result")
+}
+
+/// Generate a random password with per-class minimum character counts
+///
+/// A class whose minimum is 0 is left out of the password entirely; the
+/// positions beyond the minimums are filled from all included classes and the
+/// result is shuffled. `symbols` overrides the default symbol set
+/// `!@#$%^&*()-_=+[]{};:,.?/` when non-null. `length` may be at most 1048576
+/// characters and must cover the sum of the minimums, which is checked before
+/// anything is generated.
+///
+/// # Safety
+/// This function is unsafe because it dereferences raw pointers.
+/// The caller must ensure that:
+/// - `symbols` is a valid null-terminated C string or null
+/// - The returned pointer must be freed using `free_string`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_password(
+    length: usize,
+    min_lowercase: usize,
+    min_uppercase: usize,
+    min_digits: usize,
+    min_symbols: usize,
+    symbols: *const c_char,
+) -> *mut c_char {
+    crate::error::clear_error();
+
+    let result = read_optional_str(symbols, "Symbols").and_then(|symbols| {
+        let policy = PasswordPolicy {
+            min_lowercase,
+            min_uppercase,
+            min_digits,
+            min_symbols,
+        };
+        generate::random_password(length, policy, symbols.unwrap_or(DEFAULT_SYMBOLS))
+    });
+
+    finish_string(result, "random result")
+}
+
+/// Generate a random version 4 UUID in lowercase hyphenated form
+///
+/// # Safety
+/// This function is safe to call from any thread. Returns a newly allocated string
+/// that must be freed by the caller using `free_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn random_uuid_v4() -> *mut c_char {
+    crate::error::clear_error();
+
+    finish_string(generate::random_uuid_v4(), "random result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn take_string(ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let s = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { crate::memory::free_string(ptr) };
+        Some(s)
+    }
+
+    #[test]
+    fn test_random_bytes_export() {
+        let mut len = 0usize;
+        let ptr = unsafe { random_bytes(24, &mut len) };
+        assert!(!ptr.is_null());
+        assert_eq!(len, 24);
+        unsafe { crate::memory::free_bytes(ptr) };
+    }
+
+    #[test]
+    fn test_random_bytes_oversized_length_sets_error() {
+        let mut len = 7usize;
+        let ptr = unsafe { random_bytes(usize::MAX, &mut len) };
+        assert!(ptr.is_null());
+        assert_eq!(len, 0);
+        let error = unsafe { crate::error::get_last_error() };
+        assert!(!error.is_null());
+        unsafe { crate::memory::free_string(error) };
+    }
+
+    #[test]
+    fn test_random_string_named_alphabet() {
+        let name = CString::new("HEX").unwrap();
+        let value =
+            take_string(unsafe { random_string(40, name.as_ptr(), std::ptr::null()) }).unwrap();
+        assert_eq!(value.len(), 40);
+        assert!(value.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_random_string_custom_alphabet() {
+        let custom = CString::new("xyz").unwrap();
+        let value =
+            take_string(unsafe { random_string(8, std::ptr::null(), custom.as_ptr()) }).unwrap();
+        assert!(value.chars().all(|c| "xyz".contains(c)));
+    }
+
+    #[test]
+    fn test_random_string_requires_exactly_one_alphabet() {
+        assert!(unsafe { random_string(8, std::ptr::null(), std::ptr::null()) }.is_null());
+
+        let name = CString::new("HEX").unwrap();
+        let custom = CString::new("ab").unwrap();
+        assert!(unsafe { random_string(8, name.as_ptr(), custom.as_ptr()) }.is_null());
+    }
+
+    #[test]
+    fn test_random_password_custom_symbols() {
+        let symbols = CString::new("#").unwrap();
+        let value =
+            take_string(unsafe { random_password(10, 1, 1, 1, 2, symbols.as_ptr()) }).unwrap();
+        assert_eq!(value.len(), 10);
+        assert!(value.matches('#').count() >= 2);
+        assert!(value.chars().all(|c| c.is_ascii_alphanumeric() || c == '#'));
+    }
+
+    #[test]
+    fn test_random_uuid_v4_export() {
+        let value = take_string(unsafe { random_uuid_v4() }).unwrap();
+        assert_eq!(value.len(), 36);
+    }
+}
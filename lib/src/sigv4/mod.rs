@@ -0,0 +1,7 @@
+//! AWS Signature Version 4 request signing (headers and presigned URLs)
+
+mod algorithms;
+mod sigv4_ops;
+
+// Re-export public FFI functions
+pub use sigv4_ops::{aws_sigv4_presign_url, aws_sigv4_sign};
@@ -189,6 +189,19 @@ pub unsafe extern "C" fn from_unix_time_ffi(
     true
 }
 
+/// Returns `timestamp` as Unix seconds, substituting the current system time for
+/// negative values (the convention used by exports that take an optional clock).
+pub(crate) fn resolve_unix_time(timestamp: i64) -> Result<u64, String> {
+    if timestamp >= 0 {
+        return Ok(timestamp as u64);
+    }
+
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|_| "System clock is before the Unix epoch".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
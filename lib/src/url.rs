@@ -1,8 +1,17 @@
 //! URL encoding and decoding functions
 
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
+/// Everything except the RFC 3986 unreserved characters (`A-Z a-z 0-9 - . _ ~`),
+/// for encoders that must leave only those characters as-is.
+pub(crate) const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 /// Encode a string for use in URLs using percent-encoding
 ///
 /// # Safety